@@ -0,0 +1,342 @@
+use std::{fs, path::Path, time::SystemTime};
+
+use bevy::prelude::*;
+use space_prefab::{
+    binary::PrefabFormat,
+    save::{SaveConfig, SaveState},
+};
+use space_shared::*;
+
+use crate::EditorLoader;
+
+#[cfg(feature = "persistence_editor")]
+use space_persistence::AppPersistenceExt;
+
+/// Autosave configuration
+#[derive(Resource, Reflect, Clone)]
+#[reflect(Resource, Default)]
+pub struct AutosaveSettings {
+    pub enabled: bool,
+    /// Seconds between autosaves
+    pub interval: f32,
+    /// Recovery file. Must be inside assets folder to be loaded back
+    pub path: String,
+}
+
+impl Default for AutosaveSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval: 120.,
+            path: "assets/.recovery/autosave.scn.ron".to_string(),
+        }
+    }
+}
+
+impl AutosaveSettings {
+    /// File with path of scene, which was open during autosave
+    fn source_path(&self) -> String {
+        format!("{}.source", self.path)
+    }
+}
+
+/// Recovery file found on startup
+#[derive(Clone, Debug)]
+pub struct RecoveryOffer {
+    /// Recovery file path relative to assets folder
+    pub recovery: String,
+    /// Scene, which was open when recovery file was written
+    pub scene: Option<String>,
+}
+
+/// Runtime state of autosave
+#[derive(Resource, Default)]
+pub struct AutosaveState {
+    elapsed: f32,
+    /// Save config of user, replaced for autosave
+    stashed_config: Option<SaveConfig>,
+    /// Scene file open in editor, relative to assets folder
+    pub scene: Option<String>,
+    /// Not yet answered offer to restore recovery file
+    pub offer: Option<RecoveryOffer>,
+}
+
+/// Answer to [`RecoveryOffer`]
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RecoveryAnswer {
+    Restore,
+    Discard,
+}
+
+/// Scene path relative to assets folder
+pub(crate) fn asset_relative(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    path.rsplit_once("assets/")
+        .map_or_else(|| path.clone(), |(_, path)| path.to_string())
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// Find recovery file, which is newer than scene it was saved for
+pub fn find_recovery(settings: &AutosaveSettings) -> Option<RecoveryOffer> {
+    let recovery_time = modified(Path::new(&settings.path))?;
+    let scene = fs::read_to_string(settings.source_path())
+        .ok()
+        .map(|scene| scene.trim().to_string())
+        .filter(|scene| !scene.is_empty());
+
+    if let Some(scene) = &scene {
+        // Scene is relative to the assets folder, which contains recovery file
+        let recovery = settings.path.replace('\\', "/");
+        let assets = recovery
+            .strip_suffix(&asset_relative(&recovery))
+            .unwrap_or("assets/");
+        let scene_path = format!("{assets}{scene}");
+        if let Some(scene_time) = modified(Path::new(&scene_path)) {
+            let same = fs::read(&scene_path).ok() == fs::read(&settings.path).ok();
+            if scene_time >= recovery_time || same {
+                return None;
+            }
+        }
+    }
+
+    Some(RecoveryOffer {
+        recovery: asset_relative(&settings.path),
+        scene,
+    })
+}
+
+fn check_recovery(settings: Res<AutosaveSettings>, mut state: ResMut<AutosaveState>) {
+    state.offer = find_recovery(&settings);
+    if let Some(offer) = &state.offer {
+        info!("Found recovery file {}", offer.recovery);
+    }
+}
+
+fn answer_recovery(
+    mut answers: EventReader<RecoveryAnswer>,
+    mut state: ResMut<AutosaveState>,
+    settings: Res<AutosaveSettings>,
+    mut loader: ResMut<EditorLoader>,
+    assets: Res<AssetServer>,
+) {
+    for answer in answers.read() {
+        let Some(offer) = state.offer.take() else {
+            continue;
+        };
+        match answer {
+            RecoveryAnswer::Restore => {
+                loader.scene = Some(assets.load(offer.recovery));
+                // Original scene is still open scene, recovery file is only its copy
+                loader.path.clone_from(&offer.scene);
+                state.scene = offer.scene;
+            }
+            RecoveryAnswer::Discard => {
+                let _ = fs::remove_file(&settings.path);
+                let _ = fs::remove_file(settings.source_path());
+            }
+        }
+    }
+}
+
+/// Remember scene file open in editor
+fn track_open_scene(
+    mut events: EventReader<EditorEvent>,
+    mut state: ResMut<AutosaveState>,
+    settings: Res<AutosaveSettings>,
+) {
+    let recovery = asset_relative(&settings.path);
+    for event in events.read() {
+        if let EditorEvent::Load(EditorPrefabPath::File(path))
+        | EditorEvent::Save(EditorPrefabPath::File(path)) = event
+        {
+            let path = asset_relative(path);
+            if path != recovery {
+                state.scene = Some(path);
+            }
+        }
+    }
+}
+
+fn autosave_tick(
+    time: Res<Time>,
+    settings: Res<AutosaveSettings>,
+    mut state: ResMut<AutosaveState>,
+    mut save_config: ResMut<SaveConfig>,
+    mut save_state: ResMut<NextState<SaveState>>,
+    current_save_state: Res<State<SaveState>>,
+    prefabs: Query<(), With<PrefabMarker>>,
+) {
+    if !settings.enabled || *current_save_state.get() != SaveState::Idle {
+        return;
+    }
+    // Do not overwrite recovery file before user answered
+    if state.offer.is_some() {
+        return;
+    }
+    state.elapsed += time.delta_seconds();
+    if state.elapsed < settings.interval.max(1.) {
+        return;
+    }
+    state.elapsed = 0.;
+    if prefabs.is_empty() {
+        return;
+    }
+
+    if let Some(dir) = Path::new(&settings.path).parent() {
+        if let Err(e) = fs::create_dir_all(dir) {
+            error!("Failed to create autosave folder: {e}");
+            return;
+        }
+    }
+    let source = state.scene.clone().unwrap_or_default();
+    if let Err(e) = fs::write(settings.source_path(), source) {
+        error!("Failed to write autosave info: {e}");
+    }
+
+    state.stashed_config = Some(save_config.clone());
+    save_config.path = Some(EditorPrefabPath::File(settings.path.clone()));
+    save_config.backups = 0;
+    // Recovery is looked up and loaded back as ron file
+    save_config.format = PrefabFormat::Ron;
+    save_state.set(SaveState::Save);
+    info!("Autosave to {}", settings.path);
+}
+
+/// Give user save config back after autosave is done
+fn restore_save_config(mut state: ResMut<AutosaveState>, mut save_config: ResMut<SaveConfig>) {
+    if let Some(config) = state.stashed_config.take() {
+        *save_config = config;
+    }
+}
+
+/// Periodically save scene to recovery file and offer to restore it after crash
+pub struct AutosavePlugin;
+
+impl Plugin for AutosavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AutosaveSettings>()
+            .register_type::<AutosaveSettings>()
+            .init_resource::<AutosaveState>()
+            .add_event::<RecoveryAnswer>();
+
+        #[cfg(feature = "persistence_editor")]
+        app.persistence_resource::<AutosaveSettings>();
+
+        app.add_systems(PostStartup, check_recovery);
+        app.add_systems(
+            Update,
+            (
+                track_open_scene,
+                answer_recovery,
+                autosave_tick.run_if(in_state(EditorState::Editor)),
+            )
+                .chain(),
+        );
+        app.add_systems(OnEnter(SaveState::Idle), restore_save_config);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recovery_newer_than_scene() {
+        let dir = std::env::temp_dir().join(format!("space_autosave_{}", std::process::id()));
+        let scenes = dir.join("assets/scenes");
+        fs::create_dir_all(&scenes).unwrap();
+        let settings = AutosaveSettings {
+            path: dir
+                .join("assets/.recovery/autosave.scn.ron")
+                .to_string_lossy()
+                .to_string(),
+            ..default()
+        };
+        fs::create_dir_all(dir.join("assets/.recovery")).unwrap();
+
+        assert!(find_recovery(&settings).is_none());
+
+        fs::write(scenes.join("level.scn.ron"), "old").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        fs::write(&settings.path, "new").unwrap();
+        fs::write(settings.source_path(), "scenes/level.scn.ron").unwrap();
+        let offer = find_recovery(&settings).unwrap();
+        assert_eq!(offer.recovery, ".recovery/autosave.scn.ron");
+        assert_eq!(offer.scene.as_deref(), Some("scenes/level.scn.ron"));
+
+        // Scene saved after autosave
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        fs::write(scenes.join("level.scn.ron"), "saved").unwrap();
+        assert!(find_recovery(&settings).is_none());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn autosave_ignores_binary_format() {
+        use space_prefab::{editor_registry::*, save::SavePrefabPlugin};
+
+        let dir = std::env::temp_dir().join(format!("space_autosave_bin_{}", std::process::id()));
+        let settings = AutosaveSettings {
+            path: dir
+                .join("assets/.recovery/autosave.scn.ron")
+                .to_string_lossy()
+                .to_string(),
+            interval: 1.,
+            ..default()
+        };
+
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            bevy::scene::ScenePlugin,
+            EditorRegistryPlugin {},
+            SavePrefabPlugin,
+        ))
+        .editor_registry::<PrefabMarker>()
+        .insert_resource(settings.clone())
+        .insert_resource(SaveConfig {
+            format: PrefabFormat::Binary,
+            ..default()
+        })
+        .insert_resource(AutosaveState {
+            elapsed: 1.,
+            ..default()
+        })
+        .add_systems(Update, autosave_tick)
+        .add_systems(OnEnter(SaveState::Idle), restore_save_config);
+        app.world.spawn(PrefabMarker);
+
+        // Autosave tick, then save on state transition
+        app.update();
+        app.update();
+        app.update();
+        std::thread::sleep(std::time::Duration::from_secs_f32(0.2));
+
+        assert!(!Path::new(&space_prefab::binary::binary_path(&settings.path)).exists());
+        let offer = find_recovery(&settings).unwrap();
+        assert_eq!(offer.recovery, ".recovery/autosave.scn.ron");
+        assert_eq!(
+            app.world.resource::<SaveConfig>().format,
+            PrefabFormat::Binary
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn relative_scene_path() {
+        assert_eq!(
+            asset_relative("./assets/scenes/level.scn.ron"),
+            "scenes/level.scn.ron"
+        );
+        assert_eq!(
+            asset_relative("scenes/level.scn.ron"),
+            "scenes/level.scn.ron"
+        );
+    }
+}
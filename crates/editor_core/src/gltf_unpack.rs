@@ -1,13 +1,24 @@
 use bevy::{
-    asset::{AssetPath, LoadState},
-    ecs::system::CommandQueue,
-    gltf::{Gltf, GltfMesh, GltfNode},
+    asset::LoadState,
+    core_pipeline::{core_3d::graph::Core3d, tonemapping::DebandDither},
+    ecs::{entity::EntityHashMap, system::CommandQueue},
+    gltf::Gltf,
     prelude::*,
-    utils::HashMap,
+    render::{camera::CameraRenderGraph, mesh::skinning::SkinnedMesh},
+    utils::{HashMap, HashSet, Uuid},
 };
 
-use space_prefab::component::{AssetMaterial, AssetMesh, MaterialPrefab};
-use space_shared::PrefabMarker;
+use space_prefab::{
+    component::{
+        AnimationPlayerPrefab, AssetMaterial, AssetMesh, MaterialPrefab, PlaymodeCamera,
+        PlaymodeLight, SkinnedMeshPrefab,
+    },
+    editor_registry::EditorRegistryExt,
+};
+use space_shared::{
+    toast::{ToastKind, ToastMessage},
+    LightAreaToggle, PrefabMarker,
+};
 
 use super::{BackgroundTask, BackgroundTaskStorage};
 
@@ -15,22 +26,50 @@ use super::{BackgroundTask, BackgroundTaskStorage};
 /// Event to handle GLTF path
 pub struct EditorUnpackGltf {
     pub path: String,
+    /// Unpack materials into editable [`MaterialPrefab`] instead of [`AssetMaterial`] links to the file
+    pub extract_materials: bool,
+}
+
+/// Event to update entities unpacked from GLTF file after the file is changed.
+/// Edits of unpacked entities, which are not changed in the file, are kept
+#[derive(Event)]
+pub struct EditorReimportGltf {
+    pub path: String,
 }
 
 #[derive(Event, Clone)]
-struct GltfLoaded(Handle<Gltf>);
+struct GltfLoaded {
+    handle: Handle<Gltf>,
+    reimport: bool,
+    extract_materials: bool,
+    /// File is reloaded from disk, loaded asset still has old data
+    reloading: bool,
+}
 
 pub struct UnpackGltfPlugin;
 
 impl Plugin for UnpackGltfPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<EditorUnpackGltf>();
+        app.add_event::<EditorReimportGltf>();
         app.add_event::<GltfLoaded>();
-        app.add_systems(PreUpdate, (unpack_gltf_event, queue_push, unpack_gltf));
+        app.add_systems(
+            PreUpdate,
+            (
+                unpack_gltf_event,
+                reimport_gltf_event,
+                queue_push,
+                unpack_gltf,
+            )
+                .chain(),
+        );
 
         app.init_resource::<GltfSceneQueue>();
 
         app.register_type::<GltfHolder>();
+        app.editor_registry::<GltfSource>();
+        app.editor_registry::<GltfNodeRemoved>();
+        app.register_type::<Vec<String>>();
     }
 }
 
@@ -38,8 +77,29 @@ impl Plugin for UnpackGltfPlugin {
 #[reflect(Component)]
 struct GltfHolder(Handle<Gltf>);
 
+/// GLTF node of unpacked entity and values imported from it.
+/// Imported values are compared with the file on re-import to find changes in the file
+#[derive(Component, Reflect, Clone, Default, Debug, PartialEq)]
+#[reflect(Component, Default)]
+pub struct GltfSource {
+    /// GLTF file relative to assets folder
+    pub gltf: String,
+    /// Names of nodes from scene root to this node. Unnamed nodes are named by index, like `#2`
+    pub node: Vec<String>,
+    /// Id shared by entities of one unpack, so copies of the same file are re-imported separately
+    pub unpack: Uuid,
+    pub transform: Transform,
+    pub mesh: String,
+    pub material: String,
+}
+
+/// Marker of unpacked entity, whose node was removed from GLTF file
+#[derive(Component, Reflect, Clone, Default)]
+#[reflect(Component, Default)]
+pub struct GltfNodeRemoved;
+
 #[derive(Resource, Default)]
-struct GltfSceneQueue(Vec<Handle<Gltf>>);
+struct GltfSceneQueue(Vec<GltfLoaded>);
 
 fn unpack_gltf_event(
     mut events: EventReader<EditorUnpackGltf>,
@@ -53,27 +113,77 @@ fn unpack_gltf_event(
             event.path.clone(),
             handle.clone().untyped(),
         ));
-        queue.0.push(handle);
+        queue.0.push(GltfLoaded {
+            handle,
+            reimport: false,
+            extract_materials: event.extract_materials,
+            reloading: false,
+        });
     }
     events.clear();
 }
 
+fn reimport_gltf_event(
+    mut events: EventReader<EditorReimportGltf>,
+    assets: Res<AssetServer>,
+    mut queue: ResMut<GltfSceneQueue>,
+    mut background_tasks: ResMut<BackgroundTaskStorage>,
+) {
+    for event in events.read() {
+        let handle = assets.load(event.path.clone());
+        // File is changed since last load
+        let reloading = assets.is_loaded_with_dependencies(&handle);
+        if reloading {
+            assets.reload(event.path.clone());
+        }
+        background_tasks.tasks.push(BackgroundTask::AssetLoading(
+            event.path.clone(),
+            handle.clone().untyped(),
+        ));
+        queue.0.push(GltfLoaded {
+            handle,
+            reimport: true,
+            extract_materials: false,
+            reloading,
+        });
+    }
+}
+
 // separated from unpack_gltf for reduce arguments count and ordered unpack
 fn queue_push(
     mut queue: ResMut<GltfSceneQueue>,
     mut events: EventWriter<GltfLoaded>,
+    mut asset_events: EventReader<AssetEvent<Gltf>>,
     assets: Res<AssetServer>,
 ) {
-    if !queue.0.is_empty() && assets.get_load_state(&queue.0[0]) == Some(LoadState::Loaded) {
-        events.send(GltfLoaded(queue.0.remove(0)));
+    // Reload is finished, when asset is loaded again with all its dependencies
+    for event in asset_events.read() {
+        if let AssetEvent::LoadedWithDependencies { id } = event {
+            for loaded in queue.0.iter_mut() {
+                if loaded.handle.id() == *id {
+                    loaded.reloading = false;
+                }
+            }
+        }
+    }
+    if !queue.0.is_empty()
+        && !queue.0[0].reloading
+        && assets.get_load_state(&queue.0[0].handle) == Some(LoadState::Loaded)
+    {
+        events.send(queue.0.remove(0));
     }
 }
 
+/// Data of gltf file to spawn prefab entities
+#[derive(Clone, Copy)]
 struct UnpackContext<'a> {
-    material_map: &'a HashMap<Handle<StandardMaterial>, usize>,
-    mesh_map: &'a HashMap<Handle<GltfMesh>, usize>,
-    gltf_meshs: &'a Assets<GltfMesh>,
-    gltf_path: &'a AssetPath<'a>,
+    scene: &'a World,
+    gltf_path: &'a str,
+    /// Id of spawned or re-imported unpack
+    unpack: Uuid,
+    clips: &'a [(String, &'a AnimationClip)],
+    /// Materials to unpack into [`MaterialPrefab`], if materials are extracted
+    materials: Option<&'a Assets<StandardMaterial>>,
 }
 
 fn unpack_gltf(world: &mut World) {
@@ -85,160 +195,768 @@ fn unpack_gltf(world: &mut World) {
         loaded
     };
 
+    let mut sources = world.query::<(Entity, &GltfSource)>();
     let mut command_queue = CommandQueue::default();
-    for gltf in loaded_scenes.iter() {
-        let handle: Handle<Gltf> = gltf.0.clone();
-        let gltf_path = if let Some(path) = handle.path() {
-            path.clone()
-        } else {
+    let mut reports = vec![];
+    for loaded in loaded_scenes.iter() {
+        let Some(gltf_path) = loaded.handle.path().map(ToString::to_string) else {
             continue;
         };
         info!("Path: {:?}", &gltf_path);
 
-        let Some(gltf) = world.resource::<Assets<Gltf>>().get(&gltf.0) else {
+        let Some(gltf) = world.resource::<Assets<Gltf>>().get(&loaded.handle) else {
             continue;
         };
 
         let mut commands = Commands::new(&mut command_queue, world);
 
-        let gltf_nodes = world.resource::<Assets<GltfNode>>();
-        let gltf_meshs = world.resource::<Assets<GltfMesh>>();
         let scenes = world.resource::<Assets<Scene>>();
-
-        let mut mesh_map = HashMap::new();
-        for idx in 0..gltf.meshes.len() {
-            mesh_map.insert(gltf.meshes[idx].clone(), idx);
-        }
-
-        let mut material_map = HashMap::new();
-        for idx in 0..gltf.materials.len() {
-            info!("Material: {:?}", &gltf.materials[idx]);
-            material_map.insert(gltf.materials[idx].clone(), idx);
-        }
-
-        for idx in 0..gltf.scenes.len() {
-            let Some(scene) = scenes.get(&gltf.scenes[idx]) else {
+        let animations = world.resource::<Assets<AnimationClip>>();
+        let materials = loaded
+            .extract_materials
+            .then(|| world.resource::<Assets<StandardMaterial>>());
+
+        let clips = gltf
+            .animations
+            .iter()
+            .filter_map(|handle| Some((handle.path()?.to_string(), animations.get(handle)?)))
+            .collect::<Vec<_>>();
+
+        // Only first scene is re-imported, because node paths are not unique between scenes
+        let scene_handles = if loaded.reimport {
+            &gltf.scenes[..gltf.scenes.len().min(1)]
+        } else {
+            &gltf.scenes[..]
+        };
+        for handle in scene_handles {
+            let Some(scene) = scenes.get(handle) else {
                 continue;
             };
-
-            //find roots nodes
-            let mut roots = vec![];
-            for e in scene.world.iter_entities() {
-                if !e.contains::<Parent>() && e.contains::<Children>() {
-                    let children = e.get::<Children>().unwrap();
-                    for child in children.iter() {
-                        if let Some(name) = scene.world.entity(*child).get::<Name>() {
-                            info!("Name: {:?}", &name);
-                            if let Some(node_handle) = gltf.named_nodes.get(name.as_str()) {
-                                if let Some(node) = gltf_nodes.get(node_handle) {
-                                    roots.push(node.clone());
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-
-            info!("Roots: {:?}", &roots);
-
             let ctx = UnpackContext {
-                material_map: &material_map,
-                mesh_map: &mesh_map,
-                gltf_meshs,
+                scene: &scene.world,
                 gltf_path: &gltf_path,
+                unpack: Uuid::new_v4(),
+                clips: &clips,
+                materials,
             };
-
-            for root in roots.iter() {
-                spawn_node(&mut commands, root, gltf, &ctx);
+            if loaded.reimport {
+                let unpacks = unpacked_nodes(&mut sources, world, &gltf_path);
+                let report = reimport_unpacks(&mut commands, &ctx, world, &unpacks);
+                info!("Re-imported {}: {:?}", &gltf_path, &report);
+                reports.push((gltf_path.clone(), report));
+            } else {
+                let roots = spawn_scene(&mut commands, &ctx);
+                info!("Roots: {:?}", &roots);
             }
         }
-
-        break;
     }
 
     command_queue.apply(world);
+
+    for (path, report) in reports {
+        let kind = if report.removed > 0 {
+            ToastKind::Warning
+        } else {
+            ToastKind::Success
+        };
+        world.send_event(ToastMessage::new(
+            &format!(
+                "Re-imported {}: {} updated, {} added, {} removed",
+                path, report.updated, report.added, report.removed
+            ),
+            kind,
+        ));
+    }
+}
+
+/// Spawn prefab entities for all nodes of gltf scene world. Returns spawned root entities
+fn spawn_scene(commands: &mut Commands, ctx: &UnpackContext<'_>) -> Vec<Entity> {
+    let mut entity_map = EntityHashMap::default();
+
+    let roots = node_children(ctx.scene, None)
+        .into_iter()
+        .map(|(node, segment)| spawn_node(commands, ctx, node, vec![segment], &mut entity_map))
+        .collect();
+
+    bind_skins(commands, ctx, &entity_map, entity_map.keys());
+
+    roots
+}
+
+/// Child nodes of `node` with their path segments. Gltf nodes are children of scene root entity,
+/// so `None` gives root nodes
+fn node_children(scene: &World, node: Option<Entity>) -> Vec<(Entity, String)> {
+    let children = node
+        .map_or_else(
+            || {
+                scene
+                    .iter_entities()
+                    .find(|e| !e.contains::<Parent>() && e.contains::<Children>())
+                    .and_then(|e| e.get::<Children>())
+            },
+            |node| scene.get::<Children>(node),
+        )
+        .map(|children| children.to_vec())
+        .unwrap_or_default();
+
+    let names = children
+        .iter()
+        .map(|child| scene.get::<Name>(*child).map(Name::as_str))
+        .collect::<Vec<_>>();
+    children
+        .iter()
+        .zip(names.iter())
+        .enumerate()
+        .map(|(idx, (child, name))| {
+            let segment = match name {
+                Some(name) if names.iter().filter(|n| *n == &Some(*name)).count() == 1 => {
+                    name.to_string()
+                }
+                Some(name) => format!("{name}#{idx}"),
+                None => format!("#{idx}"),
+            };
+            (*child, segment)
+        })
+        .collect()
+}
+
+/// Joints can be anywhere in hierarchy, so skins are bound after all nodes are spawned
+fn bind_skins<'a>(
+    commands: &mut Commands,
+    ctx: &UnpackContext<'_>,
+    entity_map: &EntityHashMap<Entity>,
+    nodes: impl Iterator<Item = &'a Entity>,
+) {
+    for node in nodes {
+        let (Some(skin), Some(id)) = (ctx.scene.get::<SkinnedMesh>(*node), entity_map.get(node))
+        else {
+            continue;
+        };
+        commands.entity(*id).insert(SkinnedMeshPrefab {
+            inverse_bindposes: asset_path(&skin.inverse_bindposes)
+                .unwrap_or_default()
+                .into(),
+            joints: skin
+                .joints
+                .iter()
+                .filter_map(|joint| entity_map.get(joint).copied())
+                .collect(),
+        });
+    }
+}
+
+/// Values of gltf node to compare on re-import
+fn node_source(ctx: &UnpackContext<'_>, node: Entity, path: Vec<String>) -> GltfSource {
+    let node_ref = ctx.scene.entity(node);
+    GltfSource {
+        gltf: ctx.gltf_path.to_string(),
+        node: path,
+        unpack: ctx.unpack,
+        transform: node_ref.get::<Transform>().copied().unwrap_or_default(),
+        mesh: node_ref
+            .get::<Handle<Mesh>>()
+            .and_then(asset_path)
+            .unwrap_or_default(),
+        material: node_ref
+            .get::<Handle<StandardMaterial>>()
+            .and_then(asset_path)
+            .unwrap_or_default(),
+    }
 }
 
 fn spawn_node(
     commands: &mut Commands,
-    node: &GltfNode,
-    _gltf: &Gltf,
     ctx: &UnpackContext<'_>,
+    node: Entity,
+    path: Vec<String>,
+    entity_map: &mut EntityHashMap<Entity>,
 ) -> Entity {
+    let node_ref = ctx.scene.entity(node);
+    let source = node_source(ctx, node, path.clone());
     let id = commands
         .spawn((
             SpatialBundle {
-                transform: node.transform,
+                transform: source.transform,
                 ..default()
             },
             PrefabMarker,
         ))
         .id();
+    entity_map.insert(node, id);
 
-    if let Some(handle) = &node.mesh {
-        if let Some(mesh) = ctx.gltf_meshs.get(handle) {
-            if mesh.primitives.len() == 1 {
-                commands.entity(id).insert(AssetMesh {
-                    path: format!(
-                        "{}#Mesh{}/Primitive{}",
-                        ctx.gltf_path.path().display(),
-                        ctx.mesh_map.get(handle).unwrap(),
-                        0
-                    ),
-                });
-
-                if let Some(material_handle) = &mesh.primitives[0].material {
-                    if let Some(idx) = ctx.material_map.get(material_handle) {
-                        commands.entity(id).insert(AssetMaterial {
-                            path: format!("{}#Material{}", ctx.gltf_path.path().display(), idx),
-                        });
-                    } else {
-                        commands.entity(id).insert(MaterialPrefab::default());
-                    }
-                } else {
-                    commands.entity(id).insert(MaterialPrefab::default());
-                }
-            } else {
-                commands.entity(id).with_children(|parent| {
-                    for idx in 0..mesh.primitives.len() {
-                        let mut id = parent.spawn((
-                            SpatialBundle::default(),
-                            AssetMesh {
-                                path: format!(
-                                    "{}#Mesh{}/Primitive{}",
-                                    ctx.gltf_path.path().display(),
-                                    ctx.mesh_map.get(handle).unwrap(),
-                                    idx
-                                ),
-                            },
-                            PrefabMarker,
-                        ));
-
-                        if let Some(material_handle) = &mesh.primitives[idx].material {
-                            if let Some(idx) = ctx.material_map.get(material_handle) {
-                                id.insert(AssetMaterial {
-                                    path: format!(
-                                        "{}#Material{}",
-                                        ctx.gltf_path.path().display(),
-                                        idx
-                                    ),
-                                });
-                            } else {
-                                id.insert(MaterialPrefab::default());
-                            }
-                        } else {
-                            id.insert(MaterialPrefab::default());
-                        }
-                    }
-                });
-            }
+    let name = node_ref.get::<Name>();
+    if let Some(name) = name {
+        commands.entity(id).insert(name.clone());
+    }
+
+    if !source.mesh.is_empty() {
+        commands.entity(id).insert(AssetMesh {
+            path: source.mesh.clone().into(),
+        });
+
+        let extracted = ctx.materials.and_then(|materials| {
+            node_ref
+                .get::<Handle<StandardMaterial>>()
+                .and_then(|handle| materials.get(handle))
+                .map(MaterialPrefab::from_material)
+        });
+        if let Some(material) = extracted {
+            commands.entity(id).insert(material);
+        } else if source.material.is_empty() {
+            commands.entity(id).insert(MaterialPrefab::default());
+        } else {
+            commands.entity(id).insert(AssetMaterial {
+                path: source.material.clone(),
+            });
         }
     }
+    commands.entity(id).insert(source);
+    if let (Some(camera), Some(projection)) =
+        (node_ref.get::<Camera>(), node_ref.get::<Projection>())
+    {
+        commands.entity(id).insert((
+            Camera3d::default(),
+            Camera {
+                is_active: camera.is_active,
+                ..default()
+            },
+            DebandDither::Enabled,
+            projection.clone(),
+            PlaymodeCamera::default(),
+            CameraRenderGraph::new(Core3d),
+        ));
+    }
+
+    if let Some(light) = node_ref.get::<PointLight>() {
+        commands.entity(id).insert(*light);
+    }
+    if let Some(light) = node_ref.get::<SpotLight>() {
+        commands.entity(id).insert(*light);
+    }
+    if let Some(light) = node_ref.get::<DirectionalLight>() {
+        commands.entity(id).insert(light.clone());
+    }
+    if node_ref.contains::<PointLight>()
+        || node_ref.contains::<SpotLight>()
+        || node_ref.contains::<DirectionalLight>()
+    {
+        commands
+            .entity(id)
+            .insert((LightAreaToggle::default(), PlaymodeLight::default()));
+    }
 
-    for child in &node.children {
-        let child_id = spawn_node(commands, child, _gltf, ctx);
+    // Player is placed on animation root node, clips are targeted by its name
+    if let (true, Some(name)) = (node_ref.contains::<AnimationPlayer>(), name) {
+        commands.entity(id).insert(AnimationPlayerPrefab {
+            clips: ctx
+                .clips
+                .iter()
+                .filter(|(_, clip)| clip.compatible_with(name))
+                .map(|(path, _)| path.clone().into())
+                .collect(),
+            autoplay: None,
+            repeat: true,
+        });
+    }
+
+    for (child, segment) in node_children(ctx.scene, Some(node)) {
+        let mut child_path = path.clone();
+        child_path.push(segment);
+        let child_id = spawn_node(commands, ctx, child, child_path, entity_map);
         commands.entity(id).add_child(child_id);
     }
 
     id
 }
+
+/// Counts of changed entities on re-import
+#[derive(Default, Debug, PartialEq, Eq)]
+struct ReimportReport {
+    updated: usize,
+    added: usize,
+    removed: usize,
+}
+
+/// Entities unpacked from gltf file, grouped by unpack and found by node paths
+fn unpacked_nodes(
+    sources: &mut QueryState<(Entity, &GltfSource)>,
+    world: &World,
+    gltf_path: &str,
+) -> HashMap<Uuid, HashMap<Vec<String>, Entity>> {
+    let mut unpacks = HashMap::<Uuid, HashMap<_, _>>::default();
+    for (entity, source) in sources.iter(world) {
+        if source.gltf == gltf_path {
+            unpacks
+                .entry(source.unpack)
+                .or_default()
+                .insert(source.node.clone(), entity);
+        }
+    }
+    unpacks
+}
+
+/// Re-import every unpack of gltf scene separately
+fn reimport_unpacks(
+    commands: &mut Commands,
+    ctx: &UnpackContext<'_>,
+    world: &World,
+    unpacks: &HashMap<Uuid, HashMap<Vec<String>, Entity>>,
+) -> ReimportReport {
+    let mut report = ReimportReport::default();
+    for (unpack, unpacked) in unpacks.iter() {
+        let ctx = UnpackContext {
+            unpack: *unpack,
+            ..*ctx
+        };
+        let unpack_report = reimport_scene(commands, &ctx, world, unpacked);
+        report.updated += unpack_report.updated;
+        report.added += unpack_report.added;
+        report.removed += unpack_report.removed;
+    }
+    report
+}
+
+/// Update `unpacked` entities of one unpack, found by node paths, with changes of gltf scene
+fn reimport_scene(
+    commands: &mut Commands,
+    ctx: &UnpackContext<'_>,
+    world: &World,
+    unpacked: &HashMap<Vec<String>, Entity>,
+) -> ReimportReport {
+    let mut report = ReimportReport::default();
+    let mut entity_map = EntityHashMap::default();
+    let mut spawned = EntityHashMap::default();
+
+    // New root nodes are placed next to old root nodes
+    let roots_parent = unpacked
+        .iter()
+        .find(|(path, _)| path.len() == 1)
+        .and_then(|(_, entity)| world.get::<Parent>(*entity))
+        .map(Parent::get);
+
+    let mut stack = node_children(ctx.scene, None)
+        .into_iter()
+        .map(|(node, segment)| (node, vec![segment], roots_parent))
+        .collect::<Vec<_>>();
+    while let Some((node, path, parent)) = stack.pop() {
+        if let Some(entity) = unpacked.get(&path).copied() {
+            if update_node(commands, ctx, world, node, entity, path.clone()) {
+                report.updated += 1;
+            }
+            entity_map.insert(node, entity);
+            for (child, segment) in node_children(ctx.scene, Some(node)) {
+                let mut child_path = path.clone();
+                child_path.push(segment);
+                stack.push((child, child_path, Some(entity)));
+            }
+        } else {
+            let id = spawn_node(commands, ctx, node, path, &mut spawned);
+            if let Some(parent) = parent {
+                commands.entity(parent).add_child(id);
+            }
+        }
+    }
+
+    let found = entity_map.values().copied().collect::<HashSet<_>>();
+
+    // Skins of new nodes can use joints of old and new nodes
+    report.added = spawned.len();
+    entity_map.extend(spawned.iter().map(|(node, id)| (*node, *id)));
+    bind_skins(commands, ctx, &entity_map, spawned.keys());
+
+    for entity in unpacked.values() {
+        if !found.contains(entity) {
+            commands.entity(*entity).insert(GltfNodeRemoved);
+            report.removed += 1;
+        } else if world.get::<GltfNodeRemoved>(*entity).is_some() {
+            commands.entity(*entity).remove::<GltfNodeRemoved>();
+        }
+    }
+
+    report
+}
+
+/// Apply changes of gltf node since last import. Material is not changed if it was replaced in editor.
+/// Returns true if entity is changed
+fn update_node(
+    commands: &mut Commands,
+    ctx: &UnpackContext<'_>,
+    world: &World,
+    node: Entity,
+    entity: Entity,
+    path: Vec<String>,
+) -> bool {
+    let source = node_source(ctx, node, path);
+    let Some(old) = world.get::<GltfSource>(entity) else {
+        return false;
+    };
+    if old == &source {
+        return false;
+    }
+
+    let mut entity_commands = commands.entity(entity);
+    if source.transform != old.transform {
+        entity_commands.insert(source.transform);
+    }
+    if source.mesh != old.mesh {
+        if source.mesh.is_empty() {
+            entity_commands.remove::<AssetMesh>();
+        } else {
+            entity_commands.insert(AssetMesh {
+                path: source.mesh.clone().into(),
+            });
+        }
+    }
+    let material_replaced = world
+        .get::<AssetMaterial>(entity)
+        .map_or(!old.material.is_empty(), |material| {
+            material.path != old.material
+        });
+    if source.material != old.material && !material_replaced {
+        if source.material.is_empty() {
+            entity_commands.remove::<AssetMaterial>();
+            entity_commands.insert(MaterialPrefab::default());
+        } else {
+            entity_commands.remove::<MaterialPrefab>();
+            entity_commands.insert(AssetMaterial {
+                path: source.material.clone(),
+            });
+        }
+    }
+    entity_commands.insert(source);
+    true
+}
+
+/// Path of labeled gltf asset, like `models/fox.glb#Mesh0/Primitive0`
+fn asset_path<A: Asset>(handle: &Handle<A>) -> Option<String> {
+    handle.path().map(ToString::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        animation::{EntityPath, Interpolation, Keyframes, VariableCurve},
+        render::mesh::skinning::SkinnedMeshInverseBindposes,
+    };
+
+    use super::*;
+
+    fn test_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Mesh>()
+            .init_asset::<StandardMaterial>()
+            .init_asset::<SkinnedMeshInverseBindposes>();
+        app
+    }
+
+    /// Scene world like one loaded by bevy gltf loader
+    fn fox_scene(server: &AssetServer) -> World {
+        let mut scene = World::new();
+        let joint = scene
+            .spawn((Name::new("Bone"), Transform::from_xyz(0.0, 1.0, 0.0)))
+            .id();
+        let mesh = scene
+            .spawn((
+                Name::new("Body.0"),
+                server.load::<Mesh>("fox.glb#Mesh0/Primitive0"),
+                server.load::<StandardMaterial>("fox.glb#Material0"),
+                SkinnedMesh {
+                    inverse_bindposes: server.load("fox.glb#Skin0"),
+                    joints: vec![joint],
+                },
+            ))
+            .id();
+        let fox = scene
+            .spawn((
+                Name::new("Fox"),
+                Transform::default(),
+                AnimationPlayer::default(),
+            ))
+            .push_children(&[joint, mesh])
+            .id();
+        let light = scene
+            .spawn((Name::new("Sun"), DirectionalLight::default()))
+            .id();
+        let camera = scene
+            .spawn((
+                Name::new("Camera"),
+                Camera::default(),
+                Projection::default(),
+            ))
+            .id();
+        scene
+            .spawn(SpatialBundle::default())
+            .push_children(&[fox, light, camera]);
+        scene
+    }
+
+    fn find(world: &mut World, name: &str) -> Entity {
+        world
+            .query::<(Entity, &Name)>()
+            .iter(world)
+            .find(|(_, n)| n.as_str() == name)
+            .unwrap()
+            .0
+    }
+
+    fn unpack(app: &mut App, scene: &World, clips: &[(String, &AnimationClip)]) -> Vec<Entity> {
+        let ctx = UnpackContext {
+            scene,
+            gltf_path: "fox.glb",
+            unpack: Uuid::new_v4(),
+            clips,
+            materials: None,
+        };
+        let mut queue = CommandQueue::default();
+        let roots = spawn_scene(&mut Commands::new(&mut queue, &app.world), &ctx);
+        queue.apply(&mut app.world);
+        roots
+    }
+
+    fn reimport(app: &mut App, scene: &World) -> ReimportReport {
+        let ctx = UnpackContext {
+            scene,
+            gltf_path: "fox.glb",
+            unpack: Uuid::nil(),
+            clips: &[],
+            materials: None,
+        };
+        let mut sources = app.world.query::<(Entity, &GltfSource)>();
+        let unpacks = unpacked_nodes(&mut sources, &app.world, "fox.glb");
+        let mut queue = CommandQueue::default();
+        let report = reimport_unpacks(
+            &mut Commands::new(&mut queue, &app.world),
+            &ctx,
+            &app.world,
+            &unpacks,
+        );
+        queue.apply(&mut app.world);
+        report
+    }
+
+    #[test]
+    fn unpack_scene_nodes() {
+        let mut app = test_app();
+        let server = app.world.resource::<AssetServer>().clone();
+        let scene = fox_scene(&server);
+
+        let mut clip = AnimationClip::default();
+        clip.add_curve_to_path(
+            EntityPath {
+                parts: vec![Name::new("Fox"), Name::new("Bone")],
+            },
+            VariableCurve {
+                keyframe_timestamps: vec![0.0],
+                keyframes: Keyframes::Translation(vec![Vec3::ZERO]),
+                interpolation: Interpolation::Linear,
+            },
+        );
+        let clips = [
+            ("fox.glb#Animation0".to_string(), &clip),
+            ("fox.glb#Animation1".to_string(), &AnimationClip::default()),
+        ];
+
+        let roots = unpack(&mut app, &scene, &clips);
+        assert_eq!(roots.len(), 3);
+
+        let world = &mut app.world;
+        let fox = find(world, "Fox");
+        let bone = find(world, "Bone");
+        let body = find(world, "Body.0");
+
+        let player = world.get::<AnimationPlayerPrefab>(fox).unwrap();
+        assert_eq!(player.clips, vec!["fox.glb#Animation0".into()]);
+        assert_eq!(world.get::<Parent>(bone).unwrap().get(), fox);
+        assert_eq!(world.get::<Transform>(bone).unwrap().translation, Vec3::Y);
+        assert_eq!(
+            world.get::<AssetMesh>(body).unwrap().path.as_str(),
+            "fox.glb#Mesh0/Primitive0"
+        );
+        assert_eq!(
+            world.get::<AssetMaterial>(body).unwrap().path,
+            "fox.glb#Material0"
+        );
+        let skin = world.get::<SkinnedMeshPrefab>(body).unwrap();
+        assert_eq!(skin.inverse_bindposes.as_str(), "fox.glb#Skin0");
+        assert_eq!(skin.joints, vec![bone]);
+        assert_eq!(
+            world.get::<GltfSource>(body).unwrap().node,
+            vec!["Fox".to_string(), "Body.0".to_string()]
+        );
+
+        let sun = find(world, "Sun");
+        assert!(world.get::<DirectionalLight>(sun).is_some());
+        assert!(world.get::<PlaymodeLight>(sun).is_some());
+        let camera = find(world, "Camera");
+        assert!(world.get::<Camera3d>(camera).is_some());
+        assert!(world.get::<PlaymodeCamera>(camera).is_some());
+        assert!(world.get::<PrefabMarker>(camera).is_some());
+    }
+
+    #[test]
+    fn unpack_extracted_materials() {
+        let mut app = test_app();
+        let server = app.world.resource::<AssetServer>().clone();
+        let scene = fox_scene(&server);
+        let handle = server.load::<StandardMaterial>("fox.glb#Material0");
+        app.world.resource_mut::<Assets<StandardMaterial>>().insert(
+            &handle,
+            StandardMaterial {
+                base_color: Color::ORANGE,
+                ..default()
+            },
+        );
+
+        let ctx = UnpackContext {
+            scene: &scene,
+            gltf_path: "fox.glb",
+            unpack: Uuid::new_v4(),
+            clips: &[],
+            materials: Some(app.world.resource::<Assets<StandardMaterial>>()),
+        };
+        let mut queue = CommandQueue::default();
+        spawn_scene(&mut Commands::new(&mut queue, &app.world), &ctx);
+        queue.apply(&mut app.world);
+
+        let body = find(&mut app.world, "Body.0");
+        assert!(app.world.get::<AssetMaterial>(body).is_none());
+        assert_eq!(
+            app.world.get::<MaterialPrefab>(body).unwrap().base_color,
+            Color::ORANGE
+        );
+    }
+
+    #[test]
+    fn reimport_keeps_editor_changes() {
+        let mut app = test_app();
+        let server = app.world.resource::<AssetServer>().clone();
+        let mut scene = fox_scene(&server);
+        unpack(&mut app, &scene, &[]);
+
+        // Editor changes
+        let world = &mut app.world;
+        let (fox, body, sun) = (
+            find(world, "Fox"),
+            find(world, "Body.0"),
+            find(world, "Sun"),
+        );
+        world.entity_mut(fox).insert(Visibility::Hidden);
+        world
+            .entity_mut(body)
+            .remove::<AssetMaterial>()
+            .insert(MaterialPrefab::default());
+        world
+            .entity_mut(sun)
+            .insert(Transform::from_xyz(5.0, 0.0, 0.0));
+
+        // File changes
+        let scene_body = find(&mut scene, "Body.0");
+        scene
+            .entity_mut(scene_body)
+            .insert(server.load::<StandardMaterial>("fox.glb#Material1"));
+        let scene_fox = find(&mut scene, "Fox");
+        scene
+            .entity_mut(scene_fox)
+            .insert(Transform::from_xyz(0.0, 0.0, 2.0))
+            .with_children(|parent| {
+                parent.spawn(Name::new("Tail"));
+            });
+        let scene_camera = find(&mut scene, "Camera");
+        scene.entity_mut(scene_camera).despawn_recursive();
+
+        let report = reimport(&mut app, &scene);
+        assert_eq!(
+            report,
+            ReimportReport {
+                updated: 2,
+                added: 1,
+                removed: 1,
+            }
+        );
+
+        let world = &mut app.world;
+        assert_eq!(world.get::<Visibility>(fox), Some(&Visibility::Hidden));
+        assert_eq!(
+            world.get::<Transform>(fox).unwrap().translation,
+            Vec3::new(0.0, 0.0, 2.0)
+        );
+        assert!(world.get::<AssetMaterial>(body).is_none());
+        assert!(world.get::<MaterialPrefab>(body).is_some());
+        assert_eq!(
+            world.get::<GltfSource>(body).unwrap().material,
+            "fox.glb#Material1"
+        );
+        assert_eq!(world.get::<Transform>(sun).unwrap().translation.x, 5.0);
+        let tail = find(world, "Tail");
+        assert_eq!(world.get::<Parent>(tail).unwrap().get(), fox);
+        let camera = find(world, "Camera");
+        assert!(world.get::<GltfNodeRemoved>(camera).is_some());
+
+        // Nothing changed
+        assert_eq!(
+            reimport(&mut app, &scene),
+            ReimportReport {
+                removed: 1,
+                ..default()
+            }
+        );
+    }
+
+    #[test]
+    fn reimport_every_unpack_of_file() {
+        let mut app = test_app();
+        let server = app.world.resource::<AssetServer>().clone();
+        let mut scene = fox_scene(&server);
+        let first = unpack(&mut app, &scene, &[]);
+        let second = unpack(&mut app, &scene, &[]);
+
+        let scene_fox = find(&mut scene, "Fox");
+        scene
+            .entity_mut(scene_fox)
+            .insert(Transform::from_xyz(0.0, 0.0, 2.0))
+            .with_children(|parent| {
+                parent.spawn(Name::new("Tail"));
+            });
+
+        assert_eq!(
+            reimport(&mut app, &scene),
+            ReimportReport {
+                updated: 2,
+                added: 2,
+                removed: 0,
+            }
+        );
+
+        let world = &mut app.world;
+        let mut foxes = world.query::<(Entity, &Name, &Transform)>();
+        let foxes = foxes
+            .iter(world)
+            .filter(|(_, name, _)| name.as_str() == "Fox")
+            .map(|(entity, _, transform)| (entity, transform.translation))
+            .collect::<Vec<_>>();
+        assert_eq!(foxes.len(), 2);
+        assert!(foxes
+            .iter()
+            .all(|(_, translation)| *translation == Vec3::new(0.0, 0.0, 2.0)));
+
+        // Each copy gets own new node
+        let mut tails = world.query::<(&Name, &Parent, &GltfSource)>();
+        let mut tail_parents = tails
+            .iter(world)
+            .filter(|(name, _, _)| name.as_str() == "Tail")
+            .map(|(_, parent, source)| (parent.get(), source.unpack))
+            .collect::<Vec<_>>();
+        tail_parents.sort();
+        let mut expected = [first[0], second[0]]
+            .map(|fox| (fox, world.get::<GltfSource>(fox).unwrap().unpack))
+            .to_vec();
+        expected.sort();
+        assert_eq!(tail_parents, expected);
+        assert!(world
+            .query::<&GltfNodeRemoved>()
+            .iter(world)
+            .next()
+            .is_none());
+    }
+}
@@ -1,6 +1,7 @@
 #![allow(clippy::type_complexity)]
 #![allow(clippy::too_many_arguments)]
 
+pub mod autosave;
 pub mod hotkeys;
 mod load;
 pub mod selected;
@@ -9,7 +10,7 @@ pub mod toast;
 
 pub mod prelude {
     pub use super::*;
-    pub use super::{hotkeys::*, load::*, selected::*, task_storage::*};
+    pub use super::{autosave::*, hotkeys::*, load::*, selected::*, task_storage::*};
     pub use space_undo;
 }
 
@@ -18,7 +19,10 @@ pub mod gltf_unpack;
 use bevy::prelude::*;
 
 use prelude::load_listener;
-use space_prefab::save::{SaveConfig, SaveState};
+use space_prefab::{
+    save::{SaveConfig, SaveState},
+    scenes::{ActiveEditorScene, EditorScene, SaveEditorScene},
+};
 use space_shared::*;
 use space_undo::AppAutoUndo;
 use task_storage::{BackgroundTask, BackgroundTaskStorage, BackgroundTaskStoragePlugin};
@@ -34,6 +38,8 @@ impl Plugin for EditorCore {
 
         app.add_plugins(BackgroundTaskStoragePlugin);
 
+        app.add_plugins(autosave::AutosavePlugin);
+
         app.configure_sets(Update, EditorLoadSet.in_set(EditorSet::Editor));
 
         app.add_event::<EditorEvent>();
@@ -59,7 +65,13 @@ pub struct EditorLoadSet;
 
 #[derive(Resource, Default, Clone)]
 pub struct EditorLoader {
+    /// Scene, which replaces all opened scenes
     pub scene: Option<Handle<DynamicScene>>,
+    /// File of `scene` relative to assets folder. Entities of file without scene roots
+    /// are placed under new [`EditorScene`] root with this path
+    pub path: Option<String>,
+    /// Scene files loaded next to already opened scenes
+    pub additive: Vec<(String, Handle<DynamicScene>)>,
 }
 
 fn editor_event_listener(
@@ -71,7 +83,11 @@ fn editor_event_listener(
     mut start_game_state: ResMut<NextState<EditorState>>,
     cache: ResMut<PrefabMemoryCache>,
     mut gltf_events: EventWriter<gltf_unpack::EditorUnpackGltf>,
+    mut reimport_events: EventWriter<gltf_unpack::EditorReimportGltf>,
     mut background_tasks: ResMut<BackgroundTaskStorage>,
+    active_scene: Res<ActiveEditorScene>,
+    mut scenes: Query<(&mut EditorScene, Option<&mut Name>)>,
+    mut scene_saves: EventWriter<SaveEditorScene>,
 ) {
     for event in events.read() {
         match event {
@@ -83,14 +99,37 @@ fn editor_event_listener(
                         handle.clone().untyped(),
                     ));
                     load_server.scene = Some(handle);
+                    load_server.path = Some(path.to_string());
                     info!("Loading prefab by editor event from file {}", path);
                 }
                 EditorPrefabPath::MemoryCache => {
                     load_server.scene.clone_from(&cache.scene);
+                    load_server.path = None;
                     info!("Loading prefab by editor event from memory cache");
                 }
             },
+            EditorEvent::LoadAdditive(path) => {
+                let handle = assets.load(path.to_string());
+                background_tasks.tasks.push(BackgroundTask::AssetLoading(
+                    path.to_string(),
+                    handle.clone().untyped(),
+                ));
+                load_server.additive.push((path.to_string(), handle));
+                info!("Loading scene {} next to opened scenes", path);
+            }
             EditorEvent::Save(path) => {
+                // Active scene is saved to chosen file, other opened scenes keep their files
+                if let (EditorPrefabPath::File(file), Some(root)) = (path, active_scene.0) {
+                    if let Ok((mut scene, name)) = scenes.get_mut(root) {
+                        scene.path = autosave::asset_relative(file);
+                        if let Some(mut name) = name {
+                            name.set(scene.name());
+                        }
+                        scene_saves.send(SaveEditorScene::Scene(root));
+                        info!("Saving scene {:?} to {}", root, scene.path);
+                        continue;
+                    }
+                }
                 save_config.path = Some(path.clone());
                 save_state.set(SaveState::Save);
                 info!("Saving scene to {:?}", path);
@@ -99,7 +138,13 @@ fn editor_event_listener(
                 start_game_state.set(EditorState::GamePrepare);
             }
             EditorEvent::LoadGltfAsPrefab(path) => {
-                gltf_events.send(gltf_unpack::EditorUnpackGltf { path: path.clone() });
+                gltf_events.send(gltf_unpack::EditorUnpackGltf {
+                    path: path.clone(),
+                    extract_materials: false,
+                });
+            }
+            EditorEvent::ReimportGltf(path) => {
+                reimport_events.send(gltf_unpack::EditorReimportGltf { path: path.clone() });
             }
         }
     }
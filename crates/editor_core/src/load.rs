@@ -1,52 +1,89 @@
 use bevy::{ecs::entity::EntityHashMap, prelude::*};
+use space_prefab::{
+    editor_registry::EditorRegistry,
+    save::ChildrenPrefab,
+    scenes::{spawn_scene_root, ActiveEditorScene, EditorScene},
+};
 use space_shared::{toast::ToastMessage, *};
 
 use crate::EditorLoader;
 
-pub fn load_listener(world: &mut World) {
+/// Copy of loaded scene asset. None while asset is not loaded
+fn loaded_scene(world: &World, handle: &Handle<DynamicScene>) -> Option<DynamicScene> {
     let app_registry = world.resource::<AppTypeRegistry>().clone();
+    let assets = world.resource::<Assets<DynamicScene>>();
+    let scene = assets.get(handle)?;
+    let mut scene = Scene::from_dynamic_scene(scene, &app_registry).unwrap();
+    scene.world.insert_resource(app_registry);
+    Some(DynamicScene::from_scene(&scene)) //kill me, is it clone() analog for DynamicScene
+}
+
+pub fn load_listener(world: &mut World) {
     let load_server = world.resource::<EditorLoader>().clone();
-    let mut prefab;
+
+    if let Some(prefab) = load_server
+        .scene
+        .as_ref()
+        .and_then(|handle| loaded_scene(world, handle))
     {
-        let assets = world.resource::<Assets<DynamicScene>>();
-        if let Some(scene) = &load_server.scene {
-            if let Some(scene) = assets.get(scene) {
-                let mut scene = Scene::from_dynamic_scene(scene, &app_registry).unwrap();
-                scene.world.insert_resource(app_registry);
-                prefab = DynamicScene::from_scene(&scene); //kill me, is it clone() analog for DynamicScene
-            } else {
-                return;
+        let path = {
+            let mut load_server = world.resource_mut::<EditorLoader>();
+            load_server.scene = None;
+            load_server.path.take()
+        };
+
+        let mut query = world.query_filtered::<(Entity, Option<&Name>), With<PrefabMarker>>();
+        let mark_to_delete: Vec<_> = query
+            .iter(world)
+            .map(|(e, name)| (e, name.cloned()))
+            .collect();
+        for (entity, name) in mark_to_delete {
+            let mut despawned = false;
+            if let Some(e) = world.get_entity_mut(entity) {
+                e.despawn_recursive();
+                despawned = true;
             }
-        } else {
-            return;
-        }
-    }
-    world.resource_mut::<EditorLoader>().scene = None;
 
-    let mut query = world.query_filtered::<(Entity, Option<&Name>), With<PrefabMarker>>();
-    let mark_to_delete: Vec<_> = query
-        .iter(world)
-        .map(|(e, name)| (e, name.cloned()))
-        .collect();
-    for (entity, name) in mark_to_delete {
-        let mut despawned = false;
-        if let Some(e) = world.get_entity_mut(entity) {
-            e.despawn_recursive();
-            despawned = true;
+            if despawned {
+                world.send_event(ToastMessage::new(
+                    &if name.is_some() {
+                        format!("Despawning {}: {:?}", name.unwrap(), entity)
+                    } else {
+                        format!("Despawning {:?}", entity)
+                    },
+                    egui_toast::ToastKind::Warning,
+                ));
+            }
         }
-
-        if despawned {
-            world.send_event(ToastMessage::new(
-                &if name.is_some() {
-                    format!("Despawning {}: {:?}", name.unwrap(), entity)
-                } else {
-                    format!("Despawning {:?}", entity)
-                },
-                egui_toast::ToastKind::Warning,
-            ));
+        if let Some(mut active) = world.get_resource_mut::<ActiveEditorScene>() {
+            active.0 = None;
         }
+        // Settings of previous level must not leak into loaded one
+        if let Some(registry) = world.get_resource::<EditorRegistry>() {
+            for resource in registry.scene_resources.clone() {
+                resource.reset(world);
+            }
+        }
+
+        spawn_loaded_scene(world, prefab, path);
     }
 
+    for (path, handle) in load_server.additive.iter() {
+        let Some(mut prefab) = loaded_scene(world, handle) else {
+            continue;
+        };
+        // Level settings are taken only from scene, which replaced opened scenes
+        prefab.resources.clear();
+        world
+            .resource_mut::<EditorLoader>()
+            .additive
+            .retain(|(_, pending)| pending != handle);
+        spawn_loaded_scene(world, prefab, Some(path.clone()));
+    }
+}
+
+/// Spawn loaded entities. Scene without its own scene roots is placed under new root for `path`
+fn spawn_loaded_scene(world: &mut World, mut prefab: DynamicScene, path: Option<String>) {
     for entity in &mut prefab.entities {
         entity.components.push(Box::new(PrefabMarker));
     }
@@ -65,7 +102,36 @@ pub fn load_listener(world: &mut World) {
                 &format!("Failed to create scene:\n{err}"),
                 egui_toast::ToastKind::Error,
             ));
-            bevy::log::error!("{}", err)
+            bevy::log::error!("{}", err);
+            return;
+        }
+    }
+
+    let loaded = map.values().copied().collect::<Vec<_>>();
+    let has_roots = loaded
+        .iter()
+        .any(|e| world.get::<EditorScene>(*e).is_some());
+    let Some(path) = path.filter(|_| !has_roots) else {
+        return;
+    };
+
+    // Children are attached to their parents later from ChildrenPrefab
+    let children = loaded
+        .iter()
+        .filter_map(|e| world.get::<ChildrenPrefab>(*e))
+        .flat_map(|children| children.0.iter().copied())
+        .collect::<Vec<_>>();
+    let mut top = loaded
+        .into_iter()
+        .filter(|e| !children.contains(e))
+        .collect::<Vec<_>>();
+    top.sort();
+
+    let root = spawn_scene_root(world, &path);
+    world.entity_mut(root).push_children(&top);
+    if let Some(mut active) = world.get_resource_mut::<ActiveEditorScene>() {
+        if active.0.is_none() {
+            active.0 = Some(root);
         }
     }
 }
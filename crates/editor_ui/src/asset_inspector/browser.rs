@@ -0,0 +1,698 @@
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use bevy_egui::{
+    egui::{self, collapsing_header::CollapsingState},
+    EguiUserTextures,
+};
+use space_editor_core::prelude::*;
+use space_prefab::{
+    component::{AssetMaterial, MaterialPrefab},
+    references::{
+        missing_assets, move_asset, moved_asset_path, AssetReference, AssetReferenceIndex,
+        AssetReferencePlugin,
+    },
+    save::RestorePrefabBackup,
+};
+use space_shared::{
+    toast::{ToastKind, ToastMessage},
+    *,
+};
+
+use crate::{
+    colors::ERROR_COLOR,
+    editor_tab::{EditorTab, EditorTabName},
+    icons,
+    menu_toolbars::MenuLoadEvent,
+    ui_plugin::EditorUi,
+    EditorUiAppExt,
+};
+
+use super::{
+    drop::{spawn_asset_prefab, DraggedAsset},
+    AssetDetectorPlugin, AssetKind, DetectedAssets, EditorAsset,
+};
+
+/// Size of asset thumbnail in browser
+const THUMBNAIL_SIZE: f32 = 64.;
+
+/// Plugin with dockable asset browser tab
+pub struct AssetBrowserPlugin;
+
+impl Plugin for AssetBrowserPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<AssetDetectorPlugin>() {
+            app.add_plugins(AssetDetectorPlugin);
+        }
+        if !app.is_plugin_added::<AssetReferencePlugin>() {
+            app.add_plugins(AssetReferencePlugin);
+        }
+        app.editor_tab_by_trait(EditorTabName::AssetBrowser, AssetBrowserTab::default());
+    }
+}
+
+/// Tab with all files of assets folder
+#[derive(Resource, Default)]
+pub struct AssetBrowserTab {
+    /// Opened folder relative to assets folder
+    pub folder: String,
+    /// Search in file names of all folders
+    pub search: String,
+    /// Show only assets of this kind
+    pub filter: Option<AssetKind>,
+    selected: Option<String>,
+    thumbnails: HashMap<String, (Handle<Image>, egui::TextureId)>,
+    report: Option<AssetReport>,
+}
+
+/// Report shown at the bottom of asset browser
+enum AssetReport {
+    /// Scene files, which use asset
+    Usages(String),
+    /// References of opened scene to missing files
+    Missing(Vec<AssetReference>),
+    /// Rename or move file or folder with rewriting of references to it
+    Move { from: String, to: String },
+}
+
+impl AssetReport {
+    fn move_asset(path: &str) -> Self {
+        Self::Move {
+            from: path.to_string(),
+            to: path.to_string(),
+        }
+    }
+}
+
+/// Open asset browser tab with given filter
+pub fn open_asset_browser(world: &mut World, filter: Option<AssetKind>) {
+    if let Some(mut tab) = world.get_resource_mut::<AssetBrowserTab>() {
+        tab.filter = filter;
+    }
+    if let Some(mut editor_ui) = world.get_resource_mut::<EditorUi>() {
+        editor_ui.open_tab(EditorTabName::AssetBrowser);
+    }
+    if let Some(mut detected) = world.get_resource_mut::<DetectedAssets>() {
+        detected.refresh();
+    }
+}
+
+impl EditorTab for AssetBrowserTab {
+    fn ui(&mut self, ui: &mut egui::Ui, commands: &mut Commands, world: &mut World) {
+        world
+            .resource_mut::<DetectedAssets>()
+            .bypass_change_detection()
+            .shown = true;
+        ui.horizontal(|ui| {
+            ui.label("🔍");
+            ui.add(egui::TextEdit::singleline(&mut self.search).desired_width(160.));
+            egui::ComboBox::from_id_source("asset_browser_filter")
+                .selected_text(self.filter.map_or("All", |kind| kind.name()))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.filter, None, "All");
+                    for kind in AssetKind::ALL {
+                        ui.selectable_value(&mut self.filter, Some(kind), kind.name());
+                    }
+                });
+            if ui
+                .button("⟳")
+                .on_hover_text("Rescan assets folder")
+                .clicked()
+            {
+                world.resource_mut::<DetectedAssets>().refresh();
+            }
+            if ui
+                .button("⚠ Missing assets")
+                .on_hover_text("Find references of opened scene to missing files")
+                .clicked()
+            {
+                match missing_assets(world) {
+                    Ok(missing) => self.report = Some(AssetReport::Missing(missing)),
+                    Err(err) => {
+                        world.send_event(ToastMessage::new(
+                            &format!("Failed to check assets of scene: {err}"),
+                            ToastKind::Error,
+                        ));
+                    }
+                }
+            }
+        });
+        ui.separator();
+
+        let (assets, folders) = {
+            let detected = world.resource::<DetectedAssets>();
+            (detected.assets.clone(), detected.folders.clone())
+        };
+
+        egui::SidePanel::left("asset_browser_folders")
+            .resizable(true)
+            .default_width(160.)
+            .show_inside(ui, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    if ui
+                        .selectable_label(self.folder.is_empty(), "📁 assets")
+                        .clicked()
+                    {
+                        self.folder.clear();
+                    }
+                    folder_tree(ui, &folders, "", &mut self.folder);
+                });
+            });
+
+        if let Some(report) = &mut self.report {
+            let mut open = true;
+            egui::TopBottomPanel::bottom("asset_browser_report")
+                .resizable(true)
+                .default_height(120.)
+                .show_inside(ui, |ui| {
+                    open = report_ui(ui, commands, world, report);
+                });
+            if !open {
+                self.report = None;
+            }
+        }
+
+        egui::CentralPanel::default().show_inside(ui, |ui| {
+            let search = self.search.to_lowercase();
+            let shown = assets
+                .iter()
+                .filter(|asset| self.filter.map_or(true, |kind| asset.kind() == kind))
+                .filter(|asset| {
+                    if search.is_empty() {
+                        asset.folder() == self.folder
+                    } else {
+                        asset.file_name().to_lowercase().contains(&search)
+                    }
+                })
+                .collect::<Vec<_>>();
+            let subfolders = if search.is_empty() {
+                folders
+                    .iter()
+                    .filter(|folder| parent_folder(folder) == self.folder)
+                    .cloned()
+                    .collect::<Vec<_>>()
+            } else {
+                vec![]
+            };
+
+            if shown.is_empty() && subfolders.is_empty() {
+                ui.label(egui::RichText::new("No assets found").color(ERROR_COLOR));
+            }
+
+            let mut visible = HashSet::new();
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    if !self.folder.is_empty() && search.is_empty() {
+                        let up = asset_cell(ui, |ui| folder_button(ui, ".."));
+                        if up.double_clicked() {
+                            self.folder = parent_folder(&self.folder).to_string();
+                        }
+                    }
+                    for folder in subfolders {
+                        let name = folder.rsplit('/').next().unwrap_or(&folder).to_string();
+                        let response = asset_cell(ui, |ui| folder_button(ui, &name));
+                        response.context_menu(|ui| {
+                            if ui.button("Rename / move").clicked() {
+                                self.report = Some(AssetReport::move_asset(&folder));
+                                ui.close_menu();
+                            }
+                        });
+                        if response.double_clicked() {
+                            self.folder = folder;
+                        }
+                    }
+                    for asset in shown {
+                        let path = asset.asset_path();
+                        let kind = asset.kind();
+                        let texture = if kind == AssetKind::Image {
+                            visible.insert(path.clone());
+                            Some(self.thumbnail(world, &path))
+                        } else {
+                            None
+                        };
+                        let is_selected = self.selected.as_ref() == Some(&path);
+                        // Assets are dragged to game view or inspector fields
+                        let drag_id = egui::Id::new(("asset_browser_drag", &path));
+                        let response = ui
+                            .dnd_drag_source(drag_id, DraggedAsset(path.clone()), |ui| {
+                                asset_cell(ui, |ui| {
+                                    let response = asset_button(ui, kind, texture, is_selected);
+                                    ui.add(egui::Label::new(asset.file_name()).truncate(true));
+                                    response
+                                })
+                            })
+                            .inner
+                            .on_hover_text(&path);
+                        if response.clicked() {
+                            self.selected = Some(path.clone());
+                        }
+                        if response.double_clicked() {
+                            default_action(commands, asset);
+                        }
+                        response.context_menu(|ui| {
+                            asset_context_menu(ui, commands, asset, &mut self.report);
+                        });
+                    }
+                });
+            });
+            self.drop_hidden_thumbnails(world, &visible);
+        });
+    }
+
+    fn title(&self) -> egui::WidgetText {
+        "Asset Browser".into()
+    }
+}
+
+impl AssetBrowserTab {
+    fn thumbnail(&mut self, world: &mut World, path: &str) -> egui::TextureId {
+        if let Some((_, id)) = self.thumbnails.get(path) {
+            return *id;
+        }
+        let handle: Handle<Image> = world.resource::<AssetServer>().load(path.to_string());
+        let id = world
+            .resource_mut::<EguiUserTextures>()
+            .add_image(handle.clone());
+        self.thumbnails.insert(path.to_string(), (handle, id));
+        id
+    }
+
+    /// Images of other folders are not kept loaded
+    fn drop_hidden_thumbnails(&mut self, world: &mut World, visible: &HashSet<String>) {
+        let hidden = self
+            .thumbnails
+            .keys()
+            .filter(|path| !visible.contains(*path))
+            .cloned()
+            .collect::<Vec<_>>();
+        for path in hidden {
+            if let Some((handle, _)) = self.thumbnails.remove(&path) {
+                world
+                    .resource_mut::<EguiUserTextures>()
+                    .remove_image(&handle);
+            }
+        }
+    }
+}
+
+fn parent_folder(folder: &str) -> &str {
+    folder.rsplit_once('/').map_or("", |(parent, _)| parent)
+}
+
+fn folder_tree(ui: &mut egui::Ui, folders: &[String], parent: &str, opened: &mut String) {
+    for folder in folders
+        .iter()
+        .filter(|folder| parent_folder(folder) == parent)
+    {
+        let name = folder.rsplit('/').next().unwrap_or(folder);
+        let has_children = folders
+            .iter()
+            .any(|other| parent_folder(other) == folder.as_str());
+        if has_children {
+            CollapsingState::load_with_default_open(
+                ui.ctx(),
+                ui.make_persistent_id(format!("asset_folder_{folder}")),
+                false,
+            )
+            .show_header(ui, |ui| {
+                if ui
+                    .selectable_label(opened == folder, format!("📁 {name}"))
+                    .clicked()
+                {
+                    opened.clone_from(folder);
+                }
+            })
+            .body(|ui| {
+                folder_tree(ui, folders, folder, opened);
+            });
+        } else if ui
+            .selectable_label(opened == folder, format!("📁 {name}"))
+            .clicked()
+        {
+            opened.clone_from(folder);
+        }
+    }
+}
+
+/// Fixed size cell, so grid of assets is aligned
+fn asset_cell(
+    ui: &mut egui::Ui,
+    add_contents: impl FnOnce(&mut egui::Ui) -> egui::Response,
+) -> egui::Response {
+    ui.allocate_ui(
+        egui::vec2(THUMBNAIL_SIZE + 16., THUMBNAIL_SIZE + 24.),
+        |ui| {
+            ui.set_width(THUMBNAIL_SIZE + 16.);
+            ui.vertical_centered(add_contents).inner
+        },
+    )
+    .inner
+}
+
+fn folder_button(ui: &mut egui::Ui, name: &str) -> egui::Response {
+    let response = ui.add_sized(
+        [THUMBNAIL_SIZE, THUMBNAIL_SIZE],
+        egui::Button::new(egui::RichText::new("📁").size(THUMBNAIL_SIZE / 2.)),
+    );
+    ui.add(egui::Label::new(name).truncate(true));
+    response
+}
+
+fn asset_button(
+    ui: &mut egui::Ui,
+    kind: AssetKind,
+    texture: Option<egui::TextureId>,
+    selected: bool,
+) -> egui::Response {
+    let size = egui::vec2(THUMBNAIL_SIZE, THUMBNAIL_SIZE);
+    let image = match (kind, texture) {
+        (_, Some(texture)) => Some(egui::Image::new((texture, size))),
+        (AssetKind::Scene, _) => Some(egui::Image::from_bytes(
+            "scene.svg",
+            icons::SCENE.as_bytes(),
+        )),
+        (AssetKind::Model, _) => Some(egui::Image::from_bytes("mesh.svg", icons::MESH.as_bytes())),
+        _ => None,
+    };
+    match image {
+        Some(image) => {
+            ui.add(egui::ImageButton::new(image.fit_to_exact_size(size)).selected(selected))
+        }
+        None => {
+            let icon = match kind {
+                AssetKind::Audio => "🔊",
+                AssetKind::Material => "🎨",
+                AssetKind::Backup => "🔙",
+                _ => "📄",
+            };
+            ui.add_sized(
+                size,
+                egui::SelectableLabel::new(
+                    selected,
+                    egui::RichText::new(icon).size(THUMBNAIL_SIZE / 2.),
+                ),
+            )
+        }
+    }
+}
+
+fn default_action(commands: &mut Commands, asset: &EditorAsset) {
+    match asset.kind() {
+        AssetKind::Scene => open_scene(commands, asset.asset_path()),
+        AssetKind::Model => spawn_asset(commands, asset),
+        AssetKind::Backup => restore_backup(commands, asset),
+        AssetKind::Material => assign_material(commands, asset),
+        AssetKind::Image | AssetKind::Audio | AssetKind::Other => {}
+    }
+}
+
+fn asset_context_menu(
+    ui: &mut egui::Ui,
+    commands: &mut Commands,
+    asset: &EditorAsset,
+    report: &mut Option<AssetReport>,
+) {
+    let path = asset.asset_path();
+    match asset.kind() {
+        AssetKind::Scene => {
+            if ui.button("Open").clicked() {
+                open_scene(commands, path.clone());
+                ui.close_menu();
+            }
+            if ui.button("Add to opened scenes").clicked() {
+                let path = path.clone();
+                commands.add(move |world: &mut World| {
+                    world.send_event(EditorEvent::LoadAdditive(path));
+                });
+                ui.close_menu();
+            }
+            if ui.button("Spawn as prefab").clicked() {
+                spawn_asset(commands, asset);
+                ui.close_menu();
+            }
+        }
+        AssetKind::Model => {
+            if ui.button("Spawn").clicked() {
+                spawn_asset(commands, asset);
+                ui.close_menu();
+            }
+            if ui.button("Open as prefab").clicked() {
+                let path = path.clone();
+                commands.add(move |world: &mut World| {
+                    world.send_event(EditorEvent::LoadGltfAsPrefab(path));
+                });
+                ui.close_menu();
+            }
+            if ui
+                .button("Open as prefab with materials")
+                .on_hover_text("Unpack materials of model into editable material components")
+                .clicked()
+            {
+                let path = path.clone();
+                commands.add(move |world: &mut World| {
+                    world.send_event(gltf_unpack::EditorUnpackGltf {
+                        path,
+                        extract_materials: true,
+                    });
+                });
+                ui.close_menu();
+            }
+            if ui
+                .button("Re-import")
+                .on_hover_text("Update entities opened as prefab from this model")
+                .clicked()
+            {
+                let path = path.clone();
+                commands.add(move |world: &mut World| {
+                    world.send_event(EditorEvent::ReimportGltf(path));
+                });
+                ui.close_menu();
+            }
+        }
+        AssetKind::Backup => {
+            if ui.button("Restore backup").clicked() {
+                restore_backup(commands, asset);
+                ui.close_menu();
+            }
+        }
+        AssetKind::Material => {
+            if ui.button("Assign to selected").clicked() {
+                assign_material(commands, asset);
+                ui.close_menu();
+            }
+        }
+        AssetKind::Image | AssetKind::Audio | AssetKind::Other => {}
+    }
+    if ui.button("Rename / move").clicked() {
+        *report = Some(AssetReport::move_asset(&path));
+        ui.close_menu();
+    }
+    if ui.button("Find usages").clicked() {
+        *report = Some(AssetReport::Usages(path.clone()));
+        ui.close_menu();
+    }
+    if ui.button("Copy path").clicked() {
+        ui.output_mut(|output| output.copied_text.clone_from(&path));
+        ui.close_menu();
+    }
+}
+
+/// Show report. Returns false if report is closed
+fn report_ui(
+    ui: &mut egui::Ui,
+    commands: &mut Commands,
+    world: &mut World,
+    report: &mut AssetReport,
+) -> bool {
+    let mut open = true;
+    match report {
+        AssetReport::Usages(file) => {
+            ui.horizontal(|ui| {
+                ui.strong(format!("Usages of {file}"));
+                if ui
+                    .small_button("⟳")
+                    .on_hover_text("Rescan scene files")
+                    .clicked()
+                {
+                    world.resource_scope::<AssetReferenceIndex, _>(|world, mut index| {
+                        index.scan(world);
+                    });
+                }
+                open = !ui.small_button("✖").clicked();
+            });
+            let usages = world
+                .resource::<AssetReferenceIndex>()
+                .usages(file)
+                .to_vec();
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                if usages.is_empty() {
+                    ui.label("Not used in any scene file");
+                }
+                for usage in usages {
+                    ui.horizontal(|ui| {
+                        if ui
+                            .link(usage.scene.as_str())
+                            .on_hover_text("Open scene")
+                            .clicked()
+                        {
+                            open_scene(commands, usage.scene.clone());
+                        }
+                        ui.label(reference_text(&usage));
+                    });
+                }
+            });
+        }
+        AssetReport::Missing(missing) => {
+            ui.horizontal(|ui| {
+                ui.strong(format!("Missing assets in opened scene: {}", missing.len()));
+                open = !ui.small_button("✖").clicked();
+            });
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                if missing.is_empty() {
+                    ui.label("All referenced assets are found");
+                }
+                for reference in missing.iter() {
+                    let entity = Entity::try_from_bits(reference.entity).ok();
+                    let name = entity.map_or_else(
+                        || "Unknown entity".to_string(),
+                        |entity| {
+                            world
+                                .get::<Name>(entity)
+                                .map_or_else(|| format!("{entity:?}"), ToString::to_string)
+                        },
+                    );
+                    ui.horizontal(|ui| {
+                        if ui.link(name).on_hover_text("Select entity").clicked() {
+                            if let Some(entity) = entity {
+                                select_entity(commands, entity);
+                            }
+                        }
+                        ui.label(egui::RichText::new(reference_text(reference)).color(ERROR_COLOR));
+                    });
+                }
+            });
+        }
+        AssetReport::Move { from, to } => {
+            ui.horizontal(|ui| {
+                ui.strong(format!("Rename / move {from}"));
+                open = !ui.small_button("✖").clicked();
+            });
+            let references = world
+                .resource::<AssetReferenceIndex>()
+                .scenes
+                .values()
+                .flatten()
+                .filter(|reference| moved_asset_path(&reference.asset, from, from).is_some())
+                .count();
+            ui.horizontal(|ui| {
+                ui.label("New path");
+                ui.add(egui::TextEdit::singleline(to).desired_width(300.));
+                let valid = !to.trim_matches('/').is_empty() && to != from;
+                if ui.add_enabled(valid, egui::Button::new("Move")).clicked() {
+                    let (from, to) = (from.clone(), to.clone());
+                    commands.add(move |world: &mut World| {
+                        match move_asset(world, &from, &to) {
+                            Ok(moved) => {
+                                world.send_event(ToastMessage::new(
+                                    &format!(
+                                        "Moved {from} to {to}. Updated {} scene files and {} components",
+                                        moved.scenes.len(),
+                                        moved.components
+                                    ),
+                                    ToastKind::Success,
+                                ));
+                            }
+                            Err(err) => {
+                                world.send_event(ToastMessage::new(
+                                    &format!("Failed to move {from}: {err}"),
+                                    ToastKind::Error,
+                                ));
+                            }
+                        }
+                        world.resource_mut::<DetectedAssets>().refresh();
+                    });
+                    open = false;
+                }
+            });
+            ui.label(format!(
+                "{references} references in scene files and references of loaded entities will be updated"
+            ));
+        }
+    }
+    open
+}
+
+fn reference_text(reference: &AssetReference) -> String {
+    format!(
+        "{}.{}: {}",
+        reference.component_name(),
+        reference.field,
+        reference.asset
+    )
+}
+
+fn select_entity(commands: &mut Commands, entity: Entity) {
+    commands.add(move |world: &mut World| {
+        let selected = world
+            .query_filtered::<Entity, With<Selected>>()
+            .iter(world)
+            .collect::<Vec<_>>();
+        for selected in selected {
+            world.entity_mut(selected).remove::<Selected>();
+        }
+        if let Some(mut entity) = world.get_entity_mut(entity) {
+            entity.insert(Selected);
+        }
+    });
+}
+
+fn open_scene(commands: &mut Commands, path: String) {
+    commands.add(move |world: &mut World| {
+        if let Some(stem) = path.strip_suffix(".scn.ron") {
+            world.send_event(MenuLoadEvent {
+                path: stem.to_string(),
+            });
+        } else {
+            world.send_event(EditorEvent::Load(EditorPrefabPath::File(path)));
+        }
+    });
+}
+
+fn spawn_asset(commands: &mut Commands, asset: &EditorAsset) {
+    let path = asset.asset_path();
+    commands.add(move |world: &mut World| {
+        spawn_asset_prefab(world, &path, Transform::default());
+    });
+}
+
+/// Replace materials of selected entities with material file
+fn assign_material(commands: &mut Commands, asset: &EditorAsset) {
+    let path = asset.asset_path();
+    commands.add(move |world: &mut World| {
+        let selected = world
+            .query_filtered::<Entity, With<Selected>>()
+            .iter(world)
+            .collect::<Vec<_>>();
+        if selected.is_empty() {
+            world.send_event(ToastMessage::new(
+                "Select entities to assign material",
+                ToastKind::Warning,
+            ));
+            return;
+        }
+        for entity in selected {
+            world
+                .entity_mut(entity)
+                .remove::<MaterialPrefab>()
+                .insert(AssetMaterial { path: path.clone() });
+        }
+    });
+}
+
+fn restore_backup(commands: &mut Commands, asset: &EditorAsset) {
+    let backup = format!("assets/{}", asset.asset_path());
+    commands.add(move |world: &mut World| {
+        world.send_event(RestorePrefabBackup { backup });
+    });
+}
@@ -0,0 +1,237 @@
+use std::{path::Path, sync::Arc};
+
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_egui::{egui, EguiContexts, EguiSettings};
+use space_prefab::{component::GltfPrefab, load::PrefabBundle, references::ASSETS_FOLDER};
+use space_shared::{
+    toast::{ToastKind, ToastMessage},
+    *,
+};
+use space_undo::{AddedEntity, NewChange};
+
+use crate::ui_plugin::{show_editor_ui, UiSystemSet};
+
+use super::AssetKind;
+
+/// Distance to spawn point along camera ray, when ray misses the ground plane
+const NO_GROUND_DISTANCE: f32 = 10.;
+
+/// Plugin to drop asset files from OS file manager into editor
+pub struct AssetDropPlugin;
+
+impl Plugin for AssetDropPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            receive_file_drops
+                .before(show_editor_ui)
+                .in_set(UiSystemSet),
+        );
+    }
+}
+
+/// Egui drag-and-drop payload with asset path, used by asset browser
+#[derive(Clone, Debug)]
+pub struct DraggedAsset(pub String);
+
+/// File dropped from OS file manager, waiting for drop target under cursor
+#[derive(Clone, Debug)]
+struct OsFileDrop {
+    path: String,
+    pos: egui::Pos2,
+}
+
+fn os_drop_id() -> egui::Id {
+    egui::Id::new("space_editor_os_file_drop")
+}
+
+fn receive_file_drops(
+    mut events: EventReader<FileDragAndDrop>,
+    mut ctxs: EguiContexts,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    egui_settings: Res<EguiSettings>,
+    mut toasts: EventWriter<ToastMessage>,
+) {
+    let ctx = ctxs.ctx_mut();
+    // Drop not taken by any target in last frame is ignored
+    ctx.data_mut(|data| data.remove::<OsFileDrop>(os_drop_id()));
+
+    for event in events.read() {
+        let FileDragAndDrop::DroppedFile { window, path_buf } = event else {
+            continue;
+        };
+        let Some(path) = dropped_asset_path(Path::new(ASSETS_FOLDER), path_buf) else {
+            toasts.send(ToastMessage::new(
+                &format!(
+                    "Dropped file must be inside assets folder: {}",
+                    path_buf.display()
+                ),
+                ToastKind::Error,
+            ));
+            continue;
+        };
+        let Some(cursor) = windows
+            .get(*window)
+            .ok()
+            .and_then(|window| window.cursor_position())
+        else {
+            continue;
+        };
+        let cursor = cursor / egui_settings.scale_factor;
+        ctx.data_mut(|data| {
+            data.insert_temp(
+                os_drop_id(),
+                OsFileDrop {
+                    path,
+                    pos: egui::pos2(cursor.x, cursor.y),
+                },
+            );
+        });
+    }
+}
+
+/// Path of dropped file relative to project `assets` folder.
+/// Returns `None` if file is outside of assets folder
+pub fn dropped_asset_path(assets: &Path, file: &Path) -> Option<String> {
+    let assets = assets.canonicalize().ok()?;
+    let file = file.canonicalize().ok()?;
+    let path = file.strip_prefix(assets).ok()?;
+    Some(path.to_string_lossy().replace('\\', "/"))
+}
+
+/// Asset path and position of asset dropped onto `response` this frame.
+/// Asset can be dragged from asset browser or dropped from OS file manager
+pub fn dropped_asset(ui: &egui::Ui, response: &egui::Response) -> Option<(String, egui::Pos2)> {
+    if let Some(asset) = response.dnd_release_payload::<DraggedAsset>() {
+        let pos = ui
+            .ctx()
+            .pointer_interact_pos()
+            .unwrap_or_else(|| response.rect.center());
+        return Some((asset.0.clone(), pos));
+    }
+
+    let drop = ui
+        .ctx()
+        .data(|data| data.get_temp::<OsFileDrop>(os_drop_id()))?;
+    if !response.rect.contains(drop.pos) || !ui.clip_rect().contains(drop.pos) {
+        return None;
+    }
+    ui.ctx()
+        .data_mut(|data| data.remove::<OsFileDrop>(os_drop_id()));
+    Some((drop.path, drop.pos))
+}
+
+/// Spawn point of dropped asset: camera ray hit with ground plane
+pub fn ground_hit(ray: Ray3d) -> Vec3 {
+    ray.intersect_plane(Vec3::ZERO, Plane3d::new(Vec3::Y))
+        .map_or_else(
+            || ray.get_point(NO_GROUND_DISTANCE),
+            |distance| ray.get_point(distance),
+        )
+}
+
+/// Spawn scene or model dropped into game view at `viewport_pos` of editor camera
+pub fn spawn_dropped_asset(world: &mut World, path: &str, viewport_pos: Vec2) {
+    let kind = AssetKind::from_path(path);
+    if !matches!(kind, AssetKind::Scene | AssetKind::Model) {
+        world.send_event(ToastMessage::new(
+            &format!("Only scenes and models can be dropped into game view: {path}"),
+            ToastKind::Warning,
+        ));
+        return;
+    }
+
+    let mut cameras =
+        world.query_filtered::<(&Camera, &GlobalTransform), With<EditorCameraMarker>>();
+    let translation = cameras
+        .iter(world)
+        .find(|(camera, _)| camera.is_active)
+        .and_then(|(camera, transform)| camera.viewport_to_world(transform, viewport_pos))
+        .map(ground_hit)
+        .unwrap_or_default();
+
+    spawn_asset_prefab(world, path, Transform::from_translation(translation));
+}
+
+/// Spawn scene file as [`PrefabBundle`] or model as [`GltfPrefab`]
+pub fn spawn_asset_prefab(world: &mut World, path: &str, transform: Transform) -> Entity {
+    let name = path
+        .rsplit('/')
+        .next()
+        .and_then(|file| file.split('.').next())
+        .unwrap_or_default()
+        .to_string();
+    let mut entity = world.spawn((PrefabMarker, Name::new(name)));
+    if AssetKind::from_path(path) == AssetKind::Scene {
+        entity.insert(PrefabBundle::new(path));
+    } else {
+        entity.insert((
+            SpatialBundle::default(),
+            GltfPrefab {
+                path: path.into(),
+                scene: "Scene0".into(),
+            },
+        ));
+    }
+    entity.insert(transform);
+    let entity = entity.id();
+    world.send_event(NewChange {
+        change: Arc::new(AddedEntity { entity }),
+    });
+    entity
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dropped_asset_lands_on_ground() {
+        let ray = Ray3d::new(Vec3::new(1., 10., 0.), Vec3::new(0., -1., 1.));
+        let hit = ground_hit(ray);
+        assert!(hit.abs_diff_eq(Vec3::new(1., 0., 10.), 1e-4));
+
+        let sky = Ray3d::new(Vec3::new(0., 1., 0.), Vec3::Y);
+        assert_eq!(ground_hit(sky), Vec3::new(0., 1. + NO_GROUND_DISTANCE, 0.));
+    }
+
+    #[test]
+    fn dropped_file_must_be_in_project_assets() {
+        let dir = std::env::temp_dir().join(format!("space_drop_{}", std::process::id()));
+        let assets = dir.join("project/assets");
+        for file in [
+            "project/assets/models/car.glb",
+            "project/assets/textures/assets/grid.png",
+            "other/assets/car.glb",
+        ] {
+            let file = dir.join(file);
+            std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+            std::fs::write(file, "").unwrap();
+        }
+
+        assert_eq!(
+            dropped_asset_path(&assets, &dir.join("project/assets/models/car.glb")),
+            Some("models/car.glb".to_string())
+        );
+        assert_eq!(
+            dropped_asset_path(
+                &assets,
+                &dir.join("project/assets/textures/assets/grid.png")
+            ),
+            Some("textures/assets/grid.png".to_string())
+        );
+        assert_eq!(
+            dropped_asset_path(&assets, &dir.join("other/assets/car.glb")),
+            None
+        );
+        assert_eq!(
+            dropped_asset_path(
+                &assets,
+                &dir.join("project/assets/../../other/assets/car.glb")
+            ),
+            None
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,49 +1,257 @@
-use bevy::prelude::*;
+pub mod browser;
+pub mod drop;
+
+use bevy::{
+    prelude::*,
+    tasks::{block_on, poll_once, AsyncComputeTaskPool, Task},
+};
 use std::fs;
 use std::path::Path;
 
-///Not used right now. Planned to be UI inspector for all assets in asset/ folder
+pub use browser::{open_asset_browser, AssetBrowserPlugin, AssetBrowserTab};
+pub use drop::{dropped_asset, AssetDropPlugin, DraggedAsset};
+
+/// Seconds between scans of assets folder
+const REFRESH_INTERVAL: f32 = 2.;
+
+/// Kind of asset file, detected by its extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssetKind {
+    Scene,
+    Model,
+    Material,
+    Image,
+    Audio,
+    Backup,
+    Other,
+}
+
+impl AssetKind {
+    pub const ALL: [Self; 7] = [
+        Self::Scene,
+        Self::Model,
+        Self::Material,
+        Self::Image,
+        Self::Audio,
+        Self::Backup,
+        Self::Other,
+    ];
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Scene => "Scenes",
+            Self::Model => "Models",
+            Self::Material => "Materials",
+            Self::Image => "Images",
+            Self::Audio => "Audio",
+            Self::Backup => "Backups",
+            Self::Other => "Other",
+        }
+    }
+
+    pub fn from_path(path: &str) -> Self {
+        let path = path.to_lowercase();
+        let ext = path.rsplit('.').next().unwrap_or_default();
+        if path.ends_with(".scn.ron") || path.ends_with(".scn.bin") {
+            Self::Scene
+        } else if path.ends_with(".mat.ron") {
+            Self::Material
+        } else if ext.starts_with("bak") && ext[3..].parse::<usize>().is_ok() {
+            Self::Backup
+        } else {
+            match ext {
+                "gltf" | "glb" => Self::Model,
+                "png" | "jpg" | "jpeg" | "bmp" | "tga" | "ktx2" | "dds" | "hdr" | "exr" => {
+                    Self::Image
+                }
+                "ogg" | "wav" | "mp3" | "flac" => Self::Audio,
+                _ => Self::Other,
+            }
+        }
+    }
+}
+
+/// File inside assets folder
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EditorAsset {
+    /// Path from working directory, like `assets/models/cube.glb`
     pub path: String,
     pub ext: String,
 }
 
-///Not used right now. Planned to be UI inspector for all assets in asset/ folder
-#[derive(Resource, Default)]
+impl EditorAsset {
+    /// Path relative to assets folder, as it is used by asset server
+    pub fn asset_path(&self) -> String {
+        let path = self.path.replace('\\', "/");
+        path.split_once("assets/")
+            .map_or_else(|| path.clone(), |(_, path)| path.to_string())
+    }
+
+    /// Folder relative to assets folder. Empty for files in assets folder itself
+    pub fn folder(&self) -> String {
+        self.asset_path()
+            .rsplit_once('/')
+            .map(|(folder, _)| folder.to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn file_name(&self) -> String {
+        let path = self.asset_path();
+        path.rsplit('/').next().unwrap_or(&path).to_string()
+    }
+
+    pub fn kind(&self) -> AssetKind {
+        AssetKind::from_path(&self.path)
+    }
+}
+
+/// Files and folders found by background scan of assets folder
+type ScannedAssets = (Vec<EditorAsset>, Vec<String>);
+
+/// All files and folders in assets folder. Rescanned periodically while asset browser is shown
+#[derive(Resource)]
 pub struct DetectedAssets {
     pub assets: Vec<EditorAsset>,
+    /// Folders relative to assets folder
+    pub folders: Vec<String>,
+    refresh: Timer,
+    force_refresh: bool,
+    /// Asset browser was shown since last periodic scan
+    shown: bool,
+    scan: Option<Task<ScannedAssets>>,
 }
 
-///Not used right now. Planned to be UI inspector for all assets in asset/ folder
+impl Default for DetectedAssets {
+    fn default() -> Self {
+        Self {
+            assets: vec![],
+            folders: vec![],
+            refresh: Timer::from_seconds(REFRESH_INTERVAL, TimerMode::Repeating),
+            force_refresh: true,
+            shown: false,
+            scan: None,
+        }
+    }
+}
+
+impl DetectedAssets {
+    /// Rescan assets folder on next update
+    pub fn refresh(&mut self) {
+        self.force_refresh = true;
+    }
+}
+
+/// Plugin to keep [`DetectedAssets`] in sync with assets folder
 pub struct AssetDetectorPlugin;
 
 impl Plugin for AssetDetectorPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<DetectedAssets>();
 
-        app.add_systems(Startup, detect_assets.before(crate::ui_camera_block));
+        app.add_systems(Update, detect_assets);
     }
 }
 
-fn detect_assets(mut assets: ResMut<DetectedAssets>) {
-    get_assets_in_directory(Path::new("assets"), &mut assets.assets);
+fn detect_assets(mut detected: ResMut<DetectedAssets>, time: Res<Time>) {
+    // Bookkeeping does not trigger change detection, only changes on disk do
+    let state = detected.bypass_change_detection();
+    if let Some(scan) = state.scan.as_mut() {
+        let Some((assets, folders)) = block_on(poll_once(scan)) else {
+            return;
+        };
+        state.scan = None;
+        if detected.assets != assets || detected.folders != folders {
+            detected.assets = assets;
+            detected.folders = folders;
+        }
+    }
+
+    let state = detected.bypass_change_detection();
+    let tick = state.refresh.tick(time.delta()).just_finished();
+    // Folder is not scanned periodically while nobody looks at it
+    let periodic = tick && std::mem::take(&mut state.shown);
+    if !periodic && !state.force_refresh {
+        return;
+    }
+    state.force_refresh = false;
+    state.scan = Some(AsyncComputeTaskPool::get().spawn(async {
+        let mut assets = vec![];
+        let mut folders = vec![];
+        get_assets_in_directory(Path::new("assets"), &mut assets, &mut folders);
+        (assets, folders)
+    }));
 }
 
-fn get_assets_in_directory(dir_path: &Path, assets: &mut Vec<EditorAsset>) {
-    if let Ok(entries) = fs::read_dir(dir_path) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.is_file() {
-                if let Some(ext) = path.extension() {
-                    let editor_asset = EditorAsset {
-                        path: path.to_str().unwrap().to_string(),
-                        ext: ext.to_str().unwrap().to_string(),
-                    };
-                    assets.push(editor_asset);
-                }
-            } else if path.is_dir() {
-                get_assets_in_directory(&path, assets);
+fn get_assets_in_directory(
+    dir_path: &Path,
+    assets: &mut Vec<EditorAsset>,
+    folders: &mut Vec<String>,
+) {
+    let Ok(entries) = fs::read_dir(dir_path) else {
+        return;
+    };
+    let mut entries = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .collect::<Vec<_>>();
+    entries.sort();
+    for path in entries {
+        let hidden = path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        if hidden {
+            continue;
+        }
+        if path.is_file() {
+            if let Some(ext) = path.extension() {
+                let editor_asset = EditorAsset {
+                    path: path.to_string_lossy().to_string(),
+                    ext: ext.to_string_lossy().to_string(),
+                };
+                assets.push(editor_asset);
             }
+        } else if path.is_dir() {
+            let folder = EditorAsset {
+                path: path.to_string_lossy().to_string(),
+                ext: String::new(),
+            };
+            folders.push(folder.asset_path());
+            get_assets_in_directory(&path, assets, folders);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn asset_kind_by_extension() {
+        assert_eq!(
+            AssetKind::from_path("assets/scenes/level.scn.ron"),
+            AssetKind::Scene
+        );
+        assert_eq!(AssetKind::from_path("models/Cube.GLB"), AssetKind::Model);
+        assert_eq!(AssetKind::from_path("textures/grid.png"), AssetKind::Image);
+        assert_eq!(
+            AssetKind::from_path("materials/brick.mat.ron"),
+            AssetKind::Material
+        );
+        assert_eq!(
+            AssetKind::from_path("scenes/level.scn.ron.bak2"),
+            AssetKind::Backup
+        );
+        assert_eq!(AssetKind::from_path("scenes/notes.txt"), AssetKind::Other);
+    }
+
+    #[test]
+    fn asset_paths_are_relative_to_assets_folder() {
+        let asset = EditorAsset {
+            path: "assets\\models\\car\\car.glb".to_string(),
+            ext: "glb".to_string(),
+        };
+        assert_eq!(asset.asset_path(), "models/car/car.glb");
+        assert_eq!(asset.folder(), "models/car");
+        assert_eq!(asset.file_name(), "car.glb");
+    }
+}
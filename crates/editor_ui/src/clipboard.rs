@@ -0,0 +1,76 @@
+use std::any::TypeId;
+
+use bevy::prelude::*;
+use bevy_egui::EguiClipboard;
+use space_editor_core::prelude::*;
+use space_prefab::clipboard::{copy_components, copy_entities, paste_components, paste_entities};
+use space_shared::toast::{ToastKind, ToastMessage};
+
+fn report_error(world: &mut World, message: String) {
+    error!("{}", message);
+    world.send_event(ToastMessage::new(&message, ToastKind::Error));
+}
+
+fn set_clipboard(world: &mut World, copied: Result<String, String>) {
+    match copied {
+        Ok(text) => match world.get_resource_mut::<EguiClipboard>() {
+            Some(mut clipboard) => clipboard.set_contents(&text),
+            None => report_error(world, "System clipboard is not available".to_string()),
+        },
+        Err(err) => report_error(world, format!("Failed to copy: {err}")),
+    }
+}
+
+fn get_clipboard(world: &mut World) -> Option<String> {
+    let text = world
+        .get_resource::<EguiClipboard>()
+        .and_then(EguiClipboard::get_contents);
+    if text.is_none() {
+        report_error(world, "Clipboard is empty".to_string());
+    }
+    text
+}
+
+/// Copy entities with their children to system clipboard
+pub fn copy_entities_to_clipboard(world: &mut World, entities: &[Entity]) {
+    let copied = copy_entities(world, entities);
+    set_clipboard(world, copied);
+}
+
+/// Copy components of entity to system clipboard
+pub fn copy_components_to_clipboard(world: &mut World, entity: Entity, components: &[TypeId]) {
+    let copied = copy_components(world, entity, components);
+    set_clipboard(world, copied);
+}
+
+/// Spawn entities from system clipboard and select them
+pub fn paste_entities_from_clipboard(world: &mut World) {
+    let Some(text) = get_clipboard(world) else {
+        return;
+    };
+    match paste_entities(world, &text) {
+        Ok(pasted) => {
+            let selected = world
+                .query_filtered::<Entity, With<Selected>>()
+                .iter(world)
+                .collect::<Vec<_>>();
+            for entity in selected {
+                world.entity_mut(entity).remove::<Selected>();
+            }
+            for entity in pasted {
+                world.entity_mut(entity).insert(Selected);
+            }
+        }
+        Err(err) => report_error(world, format!("Failed to paste entities: {err}")),
+    }
+}
+
+/// Insert components from system clipboard into `targets`
+pub fn paste_components_from_clipboard(world: &mut World, targets: &[Entity]) {
+    let Some(text) = get_clipboard(world) else {
+        return;
+    };
+    if let Err(err) = paste_components(world, &text, targets) {
+        report_error(world, format!("Failed to paste components: {err}"));
+    }
+}
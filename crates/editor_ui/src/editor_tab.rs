@@ -17,6 +17,7 @@ pub trait EditorTab {
 
 #[derive(Clone, Hash, PartialEq, Eq, Debug, PartialOrd, Ord)]
 pub enum EditorTabName {
+    AssetBrowser,
     CameraView,
     EventDispatcher,
     GameView,
@@ -24,6 +25,7 @@ pub enum EditorTabName {
     Inspector,
     Resource,
     RuntimeAssets,
+    SceneSettings,
     Settings,
     ToolBox,
     Other(String),
@@ -5,7 +5,12 @@ use space_undo::UndoRedo;
 
 use space_shared::*;
 
-use crate::{colors::TEXT_COLOR, prelude::EditorTabName, EditorUiAppExt};
+use crate::{
+    asset_inspector::{drop::spawn_dropped_asset, dropped_asset},
+    colors::TEXT_COLOR,
+    prelude::EditorTabName,
+    EditorUiAppExt,
+};
 
 use super::{editor_tab::EditorTab, tool::EditorTool};
 
@@ -52,6 +57,16 @@ impl EditorTab for GameViewTab {
 
         self.viewport_rect = Some(ui.clip_rect());
 
+        // Scenes and models dropped into viewport are spawned under cursor
+        let viewport = ui.clip_rect();
+        let drop_zone = ui.interact(viewport, ui.id().with("asset_drop"), egui::Sense::hover());
+        if let Some((path, pos)) = dropped_asset(ui, &drop_zone) {
+            let viewport_pos = Vec2::new(pos.x - viewport.min.x, pos.y - viewport.min.y);
+            commands.add(move |world: &mut World| {
+                spawn_dropped_asset(world, &path, viewport_pos);
+            });
+        }
+
         ui.horizontal(|ui| {
             ui.style_mut().visuals.override_text_color = Some(TEXT_COLOR);
 
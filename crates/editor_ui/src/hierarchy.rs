@@ -7,12 +7,23 @@ use bevy_egui::{
     *,
 };
 use space_editor_core::prelude::*;
-use space_prefab::{component::SceneAutoChild, editor_registry::EditorRegistry};
+use space_prefab::{
+    component::SceneAutoChild,
+    editor_registry::EditorRegistry,
+    load::PrefabLoader,
+    overrides::{ApplyPrefabInstance, RevertPrefabInstance},
+    save::CreatePrefabFromEntities,
+    scenes::{ActiveEditorScene, EditorScene, SaveEditorScene},
+};
 use space_undo::{AddedEntity, NewChange, RemovedEntity, UndoSet};
 
 use space_shared::*;
 
-use super::{editor_tab::EditorTabName, EditorUiAppExt, EditorUiRef};
+use super::{
+    clipboard::{copy_entities_to_clipboard, paste_entities_from_clipboard},
+    editor_tab::EditorTabName,
+    EditorUiAppExt, EditorUiRef,
+};
 
 pub const WARN_COLOR: egui::Color32 = egui::Color32::from_rgb(225, 206, 67);
 
@@ -48,6 +59,11 @@ pub struct HierarchyTabState {
     pub show_editor_entities: bool,
     pub show_spawnable_bundles: bool,
     pub entity_filter: String,
+    /// Entity from context menu, for which "Save as prefab" dialog must be opened
+    pub save_as_prefab: Option<Entity>,
+    /// Prefab instance, whose revert waits for confirmation
+    pub revert_prefab: Option<Entity>,
+    save_prefab_dialog: Option<(Vec<Entity>, egui_file::FileDialog)>,
 }
 
 pub type HierarchyQueryIter<'a> = (
@@ -68,6 +84,9 @@ pub fn show_hierarchy(
     mut changes: EventWriter<NewChange>,
     mut state: ResMut<HierarchyTabState>,
     auto_children: Query<(), With<SceneAutoChild>>,
+    prefab_instances: Query<(), With<PrefabLoader>>,
+    scenes: Query<(), With<EditorScene>>,
+    active_scene: Res<ActiveEditorScene>,
 ) {
     let mut all: Vec<_> = if state.show_editor_entities {
         all_entities.iter().collect()
@@ -94,6 +113,87 @@ pub fn show_hierarchy(
     ui.spacing();
     let lower_filter = state.entity_filter.to_lowercase();
 
+    if let Some(entity) = state.save_as_prefab.take() {
+        // Whole selection is saved, if context menu was opened on selected entity
+        let entities = if selected.contains(entity) {
+            selected.iter().collect()
+        } else {
+            vec![entity]
+        };
+        let mut dialog = egui_file::FileDialog::save_file(Some("./assets/scenes".into()))
+            .default_filename("Prefab.scn.ron")
+            .title("Save as prefab");
+        dialog.open();
+        state.save_prefab_dialog = Some((entities, dialog));
+    }
+    if let Some((entities, dialog)) = &mut state.save_prefab_dialog {
+        if dialog.show(ui.ctx()).selected() {
+            if let Some(file) = dialog.path() {
+                let path = file.to_str().unwrap().to_string();
+                //path must be relative to assets folder
+                if let Some((_, path)) = path.rsplit_once("assets/") {
+                    let mut path = path.to_string();
+                    if !path.ends_with(".scn.ron") {
+                        path = format!("{}.scn.ron", path.trim_end_matches(".ron"));
+                    }
+                    let entities = entities.clone();
+                    commands.add(move |world: &mut World| {
+                        world.send_event(CreatePrefabFromEntities { entities, path });
+                    });
+                } else {
+                    error!("Prefab must be saved inside assets folder");
+                }
+            }
+            state.save_prefab_dialog = None;
+        } else if !matches!(dialog.state(), egui_file::State::Open) {
+            state.save_prefab_dialog = None;
+        }
+    }
+
+    if let Some(entity) = state.revert_prefab {
+        let mut answer = None;
+        egui::Window::new("Revert to prefab")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0., 0.])
+            .show(ui.ctx(), |ui| {
+                ui.label("All changes of prefab instance will be lost. Revert can not be undone.");
+                ui.horizontal(|ui| {
+                    if ui.button("Revert").clicked() {
+                        answer = Some(true);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        answer = Some(false);
+                    }
+                });
+            });
+        if let Some(revert) = answer {
+            state.revert_prefab = None;
+            if revert {
+                commands.add(move |world: &mut World| {
+                    world.send_event(RevertPrefabInstance { entity });
+                });
+            }
+        }
+    }
+
+    // Clipboard shortcuts work while pointer is over hierarchy and no text is edited
+    if ui.ui_contains_pointer() && !ui.ctx().wants_keyboard_input() {
+        let (copy, paste) = ui.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::COMMAND, egui::Key::C),
+                i.consume_key(egui::Modifiers::COMMAND, egui::Key::V),
+            )
+        });
+        if copy && !selected.is_empty() {
+            let entities = selected.iter().collect::<Vec<_>>();
+            commands.add(move |world: &mut World| copy_entities_to_clipboard(world, &entities));
+        }
+        if paste {
+            commands.add(paste_entities_from_clipboard);
+        }
+    }
+
     egui::ScrollArea::vertical().show(ui, |ui| {
         for (entity, _name, _children, parent) in all.iter().filter(|(_, name, _, _)| {
             name.map(|n| n.to_lowercase())
@@ -111,6 +211,9 @@ pub fn show_hierarchy(
                         &mut clone_events,
                         &mut changes,
                         &auto_children,
+                        &prefab_instances,
+                        &scenes,
+                        active_scene.0,
                     );
                 } else {
                     draw_entity::<With<PrefabMarker>>(
@@ -122,6 +225,9 @@ pub fn show_hierarchy(
                         &mut clone_events,
                         &mut changes,
                         &auto_children,
+                        &prefab_instances,
+                        &scenes,
+                        active_scene.0,
                     );
                 }
             }
@@ -145,15 +251,23 @@ fn draw_entity<F: QueryFilter>(
     clone_events: &mut EventWriter<CloneEvent>,
     changes: &mut EventWriter<NewChange>,
     auto_children: &Query<(), With<SceneAutoChild>>,
+    prefab_instances: &Query<(), With<PrefabLoader>>,
+    scenes: &Query<(), With<EditorScene>>,
+    active_scene: Option<Entity>,
 ) {
     let Ok((_, name, children, parent)) = query.get(entity) else {
         return;
     };
 
-    let entity_name = name.map_or_else(
+    let mut entity_name = name.map_or_else(
         || format!("Entity ({:?})", entity),
         |name| format!("{} ({:?})", name.as_str(), entity),
     );
+    let is_scene = scenes.contains(entity);
+    if is_scene {
+        entity_name = format!("🎬 {entity_name}");
+    }
+    let is_active_scene = active_scene == Some(entity);
 
     let is_selected = selected.contains(entity);
 
@@ -169,6 +283,9 @@ fn draw_entity<F: QueryFilter>(
             if is_auto_child {
                 entity_name = entity_name.italics();
             }
+            if is_active_scene {
+                entity_name = entity_name.strong();
+            }
 
             let response = ui.selectable_label(is_selected, entity_name);
             let is_clicked = response.clicked();
@@ -189,6 +306,8 @@ fn draw_entity<F: QueryFilter>(
                         clone_events,
                         selected,
                         parent,
+                        prefab_instances.contains(entity),
+                        is_scene,
                     );
                 });
             }
@@ -221,6 +340,9 @@ fn draw_entity<F: QueryFilter>(
                     clone_events,
                     changes,
                     auto_children,
+                    prefab_instances,
+                    scenes,
+                    active_scene,
                 );
             }
         });
@@ -230,6 +352,9 @@ fn draw_entity<F: QueryFilter>(
         if is_auto_child {
             entity_name = entity_name.italics();
         }
+        if is_active_scene {
+            entity_name = entity_name.strong();
+        }
 
         let selectable = ui.selectable_label(is_selected, entity_name);
         let is_clicked = selectable.clicked();
@@ -251,6 +376,8 @@ fn draw_entity<F: QueryFilter>(
                     clone_events,
                     selected,
                     parent,
+                    prefab_instances.contains(entity),
+                    is_scene,
                 );
             });
         }
@@ -282,6 +409,8 @@ fn hierarchy_entity_context(
     clone_events: &mut EventWriter<'_, CloneEvent>,
     selected: &mut Query<'_, '_, Entity, With<Selected>>,
     parent: Option<&Parent>,
+    is_prefab_instance: bool,
+    is_scene: bool,
 ) {
     if ui.button("Add child").clicked() {
         let new_id = commands.spawn_empty().insert(PrefabMarker).id();
@@ -302,6 +431,24 @@ fn hierarchy_entity_context(
         clone_events.send(CloneEvent { id: entity });
         ui.close_menu();
     }
+    if ui
+        .button("Copy")
+        .on_hover_text("Copy to clipboard with children")
+        .clicked()
+    {
+        // Whole selection is copied, if context menu was opened on selected entity
+        let entities = if selected.contains(entity) {
+            selected.iter().collect()
+        } else {
+            vec![entity]
+        };
+        commands.add(move |world: &mut World| copy_entities_to_clipboard(world, &entities));
+        ui.close_menu();
+    }
+    if ui.button("Paste").clicked() {
+        commands.add(paste_entities_from_clipboard);
+        ui.close_menu();
+    }
     if !selected.is_empty() && !selected.contains(entity) && ui.button("Attach to").clicked() {
         for e in selected.iter() {
             commands.entity(entity).add_child(e);
@@ -310,6 +457,55 @@ fn hierarchy_entity_context(
     if parent.is_some() && ui.button("Detach").clicked() {
         commands.entity(entity).remove_parent();
     }
+    // Scene root is saved as scene file
+    if !is_scene && ui.button("Save as prefab…").clicked() {
+        commands.add(move |world: &mut World| {
+            world.resource_mut::<HierarchyTabState>().save_as_prefab = Some(entity);
+        });
+        ui.close_menu();
+    }
+    if is_scene {
+        ui.separator();
+        if ui
+            .button("Set active scene")
+            .on_hover_text("New entities are added to active scene")
+            .clicked()
+        {
+            commands.add(move |world: &mut World| {
+                world.resource_mut::<ActiveEditorScene>().0 = Some(entity);
+            });
+            ui.close_menu();
+        }
+        if ui.button("Save scene").clicked() {
+            commands.add(move |world: &mut World| {
+                world.send_event(SaveEditorScene::Scene(entity));
+            });
+            ui.close_menu();
+        }
+    }
+    if is_prefab_instance {
+        ui.separator();
+        if ui
+            .button("Apply to prefab")
+            .on_hover_text("Write instance changes to prefab file")
+            .clicked()
+        {
+            commands.add(move |world: &mut World| {
+                world.send_event(ApplyPrefabInstance { entity });
+            });
+            ui.close_menu();
+        }
+        if ui
+            .button("Revert to prefab…")
+            .on_hover_text("Discard instance changes")
+            .clicked()
+        {
+            commands.add(move |world: &mut World| {
+                world.resource_mut::<HierarchyTabState>().revert_prefab = Some(entity);
+            });
+            ui.close_menu();
+        }
+    }
 }
 
 #[derive(Component)]
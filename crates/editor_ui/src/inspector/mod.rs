@@ -3,26 +3,38 @@ pub mod events_dispatcher;
 pub mod refl_impl;
 pub mod resources;
 pub mod runtime_assets;
+pub mod scene_settings;
 
 use std::any::TypeId;
 
 use bevy::{
-    ecs::{change_detection::MutUntyped, system::CommandQueue},
+    ecs::{
+        change_detection::MutUntyped, component::ComponentId, system::CommandQueue,
+        world::unsafe_world_cell::UnsafeWorldCell,
+    },
     prelude::*,
     ptr::PtrMut,
-    reflect::ReflectFromPtr,
+    reflect::{GetPath, ReflectFromPtr, ReflectRef, TypeRegistry},
     utils::HashMap,
 };
 
 use bevy_egui::{egui::TextEdit, *};
 
 use space_editor_core::prelude::*;
-use space_prefab::{component::EntityLink, editor_registry::EditorRegistry};
-use space_shared::ext::bevy_inspector_egui::{
-    self, inspector_egui_impls::InspectorEguiImpl, reflect_inspector::InspectorUi,
+use space_prefab::{
+    component::{EntityLink, MaterialPrefab},
+    editor_registry::EditorRegistry,
+    material_file::{extract_material_to_file, MATERIAL_EXTENSION},
+};
+use space_shared::{
+    ext::bevy_inspector_egui::{
+        self, inspector_egui_impls::InspectorEguiImpl, reflect_inspector::InspectorUi,
+    },
+    toast::{ToastKind, ToastMessage},
 };
 
 use crate::{
+    clipboard::{copy_components_to_clipboard, paste_components_from_clipboard},
     colors::DEFAULT_BG_COLOR,
     icons::add_component_icon,
     sizing::{to_label, Sizing},
@@ -31,9 +43,13 @@ use crate::{
 use self::{
     components_order::{ComponentsOrder, ComponentsPriority},
     events_dispatcher::EventDispatcherTab,
-    refl_impl::{entity_ref_ui, entity_ref_ui_readonly, many_unimplemented},
+    refl_impl::{
+        entity_ref_ui, entity_ref_ui_readonly, many_unimplemented, register_asset_path_ui,
+        string_ui, string_ui_many, string_ui_readonly,
+    },
     resources::ResourceTab,
     runtime_assets::RuntimeAssetsTab,
+    scene_settings::SceneSettingsTab,
 };
 
 use super::{
@@ -63,6 +79,7 @@ impl Plugin for SpaceInspectorPlugin {
             EventDispatcherTab::default(),
         );
         app.editor_tab_by_trait(EditorTabName::RuntimeAssets, RuntimeAssetsTab::default());
+        app.editor_tab_by_trait(EditorTabName::SceneSettings, SceneSettingsTab::default());
 
         app.add_systems(Update, execute_inspect_command);
 
@@ -74,16 +91,19 @@ impl Plugin for SpaceInspectorPlugin {
 pub struct InspectorTab {
     open_components: HashMap<String, bool>,
     show_all_components: bool,
+    /// File for "Extract material to file" action
+    material_file: String,
 }
 
 impl EditorTab for InspectorTab {
     fn ui(&mut self, ui: &mut egui::Ui, _: &mut Commands, world: &mut World) {
         let sizing = world.resource::<Sizing>().clone();
-        let selected_entity = world
+        let selected = world
             .query_filtered::<Entity, With<Selected>>()
-            .get_single(world);
+            .iter(world)
+            .collect::<Vec<_>>();
 
-        let Ok(selected_entity) = selected_entity else {
+        let Some(&selected_entity) = selected.first() else {
             return;
         };
 
@@ -122,6 +142,14 @@ impl EditorTab for InspectorTab {
         components_id.sort_by(|(.., name_a, priority_a), (.., name_b, priority_b)| {
             priority_a.cmp(priority_b).then(name_a.cmp(name_b))
         });
+        // Several selected entities are edited by their shared components
+        if selected.len() > 1 {
+            components_id.retain(|(c_id, ..)| {
+                selected
+                    .iter()
+                    .all(|entity| world.entity(*entity).contains_id(*c_id))
+            });
+        }
 
         let cell = world.as_unsafe_world_cell();
         let mut state = unsafe { cell.get_resource_mut::<InspectState>().unwrap() };
@@ -153,7 +181,19 @@ impl EditorTab for InspectorTab {
             (add_component_width - add_component_pixel_count - 16. - sizing.icon.to_size()) / 2.;
 
         let components_area = egui::ScrollArea::vertical().show(ui, |ui| {
-            if let Some(e) = cell.get_entity(selected_entity) {
+            if selected.len() > 1 {
+                self.show_shared_components(
+                    ui,
+                    &cell,
+                    &selected,
+                    &components_id,
+                    &mut env,
+                    &editor_registry_resource,
+                    &editor_registry,
+                    &app_registry,
+                    &mut commands,
+                );
+            } else if let Some(e) = cell.get_entity(selected_entity) {
                 let mut name;
                 if let Some(name_struct) = unsafe { e.get::<Name>() } {
                     name = name_struct.as_str().to_string();
@@ -219,6 +259,7 @@ impl EditorTab for InspectorTab {
                                                 &mut env,
                                                 value,
                                                 &mut set_changed,
+                                                &mut commands,
                                             );
 
                                             ui.push_id(
@@ -252,6 +293,7 @@ impl EditorTab for InspectorTab {
                                             &mut env,
                                             value,
                                             &mut set_changed,
+                                            &mut commands,
                                         );
                                         ui.end_row();
                                     }
@@ -337,7 +379,11 @@ impl EditorTab for InspectorTab {
                                     .unwrap()
                                     .type_id()
                                     .unwrap();
-                                commands.push(InspectCommand::AddComponent(selected_entity, id));
+                                commands.extend(
+                                    selected
+                                        .iter()
+                                        .map(|entity| InspectCommand::AddComponent(*entity, id)),
+                                );
                             }
                             ui.end_row();
                         }
@@ -350,6 +396,23 @@ impl EditorTab for InspectorTab {
             disable_pan_orbit = true;
         }
 
+        // Clipboard shortcuts work while pointer is over inspector and no text is edited
+        if ui.ui_contains_pointer() && !ui.ctx().wants_keyboard_input() {
+            let (copy, paste) = ui.input_mut(|i| {
+                (
+                    i.consume_key(egui::Modifiers::COMMAND, egui::Key::C),
+                    i.consume_key(egui::Modifiers::COMMAND, egui::Key::V),
+                )
+            });
+            if copy {
+                let components = components_id.iter().map(|(_, t_id, ..)| *t_id).collect();
+                commands.push(InspectCommand::CopyComponents(selected_entity, components));
+            }
+            if paste {
+                commands.push(InspectCommand::PasteComponents(selected.clone()));
+            }
+        }
+
         state.commands = commands;
 
         if disable_pan_orbit {
@@ -363,6 +426,112 @@ impl EditorTab for InspectorTab {
 }
 
 impl InspectorTab {
+    /// Edit components, which are shared by all `entities`. Edit of field is applied to all
+    /// entities at one frame, so it is undone as one change
+    fn show_shared_components(
+        &mut self,
+        ui: &mut egui::Ui,
+        cell: &UnsafeWorldCell<'_>,
+        entities: &[Entity],
+        components_id: &[(ComponentId, TypeId, String, &u8)],
+        env: &mut InspectorUi<'_, '_>,
+        editor_registry_resource: &EditorRegistry,
+        editor_registry: &TypeRegistry,
+        app_registry: &TypeRegistry,
+        commands: &mut Vec<InspectCommand>,
+    ) {
+        ui.heading(format!("{} entities", entities.len()));
+        ui.label("Shared components:");
+        egui::Grid::new("shared components").show(ui, |ui| {
+            for (c_id, t_id, name, _) in components_id {
+                if editor_registry_resource.silent.contains(t_id) {
+                    continue;
+                }
+                let Some(reflect_from_ptr) = editor_registry
+                    .get(*t_id)
+                    .or_else(|| app_registry.get(*t_id))
+                    .and_then(|registration| registration.data::<ReflectFromPtr>())
+                    .cloned()
+                else {
+                    continue;
+                };
+
+                let mut values = Vec::with_capacity(entities.len());
+                let mut set_changed = Vec::with_capacity(entities.len());
+                for entity in entities {
+                    let Some(data) = cell
+                        .get_entity(*entity)
+                        .and_then(|e| unsafe { e.get_mut_by_id(*c_id) })
+                    else {
+                        continue;
+                    };
+                    let (ptr, changed) = mut_untyped_split(data);
+                    values.push(unsafe { reflect_from_ptr.from_ptr_mut()(ptr) });
+                    set_changed.push(changed);
+                }
+                let mixed = values.windows(2).any(|pair| {
+                    !pair[0]
+                        .reflect_partial_eq(pair[1].as_reflect())
+                        .unwrap_or(false)
+                });
+
+                ui.push_id(format!("shared-{}", &name), |ui| {
+                    let default = name.to_lowercase() == *"transform";
+                    let title = if mixed {
+                        egui::RichText::new(name).italics()
+                    } else {
+                        egui::RichText::new(name)
+                    };
+                    let header = egui::CollapsingHeader::new(title)
+                        .id_source(name)
+                        .default_open(*self.open_components.get(name).unwrap_or(&default))
+                        .show(ui, |ui| {
+                            let changed = match ui_for_shared_fields(env, ui, &mut values) {
+                                Some(changed) => changed,
+                                None => env.ui_for_reflect_many(
+                                    *t_id,
+                                    name,
+                                    ui,
+                                    ui.id(),
+                                    values.as_mut_slice(),
+                                    &|value| value,
+                                ),
+                            };
+                            if changed {
+                                for set_changed in set_changed.iter_mut() {
+                                    set_changed();
+                                }
+                            }
+                        });
+                    if header.header_response.clicked() {
+                        let open_name = self.open_components.entry(name.clone()).or_default();
+                        *open_name = header.openness < 0.5;
+                    }
+                    if mixed {
+                        header
+                            .header_response
+                            .on_hover_text("Values differ between selected entities");
+                    }
+                });
+
+                let button = egui::Button::new("🗙").fill(DEFAULT_BG_COLOR);
+                if ui
+                    .add(button)
+                    .on_hover_text("Remove from all selected entities")
+                    .clicked()
+                {
+                    commands.extend(
+                        entities
+                            .iter()
+                            .map(|entity| InspectCommand::RemoveComponent(*entity, *t_id)),
+                    );
+                }
+                ui.end_row();
+            }
+        });
+        ui.separator();
+    }
+
     fn show_component(
         &mut self,
         ui: &mut egui::Ui,
@@ -371,7 +540,10 @@ impl InspectorTab {
         env: &mut InspectorUi<'_, '_>,
         value: &mut dyn Reflect,
         set_changed: &mut impl FnMut(),
+        commands: &mut Vec<InspectCommand>,
     ) {
+        let is_material = value.is::<MaterialPrefab>();
+        let type_id = value.as_any().type_id();
         ui.push_id(format!("{:?}-{}", &e.id(), &name), |ui| {
             let default = name.to_lowercase() == *"transform";
             let header = egui::CollapsingHeader::new(name)
@@ -388,10 +560,105 @@ impl InspectorTab {
                 //At click header not opened simultaneously so its need to check percent of opened
                 *open_name = header.openness < 0.5;
             }
+            header.header_response.context_menu(|ui| {
+                if ui.button("Copy component").clicked() {
+                    commands.push(InspectCommand::CopyComponents(e.id(), vec![type_id]));
+                    ui.close_menu();
+                }
+                if ui.button("Paste components").clicked() {
+                    commands.push(InspectCommand::PasteComponents(vec![e.id()]));
+                    ui.close_menu();
+                }
+                if is_material {
+                    ui.separator();
+                    if self.material_file.is_empty() {
+                        self.material_file = format!("materials/material.{MATERIAL_EXTENSION}");
+                    }
+                    ui.label("Material file:");
+                    ui.text_edit_singleline(&mut self.material_file);
+                    if ui.button("Extract material to file").clicked() {
+                        commands.push(InspectCommand::ExtractMaterial(
+                            e.id(),
+                            self.material_file.clone(),
+                        ));
+                        ui.close_menu();
+                    }
+                }
+            });
         });
     }
 }
 
+/// Edit fields of struct component of several entities. Field with different values is shown
+/// as mixed "—" placeholder, click on it sets value of first entity to all entities.
+/// Returns `None` if component is not a struct
+fn ui_for_shared_fields(
+    env: &mut InspectorUi<'_, '_>,
+    ui: &mut egui::Ui,
+    values: &mut [&mut dyn Reflect],
+) -> Option<bool> {
+    let ReflectRef::Struct(first) = values.first()?.reflect_ref() else {
+        return None;
+    };
+    let fields = (0..first.field_len())
+        .filter_map(|idx| {
+            let name = first.name_at(idx)?.to_string();
+            let type_id = first.field_at(idx)?.as_any().type_id();
+            Some((name, type_id))
+        })
+        .collect::<Vec<_>>();
+
+    let mut changed = false;
+    egui::Grid::new("shared fields").show(ui, |ui| {
+        for (field, type_id) in fields.iter() {
+            let field = field.as_str();
+            let mixed = {
+                let mut field_values = values
+                    .iter()
+                    .filter_map(|value| value.reflect_path(field).ok());
+                let first = field_values.next();
+                first.is_some_and(|first| {
+                    field_values.any(|value| !value.reflect_partial_eq(first).unwrap_or(false))
+                })
+            };
+            ui.label(field);
+            if mixed {
+                if ui
+                    .button("—")
+                    .on_hover_text("Values differ. Click to use value of first entity")
+                    .clicked()
+                {
+                    if let Some(first) =
+                        values[0].reflect_path(field).ok().map(Reflect::clone_value)
+                    {
+                        for value in values.iter_mut().skip(1) {
+                            if let Ok(value) = value.reflect_path_mut(field) {
+                                value.apply(first.as_ref());
+                            }
+                        }
+                        changed = true;
+                    }
+                }
+            } else if env.ui_for_reflect_many(
+                *type_id,
+                field,
+                ui,
+                ui.id().with(field),
+                values,
+                &|value| {
+                    value
+                        .reflect_path_mut(field)
+                        .expect("selected entities have the same component type")
+                },
+            ) {
+                changed = true;
+            }
+            ui.end_row();
+        }
+    });
+    Some(changed)
+}
+
 fn register_custom_impls(registry: Res<AppTypeRegistry>) {
     let mut registry = registry.write();
     registry
@@ -402,6 +669,23 @@ fn register_custom_impls(registry: Res<AppTypeRegistry>) {
             entity_ref_ui_readonly,
             many_unimplemented::<EntityRef>,
         ));
+    // Asset paths can be dropped into any text field
+    registry
+        .get_mut(TypeId::of::<String>())
+        .unwrap_or_else(|| panic!("{} not registered", std::any::type_name::<String>()))
+        .insert(InspectorEguiImpl::new(
+            string_ui,
+            string_ui_readonly,
+            string_ui_many,
+        ));
+    register_asset_path_ui::<Image>(&mut registry);
+    register_asset_path_ui::<Mesh>(&mut registry);
+    register_asset_path_ui::<bevy::gltf::Gltf>(&mut registry);
+    register_asset_path_ui::<DynamicScene>(&mut registry);
+    register_asset_path_ui::<AnimationClip>(&mut registry);
+    register_asset_path_ui::<bevy::render::mesh::skinning::SkinnedMeshInverseBindposes>(
+        &mut registry,
+    );
 }
 
 /// Function form `bevy_inspector_egui` to split component to data ptr and "set changed" function
@@ -429,6 +713,12 @@ struct FilterComponentState {
 enum InspectCommand {
     AddComponent(Entity, TypeId),
     RemoveComponent(Entity, TypeId),
+    /// Save material of entity to file and use the file instead
+    ExtractMaterial(Entity, String),
+    /// Copy components of entity to system clipboard
+    CopyComponents(Entity, Vec<TypeId>),
+    /// Insert components from system clipboard into entities
+    PasteComponents(Vec<Entity>),
 }
 
 fn execute_inspect_command(
@@ -445,6 +735,38 @@ fn execute_inspect_command(
             InspectCommand::RemoveComponent(e, id) => {
                 registration.remove_by_id(&mut commands.entity(*e), id);
             }
+            InspectCommand::ExtractMaterial(e, path) => {
+                let (e, path) = (*e, path.clone());
+                commands.add(move |world: &mut World| {
+                    match extract_material_to_file(world, e, &path) {
+                        Ok(()) => {
+                            world.send_event(ToastMessage::new(
+                                &format!("Material saved to {path}"),
+                                ToastKind::Success,
+                            ));
+                        }
+                        Err(err) => {
+                            error!("Failed to extract material to {}: {}", path, err);
+                            world.send_event(ToastMessage::new(
+                                &format!("Failed to extract material: {err}"),
+                                ToastKind::Error,
+                            ));
+                        }
+                    }
+                });
+            }
+            InspectCommand::CopyComponents(e, components) => {
+                let (e, components) = (*e, components.clone());
+                commands.add(move |world: &mut World| {
+                    copy_components_to_clipboard(world, e, &components);
+                });
+            }
+            InspectCommand::PasteComponents(entities) => {
+                let entities = entities.clone();
+                commands.add(move |world: &mut World| {
+                    paste_components_from_clipboard(world, &entities);
+                });
+            }
         }
     }
     state.commands.clear();
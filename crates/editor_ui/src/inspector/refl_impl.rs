@@ -2,14 +2,19 @@ use std::any::{Any, TypeId};
 
 use bevy::{
     prelude::{AppTypeRegistry, ResMut},
-    reflect::Reflect,
+    reflect::{Reflect, TypeRegistry},
 };
 use bevy_egui::egui;
 use space_shared::ext::bevy_inspector_egui::{
     inspector_egui_impls::InspectorEguiImpl, reflect_inspector::InspectorUi,
 };
 
-use space_prefab::component::EntityLink;
+use space_prefab::component::{AssetPathField, AssetPathKind, EntityLink};
+
+use crate::{
+    asset_inspector::{dropped_asset, DetectedAssets, EditorAsset},
+    colors::ERROR_COLOR,
+};
 
 /// Method from `bevy_inspector_egui` to make dummy reflection ui
 pub fn many_unimplemented<T: Any>(
@@ -75,3 +80,217 @@ pub fn entity_ref_ui_readonly(
     _: InspectorUi<'_, '_>,
 ) {
 }
+
+/// Text field, which takes asset path dropped from asset browser or OS file manager
+pub fn string_ui(
+    value: &mut dyn Any,
+    ui: &mut egui::Ui,
+    _options: &dyn Any,
+    _id: egui::Id,
+    _env: InspectorUi<'_, '_>,
+) -> bool {
+    let Some(value) = value.downcast_mut::<String>() else {
+        return false;
+    };
+    let response = if value.contains('\n') {
+        ui.text_edit_multiline(value)
+    } else {
+        ui.text_edit_singleline(value)
+    };
+    let mut changed = response.changed();
+    if let Some((path, _)) = dropped_asset(ui, &response) {
+        *value = path;
+        changed = true;
+    }
+    changed
+}
+
+/// Readonly text field for [`String`]
+pub fn string_ui_readonly(
+    value: &dyn Any,
+    ui: &mut egui::Ui,
+    _: &dyn Any,
+    _: egui::Id,
+    _: InspectorUi<'_, '_>,
+) {
+    if let Some(value) = value.downcast_ref::<String>() {
+        ui.add_enabled(false, egui::TextEdit::singleline(&mut value.as_str()));
+    }
+}
+
+/// Text field for several [`String`] values at once
+pub fn string_ui_many(
+    ui: &mut egui::Ui,
+    _options: &dyn Any,
+    _id: egui::Id,
+    _env: InspectorUi<'_, '_>,
+    values: &mut [&mut dyn Reflect],
+    projector: &dyn Fn(&mut dyn Reflect) -> &mut dyn Reflect,
+) -> bool {
+    let Some(first) = values.first_mut() else {
+        return false;
+    };
+    let mut text = projector(&mut **first)
+        .downcast_ref::<String>()
+        .cloned()
+        .unwrap_or_default();
+    let response = ui.text_edit_singleline(&mut text);
+    let mut changed = response.changed();
+    if let Some((path, _)) = dropped_asset(ui, &response) {
+        text = path;
+        changed = true;
+    }
+    if changed {
+        for value in values.iter_mut() {
+            if let Some(value) = projector(&mut **value).downcast_mut::<String>() {
+                value.clone_from(&text);
+            }
+        }
+    }
+    changed
+}
+
+/// Custom UI for [`AssetPathField`] of asset type `T`
+pub fn register_asset_path_ui<T: AssetPathKind>(registry: &mut TypeRegistry) {
+    if let Some(registration) = registry.get_mut(TypeId::of::<AssetPathField<T>>()) {
+        registration.insert(InspectorEguiImpl::new(
+            asset_path_ui::<T>,
+            asset_path_ui_readonly::<T>,
+            asset_path_ui_many::<T>,
+        ));
+    }
+}
+
+/// Paths of detected assets, which can be picked for asset type `T`
+fn pickable_assets<T: AssetPathKind>(env: &InspectorUi<'_, '_>) -> Option<Vec<String>> {
+    let world = env.context.world.as_ref()?;
+    let world_ref = unsafe { world.world().world() };
+    let detected = world_ref.get_resource::<DetectedAssets>()?;
+    Some(
+        detected
+            .assets
+            .iter()
+            .map(EditorAsset::asset_path)
+            .filter(|path| AssetPathField::<T>::matches_extension(path))
+            .collect(),
+    )
+}
+
+/// Text field with asset picker. Missing file or wrong file type is shown in red
+fn asset_path_edit<T: AssetPathKind>(
+    ui: &mut egui::Ui,
+    id: egui::Id,
+    value: &mut AssetPathField<T>,
+    assets: Option<&[String]>,
+) -> bool {
+    let problem = if value.is_empty() {
+        None
+    } else if !value.has_valid_extension() {
+        Some(format!("Expected file type: {}", T::EXTENSIONS.join(", ")))
+    } else if !assets.map_or_else(
+        || value.exists(),
+        |assets| assets.iter().any(|asset| asset == value.file()),
+    ) {
+        Some("File not found in assets folder".to_string())
+    } else {
+        None
+    };
+
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        let mut edit = egui::TextEdit::singleline(&mut value.path).hint_text("None");
+        if problem.is_some() {
+            edit = edit.text_color(ERROR_COLOR);
+        }
+        let response = ui.add(edit);
+        changed |= response.changed();
+        if let Some((path, _)) = dropped_asset(ui, &response) {
+            if AssetPathField::<T>::matches_extension(&path) {
+                *value = AssetPathField::from_file(&path);
+                changed = true;
+            }
+        }
+        if let Some(problem) = &problem {
+            response.on_hover_text(egui::RichText::new(problem).color(ERROR_COLOR));
+        }
+
+        egui::ComboBox::from_id_source(id.with("asset_path_picker"))
+            .selected_text("📂")
+            .width(24.)
+            .height(320.)
+            .show_ui(ui, |ui| {
+                if ui.selectable_label(value.is_empty(), "None").clicked() {
+                    value.path.clear();
+                    changed = true;
+                }
+                for asset in assets.unwrap_or_default() {
+                    if ui
+                        .selectable_label(value.file() == asset.as_str(), asset)
+                        .clicked()
+                    {
+                        *value = AssetPathField::from_file(asset);
+                        changed = true;
+                    }
+                }
+            });
+    });
+    changed
+}
+
+/// Custom UI for [`AssetPathField`]
+pub fn asset_path_ui<T: AssetPathKind>(
+    value: &mut dyn Any,
+    ui: &mut egui::Ui,
+    _options: &dyn Any,
+    id: egui::Id,
+    env: InspectorUi<'_, '_>,
+) -> bool {
+    let Some(value) = value.downcast_mut::<AssetPathField<T>>() else {
+        return false;
+    };
+    let assets = pickable_assets::<T>(&env);
+    asset_path_edit(ui, id, value, assets.as_deref())
+}
+
+/// Readonly UI for [`AssetPathField`]
+pub fn asset_path_ui_readonly<T: AssetPathKind>(
+    value: &dyn Any,
+    ui: &mut egui::Ui,
+    _: &dyn Any,
+    _: egui::Id,
+    _: InspectorUi<'_, '_>,
+) {
+    if let Some(value) = value.downcast_ref::<AssetPathField<T>>() {
+        ui.label(value.as_str());
+    }
+}
+
+/// UI for several [`AssetPathField`] values at once
+pub fn asset_path_ui_many<T: AssetPathKind>(
+    ui: &mut egui::Ui,
+    _options: &dyn Any,
+    id: egui::Id,
+    env: InspectorUi<'_, '_>,
+    values: &mut [&mut dyn Reflect],
+    projector: &dyn Fn(&mut dyn Reflect) -> &mut dyn Reflect,
+) -> bool {
+    let Some(first) = values.first_mut() else {
+        return false;
+    };
+    let Some(mut edited) = projector(&mut **first)
+        .downcast_ref::<AssetPathField<T>>()
+        .cloned()
+    else {
+        return false;
+    };
+    let assets = pickable_assets::<T>(&env);
+    if !asset_path_edit(ui, id, &mut edited, assets.as_deref()) {
+        return false;
+    }
+    for value in values.iter_mut() {
+        if let Some(value) = projector(&mut **value).downcast_mut::<AssetPathField<T>>() {
+            value.clone_from(&edited);
+        }
+    }
+    true
+}
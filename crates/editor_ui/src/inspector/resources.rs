@@ -1,3 +1,5 @@
+use std::any::TypeId;
+
 use bevy::{prelude::*, utils::HashMap};
 
 use bevy_egui::*;
@@ -21,12 +23,24 @@ impl EditorTab for ResourceTab {
 }
 
 pub fn inspect(ui: &mut egui::Ui, world: &mut World, open_resources: &mut HashMap<String, bool>) {
+    inspect_filtered(ui, world, open_resources, |_| true, |_, _, _| {});
+}
+
+/// Show reflected resources accepted by `filter`. `footer` is shown under fields of each resource
+pub fn inspect_filtered(
+    ui: &mut egui::Ui,
+    world: &mut World,
+    open_resources: &mut HashMap<String, bool>,
+    filter: impl Fn(TypeId) -> bool,
+    mut footer: impl FnMut(&mut egui::Ui, &mut World, TypeId),
+) {
     let type_registry = world.resource::<AppTypeRegistry>().clone();
     let type_registry = type_registry.read();
 
     let mut resources: Vec<_> = type_registry
         .iter()
         .filter(|registration| registration.data::<ReflectResource>().is_some())
+        .filter(|registration| filter(registration.type_id()))
         .map(|registration| {
             (
                 registration
@@ -54,6 +68,7 @@ pub fn inspect(ui: &mut egui::Ui, world: &mut World, open_resources: &mut HashMa
                                 &resource_name,
                                 &type_registry,
                             );
+                            footer(ui, world, type_id);
                         });
                     });
                 if header.header_response.clicked() {
@@ -0,0 +1,49 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use bevy_egui::*;
+
+use crate::{colors::ERROR_COLOR, prelude::*};
+
+/// Tab with level settings, which are saved in scene file
+#[derive(Resource, Default)]
+pub struct SceneSettingsTab {
+    open_resources: HashMap<String, bool>,
+}
+
+impl EditorTab for SceneSettingsTab {
+    fn ui(&mut self, ui: &mut egui::Ui, _: &mut Commands, world: &mut World) {
+        inspect(ui, world, &mut self.open_resources);
+    }
+
+    fn title(&self) -> egui::WidgetText {
+        "Scene Settings".into()
+    }
+}
+
+pub fn inspect(ui: &mut egui::Ui, world: &mut World, open_resources: &mut HashMap<String, bool>) {
+    let resources = world.resource::<EditorRegistry>().scene_resources.clone();
+
+    if resources.is_empty() {
+        ui.label(egui::RichText::new("No scene resources registered").color(ERROR_COLOR));
+        return;
+    }
+
+    let find = |type_id| {
+        resources
+            .iter()
+            .find(|resource| resource.type_id == type_id)
+    };
+    super::resources::inspect_filtered(
+        ui,
+        world,
+        open_resources,
+        |type_id| find(type_id).is_some(),
+        |ui, world, type_id| {
+            if let Some(resource) = find(type_id) {
+                if ui.button("Reset").on_hover_text(resource.path()).clicked() {
+                    resource.reset(world);
+                }
+            }
+        },
+    );
+}
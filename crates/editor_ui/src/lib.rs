@@ -12,6 +12,9 @@ pub mod menu_toolbars;
 /// This module contains UI logic for undo/redo functionality
 pub mod change_chain;
 
+/// This module contains copy and paste of entities and components through system clipboard
+pub mod clipboard;
+
 /// This module contains UI logic for debug panels (like WorldInspector)
 pub mod debug_panels;
 
@@ -10,11 +10,15 @@ use space_editor_core::{
     prelude::*,
     toast::{ClearToastMessage, ToastStorage},
 };
-use space_prefab::{component::GltfPrefab, load::PrefabBundle, plugins::PrefabPlugin};
+use space_prefab::{
+    plugins::PrefabPlugin,
+    scenes::{EditorScene, SaveEditorScene},
+};
 use space_shared::{ext::egui_file, *};
 use space_undo::{AddedEntity, NewChange, RemovedEntity};
 
 use crate::{
+    asset_inspector::{open_asset_browser, AssetKind},
     colors::*,
     hierarchy::{HierarchyQueryIter, HierarchyTabState},
     icons::{add_bundle_icon, add_entity_icon, delete_entity_icon, prefab_icon},
@@ -49,6 +53,13 @@ impl Plugin for BottomMenuPlugin {
                 .in_set(EditorSet::Editor)
                 .run_if(in_state(EditorState::Editor).and_then(in_state(ShowEditorUi::Show))),
         );
+        app.add_systems(
+            Update,
+            recovery_window
+                .before(EditorLoadSet)
+                .in_set(EditorSet::Editor)
+                .run_if(in_state(EditorState::Editor).and_then(in_state(ShowEditorUi::Show))),
+        );
         app.add_systems(Update, in_game_menu.in_set(EditorSet::Game));
         app.add_event::<MenuLoadEvent>();
     }
@@ -136,13 +147,39 @@ fn in_game_menu(
         });
 }
 
+/// Offer to restore autosave found on startup
+fn recovery_window(
+    mut ctxs: EguiContexts,
+    state: Res<AutosaveState>,
+    mut answers: EventWriter<RecoveryAnswer>,
+) {
+    let Some(offer) = &state.offer else {
+        return;
+    };
+    egui::Window::new("Recover unsaved work")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_CENTER, [0., 0.])
+        .show(ctxs.ctx_mut(), |ui| {
+            match &offer.scene {
+                Some(scene) => ui.label(format!("Autosave of {scene} is newer than saved scene.")),
+                None => ui.label("Autosave of unsaved scene was found."),
+            };
+            ui.label("Restore it?");
+            ui.horizontal(|ui| {
+                if ui.button("Restore").clicked() {
+                    answers.send(RecoveryAnswer::Restore);
+                }
+                if ui.button("Discard").clicked() {
+                    answers.send(RecoveryAnswer::Discard);
+                }
+            });
+        });
+}
+
 #[derive(Resource, Default)]
 pub struct MenuToolbarState {
-    pub file_dialog: Option<egui_file::FileDialog>,
-    pub gltf_dialog: Option<egui_file::FileDialog>,
     pub save_dialog: Option<egui_file::FileDialog>,
-    pub load_dialog: Option<egui_file::FileDialog>,
-    pub subscene_dialog: Option<egui_file::FileDialog>,
     show_toasts: bool,
     pub path: String,
 }
@@ -271,6 +308,11 @@ pub fn bottom_menu(
         });
 }
 
+/// Scenes, models and backups are picked in asset browser
+fn browse_assets(commands: &mut Commands, filter: Option<AssetKind>) {
+    commands.add(move |world: &mut World| open_asset_browser(world, filter));
+}
+
 pub fn top_menu(
     mut commands: Commands,
     mut ctxs: EguiContexts,
@@ -282,6 +324,7 @@ pub fn top_menu(
     background_tasks: Res<BackgroundTaskStorage>,
     toasts: Res<ToastStorage>,
     sizing: Res<Sizing>,
+    scenes: Query<(), With<EditorScene>>,
 ) {
     let ctx = ctxs.ctx_mut();
     egui::TopBottomPanel::top("top_menu_bar")
@@ -295,44 +338,12 @@ pub fn top_menu(
                 // Open Assets Folder
                 let open_button = egui::Button::new(to_richtext("📂", &sizing.icon))
                     .stroke(stroke_default_color());
-                if ui.add(open_button).clicked() {
-                    let mut dialog = egui_file::FileDialog::open_file(Some("assets/".into()))
-                        .show_files_filter(Box::new(|path| {
-                            path.to_str().unwrap().ends_with(".scn.ron")
-                        }))
-                        .title("File Explorer (Scene/Bundle) (*.scn.ron)");
-                    dialog.open();
-                    menu_state.file_dialog = Some(dialog);
-                }
-
-                if let Some(dialog) = &mut menu_state.file_dialog {
-                    if dialog.show(ctx).selected() {
-                        if let Some(file) = dialog.path() {
-                            let mut path = file.to_str().unwrap().to_string();
-                            //remove assets/ from path
-                            if path.starts_with("assets/") {
-                                path = path.replace("assets/", "");
-                                //remove .scn.ron
-                                path = path.replace(".scn.ron", "");
-                                menu_state.path = path;
-                                editor_events.send(EditorEvent::Load(EditorPrefabPath::File(
-                                    format!("{}.scn.ron", menu_state.path.clone()),
-                                )));
-                            }
-                        }
-                    } else {
-                        let mut need_move_to_default_dir = false;
-                        if let Some(path) = dialog.directory().to_str() {
-                            if !path.contains("assets") {
-                                need_move_to_default_dir = true;
-                            }
-                        } else {
-                            need_move_to_default_dir = true;
-                        }
-                        if need_move_to_default_dir {
-                            dialog.set_path("assets/");
-                        }
-                    }
+                if ui
+                    .add(open_button)
+                    .on_hover_text("Open asset browser")
+                    .clicked()
+                {
+                    browse_assets(&mut commands, None);
                 }
                 // END Open Assets Folder
 
@@ -381,6 +392,18 @@ pub fn top_menu(
                 }
                 // End Save File
 
+                // Restore from backup
+                let backup_button = egui::Button::new(to_richtext("🔙", &sizing.icon))
+                    .stroke(stroke_default_color());
+                if ui
+                    .add(backup_button)
+                    .on_hover_text("Restore scene from backup")
+                    .clicked()
+                {
+                    browse_assets(&mut commands, Some(AssetKind::Backup));
+                }
+                // End Restore from backup
+
                 // Load Scene
                 let load_button = egui::Button::new(to_richtext("📤", &sizing.icon))
                     .stroke(stroke_default_color());
@@ -389,45 +412,23 @@ pub fn top_menu(
                     .on_hover_text("Load scene file")
                     .clicked()
                 {
-                    let mut dialog = egui_file::FileDialog::open_file(Some("assets/scenes".into()))
-                        .show_files_filter(Box::new(|path| {
-                            path.to_str().unwrap().ends_with(".scn.ron")
-                        }))
-                        .title("Load Scene (*.scn.ron)");
-                    dialog.open();
-                    menu_state.load_dialog = Some(dialog);
+                    browse_assets(&mut commands, Some(AssetKind::Scene));
                 }
+                // END Load Scene
 
-                if let Some(dialog) = &mut menu_state.load_dialog {
-                    if dialog.show(ctx).selected() {
-                        if let Some(file) = dialog.path() {
-                            let mut path = file.to_str().unwrap().to_string();
-                            //remove assets/ from path
-                            if path.starts_with("assets/") {
-                                path = path.replace("assets/", "");
-                                //remove .scn.ron
-                                path = path.replace(".scn.ron", "");
-                                menu_state.path = path;
-                                editor_events.send(EditorEvent::Load(EditorPrefabPath::File(
-                                    format!("{}.scn.ron", menu_state.path.clone()),
-                                )));
-                            }
-                        }
-                    } else {
-                        let mut need_move_to_default_dir = false;
-                        if let Some(path) = dialog.directory().to_str() {
-                            if !path.contains("assets") {
-                                need_move_to_default_dir = true;
-                            }
-                        } else {
-                            need_move_to_default_dir = true;
-                        }
-                        if need_move_to_default_dir {
-                            dialog.set_path("assets/");
-                        }
+                if !scenes.is_empty() {
+                    let save_all_button = egui::Button::new(to_richtext("🗐", &sizing.icon))
+                        .stroke(stroke_default_color());
+                    if ui
+                        .add(save_all_button)
+                        .on_hover_text("Save all opened scenes to their files")
+                        .clicked()
+                    {
+                        commands.add(|world: &mut World| {
+                            world.send_event(SaveEditorScene::All);
+                        });
                     }
                 }
-                // END Load Scene
 
                 // Open GLTF
                 let open_gltf_button =
@@ -437,92 +438,10 @@ pub fn top_menu(
                     .on_hover_text("Open GLTF/GLB as prefab")
                     .clicked()
                 {
-                    let mut gltf_dialog =
-                        egui_file::FileDialog::open_file(Some("assets/models".into()))
-                            .show_files_filter(Box::new(|path| {
-                                path.to_str().unwrap().ends_with(".gltf")
-                                    || path.to_str().unwrap().ends_with(".glb")
-                            }))
-                            .title("Opens GLTF as Prefab");
-                    gltf_dialog.open();
-                    menu_state.gltf_dialog = Some(gltf_dialog);
-                }
-
-                if let Some(gltf_dialog) = &mut menu_state.gltf_dialog {
-                    if gltf_dialog.show(ctx).selected() {
-                        if let Some(file) = gltf_dialog.path() {
-                            let mut path = file.to_str().unwrap().to_string();
-                            if path.starts_with("assets/") {
-                                path = path.replace("assets/", "");
-
-                                editor_events.send(EditorEvent::LoadGltfAsPrefab(path));
-                            }
-                        }
-                    } else {
-                        let mut need_move_to_default_dir = false;
-                        if let Some(path) = gltf_dialog.directory().to_str() {
-                            if !path.contains("assets") {
-                                need_move_to_default_dir = true;
-                            }
-                        } else {
-                            need_move_to_default_dir = true;
-                        }
-                        if need_move_to_default_dir {
-                            gltf_dialog.set_path("assets/");
-                        }
-                    }
+                    browse_assets(&mut commands, Some(AssetKind::Model));
                 }
                 // End Open GLTF
 
-                //Open subscene
-                let subscene_button = egui::Button::new(to_richtext("📦", &sizing.icon))
-                    .stroke(stroke_default_color());
-                if ui
-                    .add(subscene_button)
-                    .on_hover_text("Open subscene")
-                    .clicked()
-                {
-                    let mut filedialog = egui_file::FileDialog::open_file(Some("assets".into()))
-                        .show_files_filter(Box::new(|path| {
-                            path.to_str().unwrap().ends_with(".scn.ron")
-                                || path.to_str().unwrap().ends_with(".gltf")
-                                || path.to_str().unwrap().ends_with(".glb")
-                        }))
-                        .title("Open Subscene (.scn.ron, .gltf, .glb)");
-                    filedialog.open();
-
-                    menu_state.subscene_dialog = Some(filedialog);
-                }
-
-                if let Some(subscene_dialog) = &mut menu_state.subscene_dialog {
-                    if subscene_dialog.show(ctx).selected() {
-                        if let Some(file) = subscene_dialog.path() {
-                            let mut path = file.to_str().unwrap().to_string();
-                            info!("path: {}", path);
-                            if path.starts_with("assets") {
-                                path = path.replace("assets", "");
-                                path = path.trim_start_matches('\\').to_string();
-                                path = path.trim_start_matches('/').to_string();
-
-                                if path.ends_with(".scn.ron") {
-                                    commands.spawn((PrefabBundle::new(&path), PrefabMarker));
-                                } else if path.ends_with(".gltf") || path.ends_with(".glb") {
-                                    commands.spawn((
-                                        SpatialBundle::default(),
-                                        GltfPrefab {
-                                            path,
-                                            scene: "Scene0".into(),
-                                        },
-                                        PrefabMarker,
-                                    ));
-                                } else {
-                                    error!("Unknown file type: {}", path);
-                                }
-                            }
-                        }
-                    }
-                }
-
                 let width = ui.available_width();
                 let distance = width / 2. - 40.;
                 ui.add_space(distance);
@@ -5,7 +5,8 @@ use bevy::{
     utils::{HashMap, HashSet},
 };
 use bevy_egui::*;
-use space_editor_core::hotkeys::AllHotkeys;
+use space_editor_core::{autosave::AutosaveSettings, hotkeys::AllHotkeys};
+use space_prefab::{binary::PrefabFormat, save::SaveConfig};
 use space_shared::ext::bevy_inspector_egui::bevy_inspector;
 use space_undo::ChangeChainSettings;
 
@@ -27,6 +28,12 @@ const TAB_MODES: [NewTabBehaviour; 3] = [
 
 const GAME_MODES: [GameMode; 2] = [GameMode::Game2D, GameMode::Game3D];
 
+const SAVE_FORMATS: [(PrefabFormat, &str); 3] = [
+    (PrefabFormat::Ron, "RON"),
+    (PrefabFormat::Binary, "Binary"),
+    (PrefabFormat::RonAndBinary, "RON + Binary export"),
+];
+
 pub struct SettingsWindowPlugin;
 
 impl Plugin for SettingsWindowPlugin {
@@ -212,6 +219,27 @@ impl EditorTab for SettingsWindow {
             );
         });
 
+        ui.add_space(12.);
+        ui.heading("Autosave");
+        bevy_inspector::ui_for_resource::<AutosaveSettings>(world, ui);
+
+        ui.add_space(12.);
+        ui.heading("Save Format");
+        if world.contains_resource::<SaveConfig>() {
+            let mut save_config = world.resource_mut::<SaveConfig>();
+            let selected = SAVE_FORMATS
+                .iter()
+                .find(|(format, _)| *format == save_config.format)
+                .map_or("", |(_, name)| name);
+            egui::ComboBox::new("save_format", "")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for (format, name) in SAVE_FORMATS.into_iter() {
+                        ui.selectable_value(&mut save_config.format, format, name);
+                    }
+                });
+        }
+
         ui.add_space(12.);
         ui.heading("New Tab Behaviour");
         let new_window_settings = &mut world.resource_mut::<NewWindowSettings>();
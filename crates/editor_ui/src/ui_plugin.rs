@@ -51,7 +51,9 @@ impl FlatPluginList for EditorUiPlugin {
             .add(SpaceInspectorPlugin)
             .add(GizmoToolPlugin)
             .add(ChangeChainViewPlugin)
-            .add(settings::SettingsWindowPlugin);
+            .add(settings::SettingsWindowPlugin)
+            .add(asset_inspector::AssetBrowserPlugin)
+            .add(asset_inspector::AssetDropPlugin);
 
         if self.use_standard_layout {
             res = res.add(DefaultEditorLayoutPlugin);
@@ -76,6 +78,11 @@ impl Plugin for DefaultEditorLayoutPlugin {
         let mut editor = app.world.resource_mut::<EditorUi>();
         editor.tree = egui_dock::DockState::new(vec![EditorTabName::GameView]);
 
+        let [_game, _assets] = editor.tree.main_surface_mut().split_below(
+            egui_dock::NodeIndex::root(),
+            0.75,
+            vec![EditorTabName::AssetBrowser],
+        );
         let [_game, hierarchy] = editor.tree.main_surface_mut().split_left(
             egui_dock::NodeIndex::root(),
             0.2,
@@ -264,6 +271,15 @@ pub enum EditorUiReg {
 }
 
 impl EditorUi {
+    /// Focus tab if it is opened, otherwise open it in new window
+    pub fn open_tab(&mut self, name: EditorTabName) {
+        if let Some(tab) = self.tree.find_tab(&name) {
+            self.tree.set_active_tab(tab);
+        } else {
+            self.tree.add_window(vec![name]);
+        }
+    }
+
     pub fn ui(&mut self, world: &mut World, ctx: &mut egui::Context) {
         //collect tab names to vec to detect visible
         let mut visible = vec![];
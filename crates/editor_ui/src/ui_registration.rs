@@ -368,7 +368,7 @@ pub fn register_mesh_editor_bundles(app: &mut App) {
         "Texture Sprite",
         (
             SpriteTexture {
-                texture: "branding/bevy_bird_dark.png".to_string(),
+                texture: "branding/bevy_bird_dark.png".into(),
             },
             Name::new("Texture Sprite".to_string()),
             PrefabMarker,
@@ -380,7 +380,7 @@ pub fn register_mesh_editor_bundles(app: &mut App) {
         "Sprite Sheet",
         (
             SpritesheetTexture {
-                texture: String::from("textures/gabe-idle-run.png"),
+                texture: "textures/gabe-idle-run.png".into(),
             },
             Name::from("Spritesheet"),
             AnimationIndicesSpriteSheet::default(),
@@ -0,0 +1,353 @@
+use std::fmt;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    reflect::TypeRegistryArc,
+    scene::serde::{
+        SceneDeserializer, SceneMapDeserializer, SceneSerializer, SCENE_ENTITIES, SCENE_RESOURCES,
+        SCENE_STRUCT,
+    },
+    utils::{BoxedFuture, HashMap},
+};
+use bincode::Options;
+use serde::de::{DeserializeSeed, Deserializer, Error, SeqAccess, Visitor};
+
+use crate::migration::{Migrations, PrefabMigrations, PrefabVersion, PREFAB_FORMAT_VERSION};
+
+/// Extension of binary prefab files
+pub const BINARY_PREFAB_EXTENSION: &str = "scn.bin";
+
+/// Encoding of saved prefab files
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum PrefabFormat {
+    /// Human readable `.scn.ron`, used by editor as source format
+    #[default]
+    Ron,
+    /// Compact `.scn.bin` for fast loading in game
+    Binary,
+    /// Save `.scn.ron` and export `.scn.bin` next to it
+    RonAndBinary,
+}
+
+impl PrefabFormat {
+    pub const fn writes_ron(&self) -> bool {
+        matches!(self, Self::Ron | Self::RonAndBinary)
+    }
+
+    pub const fn writes_binary(&self) -> bool {
+        matches!(self, Self::Binary | Self::RonAndBinary)
+    }
+}
+
+/// Path of binary prefab for RON prefab path
+pub fn binary_path(path: &str) -> String {
+    let stem = path
+        .strip_suffix(".scn.ron")
+        .or_else(|| path.strip_suffix(".ron"))
+        .unwrap_or(path);
+    format!("{stem}.{BINARY_PREFAB_EXTENSION}")
+}
+
+fn bincode_options() -> impl Options {
+    bincode::DefaultOptions::new().with_fixint_encoding()
+}
+
+/// Serialize prefab to binary format
+pub fn serialize_binary(
+    scene: &DynamicScene,
+    type_registry: &TypeRegistryArc,
+) -> Result<Vec<u8>, String> {
+    let serializer = SceneSerializer::new(scene, type_registry);
+    bincode_options()
+        .serialize(&serializer)
+        .map_err(|e| e.to_string())
+}
+
+/// Reads only resources of binary scene, so header can be checked before components
+struct SceneResourcesDeserializer<'a> {
+    registry: &'a bevy::reflect::TypeRegistry,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for SceneResourcesDeserializer<'a> {
+    type Value = Vec<Box<dyn Reflect>>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_struct(SCENE_STRUCT, &[SCENE_RESOURCES, SCENE_ENTITIES], self)
+    }
+}
+
+impl<'a, 'de> Visitor<'de> for SceneResourcesDeserializer<'a> {
+    type Value = Vec<Box<dyn Reflect>>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("scene struct")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        seq.next_element_seed(SceneMapDeserializer {
+            registry: self.registry,
+        })?
+        .ok_or_else(|| A::Error::missing_field(SCENE_RESOURCES))
+    }
+}
+
+/// Read [`PrefabVersion`] header of binary prefab. Missing header is version 0
+pub fn read_binary_header(
+    bytes: &[u8],
+    type_registry: &TypeRegistryArc,
+) -> Result<PrefabVersion, String> {
+    let resources = bincode_options()
+        .allow_trailing_bytes()
+        .deserialize_seed(
+            SceneResourcesDeserializer {
+                registry: &type_registry.read(),
+            },
+            bytes,
+        )
+        .map_err(|e| format!("invalid prefab header: {e}"))?;
+    Ok(resources
+        .iter()
+        .find_map(|resource| PrefabVersion::from_reflect(resource.as_ref()))
+        .unwrap_or_default())
+}
+
+/// Versioned types with other schema version in header than current one,
+/// as (saved, current) versions
+fn stale_schemas(header: &PrefabVersion, migrations: &Migrations) -> HashMap<String, (u32, u32)> {
+    let versioned = migrations.header().schemas;
+    header
+        .schemas
+        .keys()
+        .chain(versioned.keys())
+        .filter_map(|type_path| {
+            let saved = header.schemas.get(type_path).copied().unwrap_or_default();
+            let current = migrations.version(type_path);
+            (saved != current).then(|| (type_path.clone(), (saved, current)))
+        })
+        .collect()
+}
+
+fn stale_error(type_path: &str, (saved, current): (u32, u32)) -> String {
+    format!(
+        "binary prefab has {type_path} version {saved}, current version is {current}. \
+        Binary prefabs can not be migrated, export it again from .scn.ron"
+    )
+}
+
+/// Deserialize prefab from binary format.
+/// Binary components can not be migrated, so prefab with outdated schema version
+/// of any of its components is rejected
+pub fn deserialize_binary(
+    bytes: &[u8],
+    migrations: &Migrations,
+    type_registry: &TypeRegistryArc,
+) -> Result<DynamicScene, String> {
+    let header = read_binary_header(bytes, type_registry)?;
+    if header.format > PREFAB_FORMAT_VERSION {
+        return Err(format!(
+            "prefab format version {} is newer than supported {}",
+            header.format, PREFAB_FORMAT_VERSION
+        ));
+    }
+    let stale = stale_schemas(&header, migrations);
+
+    let mut scene = bincode_options()
+        .deserialize_seed(
+            SceneDeserializer {
+                type_registry: &type_registry.read(),
+            },
+            bytes,
+        )
+        .map_err(|e| {
+            // Outdated layout is the likely reason of broken data
+            let mut stale = stale.iter().collect::<Vec<_>>();
+            stale.sort();
+            stale.first().map_or_else(
+                || e.to_string(),
+                |(type_path, versions)| stale_error(type_path, **versions),
+            )
+        })?;
+    for component in scene
+        .entities
+        .iter()
+        .flat_map(|entity| entity.components.iter())
+    {
+        let Some(type_info) = component.get_represented_type_info() else {
+            continue;
+        };
+        let type_path = type_info.type_path();
+        if let Some(versions) = stale.get(type_path) {
+            return Err(stale_error(type_path, *versions));
+        }
+    }
+    // Header is not needed in world
+    scene
+        .resources
+        .retain(|resource| !resource.represents::<PrefabVersion>());
+    Ok(scene)
+}
+
+/// Asset loader for binary `.scn.bin` prefabs
+pub struct BinarySceneLoader {
+    type_registry: TypeRegistryArc,
+    migrations: PrefabMigrations,
+}
+
+impl FromWorld for BinarySceneLoader {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            type_registry: world.resource::<AppTypeRegistry>().0.clone(),
+            migrations: world
+                .get_resource_or_insert_with(PrefabMigrations::default)
+                .clone(),
+        }
+    }
+}
+
+impl AssetLoader for BinarySceneLoader {
+    type Asset = DynamicScene;
+    type Settings = ();
+    type Error = String;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<DynamicScene, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = vec![];
+            reader
+                .read_to_end(&mut bytes)
+                .await
+                .map_err(|e| e.to_string())?;
+            let migrations = self
+                .migrations
+                .0
+                .read()
+                .map_err(|_| "migrations lock is poisoned".to_string())?
+                .clone();
+            deserialize_binary(&bytes, &migrations, &self.type_registry)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[BINARY_PREFAB_EXTENSION]
+    }
+}
+
+/// Plugin for binary prefab files
+pub struct BinaryPrefabPlugin;
+
+impl Plugin for BinaryPrefabPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<PrefabFormat>();
+        if app.world.contains_resource::<AssetServer>() {
+            app.init_asset_loader::<BinarySceneLoader>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::save::ChildrenPrefab;
+
+    #[test]
+    fn binary_roundtrip() {
+        let mut world = World::new();
+        let registry = AppTypeRegistry::default();
+        {
+            let mut registry = registry.write();
+            registry.register::<Transform>();
+            registry.register::<Vec3>();
+            registry.register::<Quat>();
+            registry.register::<ChildrenPrefab>();
+            registry.register::<Vec<Entity>>();
+            registry.register::<Entity>();
+        }
+        world.insert_resource(registry.clone());
+        let child = world.spawn(Transform::from_xyz(1., 2., 3.)).id();
+        world.spawn(ChildrenPrefab(vec![child]));
+        let mut scene = DynamicScene::from_world(&world);
+        scene.resources.push(Box::new(PrefabVersion::default()));
+        registry.write().register::<PrefabVersion>();
+        registry
+            .write()
+            .register::<bevy::utils::HashMap<String, u32>>();
+
+        let bytes = serialize_binary(&scene, &registry).unwrap();
+        let loaded = deserialize_binary(&bytes, &Migrations::default(), &registry).unwrap();
+
+        assert!(loaded.resources.is_empty());
+        assert_eq!(loaded.entities.len(), 2);
+        let transform = loaded
+            .entities
+            .iter()
+            .flat_map(|entity| entity.components.iter())
+            .find_map(|c| Transform::from_reflect(c.as_ref()))
+            .unwrap();
+        assert_eq!(transform.translation, Vec3::new(1., 2., 3.));
+        assert!(
+            deserialize_binary(&bytes[..bytes.len() / 2], &Migrations::default(), &registry)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn binary_with_outdated_header_is_rejected() {
+        let mut world = World::new();
+        let registry = AppTypeRegistry::default();
+        {
+            let mut registry = registry.write();
+            registry.register::<Transform>();
+            registry.register::<Vec3>();
+            registry.register::<Quat>();
+            registry.register::<Name>();
+            registry.register::<std::borrow::Cow<'static, str>>();
+            registry.register::<PrefabVersion>();
+            registry.register::<HashMap<String, u32>>();
+        }
+        world.insert_resource(registry.clone());
+        world.spawn(Transform::from_xyz(1., 2., 3.));
+        let mut scene = DynamicScene::from_world(&world);
+        // Saved before migration of Transform was registered
+        let old = Migrations::default();
+        scene.resources.push(Box::new(old.header()));
+        let bytes = serialize_binary(&scene, &registry).unwrap();
+        assert_eq!(
+            read_binary_header(&bytes, &registry).unwrap().format,
+            PREFAB_FORMAT_VERSION
+        );
+        assert!(deserialize_binary(&bytes, &old, &registry).is_ok());
+
+        let mut current = Migrations::default();
+        current.register(Transform::type_path(), 0, std::sync::Arc::new(|_| Ok(())));
+        let err = deserialize_binary(&bytes, &current, &registry)
+            .err()
+            .unwrap();
+        assert!(err.contains("version 0, current version is 1"), "{err}");
+
+        // Prefab without outdated components is still loaded
+        let mut world = World::new();
+        world.insert_resource(registry.clone());
+        world.spawn(Name::new("light"));
+        let mut scene = DynamicScene::from_world(&world);
+        scene.resources.push(Box::new(old.header()));
+        let bytes = serialize_binary(&scene, &registry).unwrap();
+        assert_eq!(
+            deserialize_binary(&bytes, &current, &registry)
+                .unwrap()
+                .entities
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn binary_path_replaces_extension() {
+        assert_eq!(binary_path("scenes/level.scn.ron"), "scenes/level.scn.bin");
+        assert_eq!(binary_path("level"), "level.scn.bin");
+    }
+}
@@ -0,0 +1,127 @@
+use std::{path::Path, process::ExitCode};
+
+use bevy::{prelude::*, reflect::TypeRegistryArc, scene::ScenePlugin};
+
+use crate::{
+    headless::{convert_prefab_file, diff_prefabs, find_prefab_files, load_prefab_file},
+    migration::{Migrations, PrefabMigrations},
+    plugins::BasePrefabPlugin,
+};
+
+const USAGE: &str = "usage:
+    prefab_cli validate [dir]          check that all prefabs in dir (default assets/scenes) load
+    prefab_cli convert <input> <output> convert between .scn.ron and .scn.bin
+    prefab_cli diff <old> <new>         print semantic difference of two prefabs";
+
+/// Headless app with prefab types. Add plugins, which register game types, before [`run_cli`]
+pub fn cli_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        ScenePlugin,
+        BasePrefabPlugin,
+    ));
+    app
+}
+
+/// Run prefab command from process arguments with types and migrations registered in `app`.
+///
+/// Game can ship own tool, which knows its components:
+/// ```ignore
+/// fn main() -> ExitCode {
+///     let mut app = cli_app();
+///     app.add_plugins(GamePrefabPlugin);
+///     run_cli(&mut app)
+/// }
+/// ```
+pub fn run_cli(app: &mut App) -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    let registry = app.world.resource::<AppTypeRegistry>().0.clone();
+    let migrations = app
+        .world
+        .get_resource::<PrefabMigrations>()
+        .and_then(|migrations| migrations.0.read().ok().map(|m| m.clone()))
+        .unwrap_or_default();
+
+    let res = match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["validate"] => validate(Path::new("assets/scenes"), &migrations, &registry),
+        ["validate", dir] => validate(Path::new(dir), &migrations, &registry),
+        ["convert", input, output] => {
+            convert_prefab_file(Path::new(input), Path::new(output), &migrations, &registry).map(
+                |report| {
+                    for dropped in report.dropped {
+                        eprintln!("dropped {} ({})", dropped.type_path, dropped.reason);
+                    }
+                    println!("converted {input} -> {output}");
+                    true
+                },
+            )
+        }
+        ["diff", old, new] => diff(Path::new(old), Path::new(new), &migrations, &registry),
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    match res {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn validate(
+    dir: &Path,
+    migrations: &Migrations,
+    registry: &TypeRegistryArc,
+) -> Result<bool, String> {
+    let files = find_prefab_files(dir);
+    if files.is_empty() {
+        return Err(format!("no prefabs found in {}", dir.display()));
+    }
+    let mut valid = true;
+    for file in files {
+        match load_prefab_file(&file, migrations, registry) {
+            Ok((_, report)) if report.dropped.is_empty() => println!("ok    {}", file.display()),
+            Ok((_, report)) => {
+                valid = false;
+                println!("FAIL  {}", file.display());
+                for dropped in report.dropped {
+                    match dropped.entity {
+                        Some(entity) => println!(
+                            "      entity {entity}: {} ({})",
+                            dropped.type_path, dropped.reason
+                        ),
+                        None => println!("      {} ({})", dropped.type_path, dropped.reason),
+                    }
+                }
+            }
+            Err(e) => {
+                valid = false;
+                println!("FAIL  {}\n      {e}", file.display());
+            }
+        }
+    }
+    Ok(valid)
+}
+
+fn diff(
+    old: &Path,
+    new: &Path,
+    migrations: &Migrations,
+    registry: &TypeRegistryArc,
+) -> Result<bool, String> {
+    let (old, _) = load_prefab_file(old, migrations, registry)?;
+    let (new, _) = load_prefab_file(new, migrations, registry)?;
+    let diffs = diff_prefabs(&old, &new, registry)?;
+    for diff in diffs.iter() {
+        println!("{diff}");
+    }
+    Ok(diffs.is_empty())
+}
@@ -0,0 +1,269 @@
+use std::{any::TypeId, sync::Arc};
+
+use bevy::{
+    ecs::entity::EntityHashMap,
+    prelude::*,
+    scene::{ron, serde::SceneDeserializer},
+    utils::HashSet,
+};
+use serde::de::DeserializeSeed;
+use space_shared::PrefabMarker;
+use space_undo::{AddedEntity, NewChange};
+
+use crate::{
+    editor_registry::EditorRegistry,
+    guid::{sort_prefab_scene, PrefabGuid},
+    save::{
+        extract_prefab_scene_with_children, prefab_subtree, selection_roots, serialize_prefab,
+        ChildrenPrefab,
+    },
+};
+
+/// Serialize selected prefab entities with their children to RON text for clipboard
+pub fn copy_entities(world: &World, entities: &[Entity]) -> Result<String, String> {
+    let roots = selection_roots(world, entities);
+    if roots.is_empty() {
+        return Err("no prefab entities selected".to_string());
+    }
+    let subtree = prefab_subtree(world, &roots);
+    let mut scene = extract_prefab_scene_with_children(world, &subtree);
+    scene.resources.clear();
+    sort_prefab_scene(world, &mut scene);
+    serialize_prefab(world, &scene)
+}
+
+/// Serialize `components` of `entity` to RON text for clipboard.
+/// Only components registered in [`EditorRegistry`] are copied
+pub fn copy_components(
+    world: &World,
+    entity: Entity,
+    components: &[TypeId],
+) -> Result<String, String> {
+    let registry = world.resource::<EditorRegistry>().clone();
+    let allow = {
+        let editor_types = registry.registry.read();
+        components
+            .iter()
+            .copied()
+            .filter(|type_id| editor_types.get(*type_id).is_some())
+            .collect::<HashSet<_>>()
+    };
+    if allow.is_empty() {
+        return Err("no editor components to copy".to_string());
+    }
+    let mut scene = DynamicSceneBuilder::from_world(world)
+        .with_filter(SceneFilter::Allowlist(allow))
+        .extract_entity(entity)
+        .build();
+    registry.convert_auto_structs(&mut scene);
+    serialize_prefab(world, &scene)
+}
+
+/// Deserialize clipboard text made by [`copy_entities`] or [`copy_components`]
+pub fn parse_clipboard(world: &World, text: &str) -> Result<DynamicScene, String> {
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+    let mut deserializer = ron::Deserializer::from_str(text).map_err(|e| e.to_string())?;
+    let mut scene = SceneDeserializer {
+        type_registry: &type_registry,
+    }
+    .deserialize(&mut deserializer)
+    .map_err(|e| e.to_string())?;
+    // Level settings are not pasted
+    scene.resources.clear();
+    Ok(scene)
+}
+
+/// Top entities of scene, which are not children of other scene entities
+fn scene_top_entities(scene: &DynamicScene) -> Vec<Entity> {
+    let children = scene
+        .entities
+        .iter()
+        .flat_map(|dyn_entity| dyn_entity.components.iter())
+        .filter_map(|component| ChildrenPrefab::from_reflect(component.as_ref()))
+        .flat_map(|children| children.0)
+        .collect::<HashSet<_>>();
+    scene
+        .entities
+        .iter()
+        .map(|dyn_entity| dyn_entity.entity)
+        .filter(|entity| !children.contains(entity))
+        .collect()
+}
+
+/// Spawn entities from clipboard text as new prefab entities.
+/// Each new top entity is recorded as [`AddedEntity`] change. Returns new top entities
+pub fn paste_entities(world: &mut World, text: &str) -> Result<Vec<Entity>, String> {
+    let mut scene = parse_clipboard(world, text)?;
+    if scene.entities.is_empty() {
+        return Err("clipboard has no entities".to_string());
+    }
+    let top = scene_top_entities(&scene);
+    for dyn_entity in scene.entities.iter_mut() {
+        // Pasted entities are new entities, they get their own guids
+        dyn_entity
+            .components
+            .retain(|component| !component.represents::<PrefabGuid>());
+        dyn_entity.components.push(Box::new(PrefabMarker));
+    }
+
+    let mut map = EntityHashMap::default();
+    scene
+        .write_to_world(world, &mut map)
+        .map_err(|e| e.to_string())?;
+
+    let top = top
+        .iter()
+        .filter_map(|entity| map.get(entity).copied())
+        .collect::<Vec<_>>();
+    for entity in top.iter() {
+        world.send_event(NewChange {
+            change: Arc::new(AddedEntity { entity: *entity }),
+        });
+    }
+    Ok(top)
+}
+
+/// Insert components of first top entity from clipboard text into `targets`.
+/// Existing components are overwritten. Added components are recorded by auto undo
+/// of their types as `ReflectedAddedComponent`. Returns count of pasted components
+pub fn paste_components(
+    world: &mut World,
+    text: &str,
+    targets: &[Entity],
+) -> Result<usize, String> {
+    let scene = parse_clipboard(world, text)?;
+    let source = scene_top_entities(&scene)
+        .first()
+        .and_then(|top| scene.entities.iter().find(|e| e.entity == *top))
+        .ok_or_else(|| "clipboard has no components".to_string())?;
+    // Identity and hierarchy of source entity are not copied to other entities
+    let skip = [TypeId::of::<ChildrenPrefab>(), TypeId::of::<PrefabGuid>()];
+
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let mut count = 0;
+    for component in source.components.iter() {
+        let Some(type_info) = component.get_represented_type_info() else {
+            continue;
+        };
+        if skip.contains(&type_info.type_id()) {
+            continue;
+        }
+        let Some(reflect_component) = type_registry
+            .get(type_info.type_id())
+            .and_then(|registration| registration.data::<ReflectComponent>())
+        else {
+            warn!("{} is not a component", type_info.type_path());
+            continue;
+        };
+        for target in targets {
+            if let Some(mut entity) = world.get_entity_mut(*target) {
+                reflect_component.apply_or_insert(&mut entity, component.as_ref(), &type_registry);
+            }
+        }
+        count += 1;
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> World {
+        let mut world = World::new();
+        let registry = AppTypeRegistry::default();
+        {
+            let mut registry = registry.write();
+            registry.register::<Transform>();
+            registry.register::<Vec3>();
+            registry.register::<Quat>();
+            registry.register::<f32>();
+            registry.register::<Name>();
+            registry.register::<std::borrow::Cow<'static, str>>();
+            registry.register::<PrefabMarker>();
+            registry.register::<PrefabGuid>();
+            registry.register::<bevy::utils::Uuid>();
+            registry.register::<ChildrenPrefab>();
+            registry.register::<Entity>();
+            registry.register::<Vec<Entity>>();
+        }
+        world.insert_resource(registry);
+        let mut editor_registry = EditorRegistry::default();
+        editor_registry.register::<Transform>();
+        editor_registry.register::<Name>();
+        editor_registry.register::<ChildrenPrefab>();
+        editor_registry.register::<PrefabGuid>();
+        world.insert_resource(editor_registry);
+        world.init_resource::<Events<NewChange>>();
+        world
+    }
+
+    #[test]
+    fn copy_paste_entity_subtree() {
+        let mut world = setup();
+        let guid = PrefabGuid::new_random();
+        let child = world.spawn((PrefabMarker, Name::new("wheel"))).id();
+        let root = world
+            .spawn((
+                PrefabMarker,
+                guid,
+                Name::new("car"),
+                Transform::from_xyz(1., 2., 3.),
+            ))
+            .add_child(child)
+            .id();
+
+        let text = copy_entities(&world, &[root, child]).unwrap();
+        // Pasted into other editor instance
+        let mut other = setup();
+        let top = paste_entities(&mut other, &text).unwrap();
+        assert_eq!(top.len(), 1);
+        assert_eq!(other.get::<Name>(top[0]).unwrap().as_str(), "car");
+        assert_eq!(
+            other.get::<Transform>(top[0]).unwrap().translation,
+            Vec3::new(1., 2., 3.)
+        );
+        let children = other.get::<ChildrenPrefab>(top[0]).unwrap();
+        assert_eq!(children.0.len(), 1);
+        assert_eq!(other.get::<Name>(children.0[0]).unwrap().as_str(), "wheel");
+        assert!(other.get::<PrefabMarker>(children.0[0]).is_some());
+        assert_eq!(other.resource::<Events<NewChange>>().len(), 1);
+        assert!(other.get::<PrefabGuid>(top[0]).is_none());
+
+        // Pasted into the same editor
+        let mut schedule = Schedule::default();
+        schedule.add_systems(crate::guid::assign_prefab_guids);
+        schedule.run(&mut world);
+        let top = paste_entities(&mut world, &text).unwrap();
+        schedule.run(&mut world);
+        assert_eq!(world.get::<PrefabGuid>(root), Some(&guid));
+        let pasted = world.get::<PrefabGuid>(top[0]).unwrap();
+        assert_ne!(pasted, &guid);
+    }
+
+    #[test]
+    fn copy_paste_components() {
+        let mut world = setup();
+        let source = world
+            .spawn((
+                PrefabMarker,
+                Name::new("source"),
+                Transform::from_xyz(4., 0., 0.),
+            ))
+            .id();
+        let targets = [
+            world.spawn(PrefabMarker).id(),
+            world.spawn((PrefabMarker, Transform::default())).id(),
+        ];
+
+        let text = copy_components(&world, source, &[TypeId::of::<Transform>()]).unwrap();
+        assert!(!text.contains("bevy_core::name::Name"));
+        assert_eq!(paste_components(&mut world, &text, &targets).unwrap(), 1);
+        for target in targets {
+            assert_eq!(world.get::<Transform>(target).unwrap().translation.x, 4.);
+            assert!(world.get::<Name>(target).is_none());
+        }
+        assert!(paste_components(&mut world, "(", &targets).is_err());
+    }
+}
@@ -0,0 +1,111 @@
+use bevy::{
+    ecs::{
+        entity::{EntityMapper, MapEntities},
+        reflect::ReflectMapEntities,
+    },
+    render::mesh::skinning::{SkinnedMesh, SkinnedMeshInverseBindposes},
+};
+
+use super::AssetPathField;
+use crate::ext::*;
+
+/// Prefab component for [`SkinnedMesh`]. Joints are entities of the same prefab
+#[derive(Component, Reflect, Clone, Default)]
+#[reflect(Component, Default, MapEntities)]
+pub struct SkinnedMeshPrefab {
+    pub inverse_bindposes: AssetPathField<SkinnedMeshInverseBindposes>,
+    pub joints: Vec<Entity>,
+}
+
+impl SkinnedMeshPrefab {
+    pub fn to_skinned_mesh(&self, asset_server: &AssetServer) -> SkinnedMesh {
+        SkinnedMesh {
+            inverse_bindposes: self.inverse_bindposes.load(asset_server),
+            joints: self.joints.clone(),
+        }
+    }
+}
+
+impl MapEntities for SkinnedMeshPrefab {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        for joint in self.joints.iter_mut() {
+            *joint = entity_mapper.map_entity(*joint);
+        }
+    }
+}
+
+/// Prefab component for [`AnimationPlayer`] with animation clips of unpacked model.
+/// Clip with `autoplay` index is played in [`EditorState::Game`] state
+///
+/// [`EditorState::Game`]: crate::EditorState::Game
+#[derive(Component, Reflect, Clone, Default)]
+#[reflect(Component, Default)]
+pub struct AnimationPlayerPrefab {
+    pub clips: Vec<AssetPathField<AnimationClip>>,
+    pub autoplay: Option<usize>,
+    pub repeat: bool,
+}
+
+impl AnimationPlayerPrefab {
+    /// Animation player with started autoplay clip, if `play` is true
+    pub fn to_player(&self, asset_server: &AssetServer, play: bool) -> AnimationPlayer {
+        let mut player = AnimationPlayer::default();
+        let clip = self.autoplay.and_then(|idx| self.clips.get(idx));
+        if let (true, Some(clip)) = (play, clip) {
+            player.play(clip.load(asset_server));
+            if self.repeat {
+                player.repeat();
+            }
+        }
+        player
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::entity::{EntityHashMap, SceneEntityMapper};
+
+    use super::*;
+
+    #[test]
+    fn map_skin_joints() {
+        let mut world = World::new();
+        let old = Entity::from_raw(10);
+        let new = world.spawn_empty().id();
+        let mut skin = SkinnedMeshPrefab {
+            inverse_bindposes: "models/fox.glb#Skin0".into(),
+            joints: vec![old],
+        };
+        let mut map = EntityHashMap::default();
+        map.insert(old, new);
+        SceneEntityMapper::world_scope(&mut map, &mut world, |_, mapper| {
+            skin.map_entities(mapper);
+        });
+        assert_eq!(skin.joints, vec![new]);
+    }
+
+    #[test]
+    fn autoplay_only_when_playing() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<AnimationClip>();
+        let prefab = AnimationPlayerPrefab {
+            clips: vec!["models/fox.glb#Animation0".into()],
+            autoplay: Some(0),
+            repeat: true,
+        };
+        let server = app.world.resource::<AssetServer>();
+
+        let player = prefab.to_player(server, true);
+        assert_eq!(
+            player.animation_clip().path().map(ToString::to_string),
+            Some("models/fox.glb#Animation0".to_string())
+        );
+        assert!(player.is_playing_clip(&server.load("models/fox.glb#Animation0")));
+        assert!(prefab
+            .to_player(server, false)
+            .animation_clip()
+            .path()
+            .is_none());
+    }
+}
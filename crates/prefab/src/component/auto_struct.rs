@@ -0,0 +1,231 @@
+use bevy::{
+    prelude::*,
+    reflect::{GetPath, ReflectMut},
+    utils::HashMap,
+};
+
+/// Saved form of component `T`, which is registered with `editor_auto_struct`.
+/// Asset handles can not be saved, so all handles in `data` are replaced with default handles
+/// and asset paths of them are stored in `asset_paths`.
+///
+/// Supported handles are `Handle<Image>`, `Handle<Mesh>`, `Handle<StandardMaterial>` and
+/// `Handle<AudioSource>`, including handles in nested structs, tuples, enums and `Vec`s
+#[derive(Component, Reflect, Clone, Default)]
+#[reflect(Component)]
+pub struct AutoStruct<T: Reflect + Default + Clone> {
+    pub data: T,
+    /// Asset path of each handle by reflect path of its field, like `material.texture` or `textures[1]`
+    pub asset_paths: HashMap<String, String>,
+}
+
+impl<T: Reflect + FromReflect + Default + Clone> AutoStruct<T> {
+    pub fn new(data: &T) -> Self {
+        let mut data = data.clone();
+        let mut asset_paths = HashMap::new();
+        take_asset_paths(data.as_reflect_mut(), String::new(), &mut asset_paths);
+        Self { data, asset_paths }
+    }
+
+    /// Change saved asset paths. Returns true if any path is changed
+    pub fn rename_assets(&mut self, rename: impl Fn(&str) -> Option<String>) -> bool {
+        let mut changed = false;
+        for path in self.asset_paths.values_mut() {
+            if let Some(renamed) = rename(path) {
+                *path = renamed;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Component with handles loaded from saved asset paths
+    pub fn get_data(&self, assets: &AssetServer) -> T {
+        let mut res = self.data.clone();
+        for (field_path, asset_path) in self.asset_paths.iter() {
+            let Ok(field) = res.reflect_path_mut(field_path.as_str()) else {
+                warn!(
+                    "Field {} not found in {}",
+                    field_path,
+                    std::any::type_name::<T>()
+                );
+                continue;
+            };
+            if !load_asset(field, asset_path, assets) {
+                warn!(
+                    "Field {} of {} is not a supported asset handle",
+                    field_path,
+                    std::any::type_name::<T>()
+                );
+            }
+        }
+        res
+    }
+}
+
+/// Replace all handles in `value` with default handles and collect their asset paths by field paths.
+/// Handles without asset path (like assets created in code) are not saved
+fn take_asset_paths(value: &mut dyn Reflect, path: String, paths: &mut HashMap<String, String>) {
+    if let Some(asset_path) = take_handle(value) {
+        if let Some(asset_path) = asset_path {
+            paths.insert(path, asset_path);
+        }
+        return;
+    }
+
+    match value.reflect_mut() {
+        ReflectMut::Struct(s) => {
+            for idx in 0..s.field_len() {
+                let name = s.name_at(idx).unwrap_or_default().to_string();
+                if let Some(field) = s.field_at_mut(idx) {
+                    take_asset_paths(field, field_path(&path, &name), paths);
+                }
+            }
+        }
+        ReflectMut::TupleStruct(s) => {
+            for idx in 0..s.field_len() {
+                if let Some(field) = s.field_mut(idx) {
+                    take_asset_paths(field, format!("{path}.{idx}"), paths);
+                }
+            }
+        }
+        ReflectMut::Tuple(s) => {
+            for idx in 0..s.field_len() {
+                if let Some(field) = s.field_mut(idx) {
+                    take_asset_paths(field, format!("{path}.{idx}"), paths);
+                }
+            }
+        }
+        ReflectMut::List(list) => {
+            for idx in 0..list.len() {
+                if let Some(item) = list.get_mut(idx) {
+                    take_asset_paths(item, format!("{path}[{idx}]"), paths);
+                }
+            }
+        }
+        ReflectMut::Array(array) => {
+            for idx in 0..array.len() {
+                if let Some(item) = array.get_mut(idx) {
+                    take_asset_paths(item, format!("{path}[{idx}]"), paths);
+                }
+            }
+        }
+        ReflectMut::Enum(e) => {
+            for idx in 0..e.field_len() {
+                let name = e.name_at(idx).map(ToString::to_string);
+                if let Some(field) = e.field_at_mut(idx) {
+                    let path = name
+                        .map_or_else(|| format!("{path}.{idx}"), |name| field_path(&path, &name));
+                    take_asset_paths(field, path, paths);
+                }
+            }
+        }
+        // Map keys can not be used in reflect path
+        ReflectMut::Map(_) | ReflectMut::Value(_) => {}
+    }
+}
+
+fn field_path(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{path}.{name}")
+    }
+}
+
+/// Replace supported handle with default handle. Returns `None` if `value` is not a handle
+fn take_handle(value: &mut dyn Reflect) -> Option<Option<String>> {
+    take_typed_handle::<Image>(value)
+        .or_else(|| take_typed_handle::<Mesh>(value))
+        .or_else(|| take_typed_handle::<StandardMaterial>(value))
+        .or_else(|| take_typed_handle::<AudioSource>(value))
+}
+
+fn take_typed_handle<A: Asset>(value: &mut dyn Reflect) -> Option<Option<String>> {
+    let handle = value.downcast_mut::<Handle<A>>()?;
+    let path = handle.path().map(ToString::to_string);
+    *handle = Handle::default();
+    Some(path)
+}
+
+/// Load asset into supported handle. Returns false if `value` is not a handle
+fn load_asset(value: &mut dyn Reflect, path: &str, assets: &AssetServer) -> bool {
+    load_typed_asset::<Image>(value, path, assets)
+        || load_typed_asset::<Mesh>(value, path, assets)
+        || load_typed_asset::<StandardMaterial>(value, path, assets)
+        || load_typed_asset::<AudioSource>(value, path, assets)
+}
+
+fn load_typed_asset<A: Asset>(value: &mut dyn Reflect, path: &str, assets: &AssetServer) -> bool {
+    value
+        .downcast_mut::<Handle<A>>()
+        .map(|handle| *handle = assets.load(path.to_string()))
+        .is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default, Clone, Reflect)]
+    struct TestMaterial {
+        texture: Handle<Image>,
+        color: Color,
+    }
+
+    #[derive(Debug, Default, Clone, Reflect, Component)]
+    #[reflect(Default, Component)]
+    struct TestAuto {
+        value: bool,
+        mesh: Handle<Mesh>,
+        material: TestMaterial,
+        sounds: Vec<Handle<AudioSource>>,
+        fallback: Option<Handle<StandardMaterial>>,
+    }
+
+    fn test_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Image>()
+            .init_asset::<Mesh>()
+            .init_asset::<StandardMaterial>()
+            .init_asset::<AudioSource>();
+        app
+    }
+
+    #[test]
+    fn get_auto_struct_data() {
+        let app = test_app();
+        let server = app.world.resource::<AssetServer>();
+        let data = TestAuto {
+            value: true,
+            mesh: server.load("models/car.glb#Mesh0/Primitive0"),
+            material: TestMaterial {
+                texture: server.load("textures/grid.png"),
+                color: Color::RED,
+            },
+            sounds: vec![Handle::default(), server.load("sounds/jump.ogg")],
+            fallback: Some(server.load("materials/red.mat.ron")),
+        };
+
+        let prefab = AutoStruct::new(&data);
+        assert!(prefab.data.material.texture.path().is_none());
+        assert_eq!(prefab.asset_paths.len(), 4);
+        assert_eq!(
+            prefab.asset_paths.get("material.texture").unwrap(),
+            "textures/grid.png"
+        );
+        assert_eq!(
+            prefab.asset_paths.get("sounds[1]").unwrap(),
+            "sounds/jump.ogg"
+        );
+        assert!(prefab.asset_paths.contains_key("fallback.0"));
+
+        let restored = prefab.get_data(server);
+        assert!(restored.value);
+        assert_eq!(restored.material.color, Color::RED);
+        assert_eq!(restored.mesh, data.mesh);
+        assert_eq!(restored.material.texture, data.material.texture);
+        assert_eq!(restored.sounds, data.sounds);
+        assert_eq!(restored.fallback, data.fallback);
+    }
+}
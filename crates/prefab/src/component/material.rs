@@ -1,5 +1,4 @@
-use std::fs;
-
+use super::AssetPathField;
 use crate::ext::*;
 use bevy_inspector_egui::{inspector_options::ReflectInspectorOptions, InspectorOptions};
 
@@ -8,24 +7,24 @@ use bevy_inspector_egui::{inspector_options::ReflectInspectorOptions, InspectorO
 #[reflect(Default, Component, InspectorOptions)]
 pub struct MaterialPrefab {
     pub base_color: Color,
-    pub base_color_texture: String,
+    pub base_color_texture: AssetPathField<Image>,
     pub emissive: Color,
-    pub emissive_texture: String,
+    pub emissive_texture: AssetPathField<Image>,
     pub perceptual_roughness: f32,
     #[inspector(min = 0.0, max = 1.0)]
     pub metallic: f32,
-    pub metallic_roughness_texture: String,
+    pub metallic_roughness_texture: AssetPathField<Image>,
     #[inspector(min = 0.0, max = 1.0)]
     pub reflectance: f32,
-    pub normal_map_texture: String,
+    pub normal_map_texture: AssetPathField<Image>,
     pub flip_normal_map_y: bool,
-    pub occlusion_texture: String,
+    pub occlusion_texture: AssetPathField<Image>,
     pub double_sided: bool,
     pub unlit: bool,
     pub fog_enabled: bool,
     pub alpha_mode: AlphaMode,
     pub depth_bias: f32,
-    pub depth_map: String,
+    pub depth_map: AssetPathField<Image>,
     pub parallax_depth_scale: f32,
     pub parallax_mapping_method: ParallaxMappingMethod,
     pub max_parallax_layer_count: f32,
@@ -35,27 +34,27 @@ impl Default for MaterialPrefab {
     fn default() -> Self {
         Self {
             base_color: Color::rgb(1.0, 1.0, 1.0),
-            base_color_texture: String::default(),
+            base_color_texture: AssetPathField::default(),
             emissive: Color::BLACK,
-            emissive_texture: String::default(),
+            emissive_texture: AssetPathField::default(),
             // Matches Blender's default roughness.
             perceptual_roughness: 0.5,
             // Metallic should generally be set to 0.0 or 1.0.
             metallic: 0.0,
-            metallic_roughness_texture: String::default(),
+            metallic_roughness_texture: AssetPathField::default(),
             // Minimum real-world reflectance is 2%, most materials between 2-5%
             // Expressed in a linear scale and equivalent to 4% reflectance see
             // <https://google.github.io/filament/Material%20Properties.pdf>
             reflectance: 0.5,
-            occlusion_texture: String::default(),
-            normal_map_texture: String::default(),
+            occlusion_texture: AssetPathField::default(),
+            normal_map_texture: AssetPathField::default(),
             flip_normal_map_y: false,
             double_sided: false,
             unlit: false,
             fog_enabled: true,
             alpha_mode: AlphaMode::Opaque,
             depth_bias: 0.0,
-            depth_map: String::default(),
+            depth_map: AssetPathField::default(),
             parallax_depth_scale: 0.1,
             max_parallax_layer_count: 16.0,
             parallax_mapping_method: ParallaxMappingMethod::Occlusion,
@@ -66,12 +65,20 @@ impl Default for MaterialPrefab {
 impl MaterialPrefab {
     /// Convert [`MaterialPrefab`] to [`StandardMaterial`]
     pub fn to_material(&self, asset_server: &AssetServer) -> StandardMaterial {
-        let base_color_texture = try_image(&self.base_color_texture, asset_server);
-        let emissive_texture = try_image(&self.emissive_texture, asset_server);
-        let metallic_roughness_texture = try_image(&self.metallic_roughness_texture, asset_server);
-        let normal_map_texture = try_image(&self.normal_map_texture, asset_server);
-        let occlusion_texture = try_image(&self.occlusion_texture, asset_server);
-        let depth_map = try_image(&self.depth_map, asset_server);
+        self.to_material_with(|path| try_image(path, asset_server))
+    }
+
+    /// Convert [`MaterialPrefab`] to [`StandardMaterial`] with textures loaded by `load_image`
+    pub fn to_material_with(
+        &self,
+        mut load_image: impl FnMut(&AssetPathField<Image>) -> Option<Handle<Image>>,
+    ) -> StandardMaterial {
+        let base_color_texture = load_image(&self.base_color_texture);
+        let emissive_texture = load_image(&self.emissive_texture);
+        let metallic_roughness_texture = load_image(&self.metallic_roughness_texture);
+        let normal_map_texture = load_image(&self.normal_map_texture);
+        let occlusion_texture = load_image(&self.occlusion_texture);
+        let depth_map = load_image(&self.depth_map);
         StandardMaterial {
             base_color: self.base_color,
             base_color_texture,
@@ -96,6 +103,41 @@ impl MaterialPrefab {
             ..Default::default()
         }
     }
+
+    /// Create [`MaterialPrefab`] from [`StandardMaterial`]. Textures are stored by their
+    /// asset paths, like `models/car.glb#Texture0`, textures without path are dropped
+    pub fn from_material(material: &StandardMaterial) -> Self {
+        Self {
+            base_color: material.base_color,
+            base_color_texture: texture_path(&material.base_color_texture),
+            emissive: material.emissive,
+            emissive_texture: texture_path(&material.emissive_texture),
+            perceptual_roughness: material.perceptual_roughness,
+            metallic: material.metallic,
+            metallic_roughness_texture: texture_path(&material.metallic_roughness_texture),
+            reflectance: material.reflectance,
+            normal_map_texture: texture_path(&material.normal_map_texture),
+            flip_normal_map_y: material.flip_normal_map_y,
+            occlusion_texture: texture_path(&material.occlusion_texture),
+            double_sided: material.double_sided,
+            unlit: material.unlit,
+            fog_enabled: material.fog_enabled,
+            alpha_mode: material.alpha_mode,
+            depth_bias: material.depth_bias,
+            depth_map: texture_path(&material.depth_map),
+            parallax_depth_scale: material.parallax_depth_scale,
+            parallax_mapping_method: material.parallax_mapping_method,
+            max_parallax_layer_count: material.max_parallax_layer_count,
+        }
+    }
+}
+
+fn texture_path(texture: &Option<Handle<Image>>) -> AssetPathField<Image> {
+    texture
+        .as_ref()
+        .and_then(|handle| handle.path())
+        .map(|path| AssetPathField::new(path.to_string()))
+        .unwrap_or_default()
 }
 
 /// Prefab component that store parameters and asset paths for creating [`StandardMaterial`]
@@ -104,14 +146,14 @@ impl MaterialPrefab {
 #[reflect(Default, Component, InspectorOptions)]
 pub struct ColorMaterialPrefab {
     pub color: Color,
-    pub texture: String,
+    pub texture: AssetPathField<Image>,
 }
 
 impl Default for ColorMaterialPrefab {
     fn default() -> Self {
         Self {
             color: Color::rgb(1.0, 1.0, 1.0),
-            texture: String::default(),
+            texture: AssetPathField::default(),
         }
     }
 }
@@ -127,11 +169,14 @@ impl ColorMaterialPrefab {
     }
 }
 
-pub fn try_image(path: &String, asset_server: &AssetServer) -> Option<Handle<Image>> {
-    if path.is_empty() || fs::metadata(format!("assets/{path}")).is_err() {
-        None
+pub fn try_image(
+    path: &AssetPathField<Image>,
+    asset_server: &AssetServer,
+) -> Option<Handle<Image>> {
+    if path.exists() {
+        Some(path.load(asset_server))
     } else {
-        Some(asset_server.load(path))
+        None
     }
 }
 
@@ -150,7 +195,7 @@ mod tests {
 
         let server = app.world.resource::<AssetServer>();
 
-        assert!(try_image(&String::new(), server).is_none());
+        assert!(try_image(&AssetPathField::default(), server).is_none());
     }
 
     #[test]
@@ -165,7 +210,36 @@ mod tests {
         let path = "test_asset.png";
         let server = app.world.resource::<AssetServer>();
 
-        assert!(try_image(&String::from(path), server).is_some());
+        assert!(try_image(&AssetPathField::new(path), server).is_some());
+    }
+
+    #[test]
+    fn material_prefab_from_material() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            ImagePlugin::default(),
+        ));
+        let server = app.world.resource::<AssetServer>();
+
+        let material = StandardMaterial {
+            base_color: Color::RED,
+            base_color_texture: Some(server.load("models/car.glb#Texture0")),
+            normal_map_texture: Some(Handle::default()),
+            metallic: 0.8,
+            alpha_mode: AlphaMode::Mask(0.5),
+            ..default()
+        };
+        let prefab = MaterialPrefab::from_material(&material);
+        assert_eq!(prefab.base_color, Color::RED);
+        assert_eq!(
+            prefab.base_color_texture.as_str(),
+            "models/car.glb#Texture0"
+        );
+        assert!(prefab.normal_map_texture.is_empty());
+        assert_eq!(prefab.metallic, 0.8);
+        assert_eq!(prefab.alpha_mode, AlphaMode::Mask(0.5));
     }
 
     #[test]
@@ -180,13 +254,13 @@ mod tests {
         let path = "fake_asset.png";
         let server = app.world.resource::<AssetServer>();
 
-        assert!(try_image(&String::from(path), server).is_none());
+        assert!(try_image(&AssetPathField::new(path), server).is_none());
     }
 
     #[test]
     fn color_material_prefab_with_texture() {
         let prefab = ColorMaterialPrefab {
-            texture: String::from("test_asset.png"),
+            texture: AssetPathField::new("test_asset.png"),
             ..default()
         };
 
@@ -207,7 +281,7 @@ mod tests {
     #[test]
     fn color_material_prefab_with_wrong_texture() {
         let prefab = ColorMaterialPrefab {
-            texture: String::from("fake_asset.png"),
+            texture: AssetPathField::new("fake_asset.png"),
             ..default()
         };
 
@@ -18,10 +18,19 @@ pub use camera::*;
 pub mod player_start;
 pub use player_start::*;
 
-/// NOT USED. Planned to be used in future for auto structs
+/// Module contatins typed asset paths for prefab components
 pub mod path;
+pub use path::*;
 
-use bevy::{prelude::*, reflect::*, utils::HashMap};
+/// Module contatins structures for skinned meshes and animations of models
+pub mod animation;
+pub use animation::*;
+
+/// Module contatins saving of components with asset handles
+pub mod auto_struct;
+pub use auto_struct::*;
+
+use bevy::prelude::*;
 
 /// External dependencies
 pub mod ext {
@@ -32,7 +41,7 @@ pub mod ext {
 #[derive(Component, Reflect, Clone)]
 #[reflect(Component)]
 pub struct GltfPrefab {
-    pub path: String,
+    pub path: AssetPathField<bevy::gltf::Gltf>,
     pub scene: String,
 }
 
@@ -40,7 +49,7 @@ impl Default for GltfPrefab {
     fn default() -> Self {
         Self {
             scene: "Scene0".to_string(),
-            path: String::new(),
+            path: AssetPathField::default(),
         }
     }
 }
@@ -59,60 +68,6 @@ pub struct SceneAutoChild;
 #[allow(dead_code)]
 pub struct AutoScenePersistence(String);
 
-/// Not used right now. Planned to be easy method for creating prefab structs from usual structs with assets
-#[derive(Component, Reflect, Clone, Default)]
-#[reflect(Component)]
-pub struct AutoStruct<T: Reflect + Default + Clone> {
-    pub data: T,
-    pub asset_paths: HashMap<String, String>,
-}
-
-impl<T: Reflect + FromReflect + Default + Clone> AutoStruct<T> {
-    pub fn new(data: &T, _assets: &AssetServer) -> Self {
-        let mut paths = HashMap::new();
-
-        if let ReflectRef::Struct(s) = data.reflect_ref() {
-            for idx in 0..s.field_len() {
-                let field_name = s.name_at(idx).unwrap_or("unknown");
-                let Some(field) = s.field_at(idx) else {
-                    continue;
-                };
-                if let Some(handle) = field.downcast_ref::<Handle<Image>>() {
-                    if let Some(path) = handle.path() {
-                        let path = path.path().to_str().unwrap_or("./assets").to_string();
-                        paths.insert(field_name.to_string(), path);
-                    }
-                }
-            }
-        }
-
-        Self {
-            data: data.clone(),
-            asset_paths: paths,
-        }
-    }
-
-    pub fn get_data(&self, assets: &AssetServer) -> T {
-        let mut res = self.data.clone();
-        {
-            let res_reflect = res.as_reflect_mut();
-            if let ReflectMut::Struct(s) = res_reflect.reflect_mut() {
-                for (field_name, path) in self.asset_paths.iter() {
-                    if let Some(field) = s.field_mut(field_name) {
-                        #[allow(clippy::option_if_let_else)]
-                        if let Some(handle) = field.downcast_mut::<Handle<Image>>() {
-                            *handle = assets.load(path);
-                        } else if let Some(handle) = field.downcast_mut::<Handle<Mesh>>() {
-                            *handle = assets.load(path);
-                        }
-                    }
-                }
-            }
-        }
-        T::default()
-    }
-}
-
 /// This component used in prefab to determine links between entities. It is needed to create custom UI in `bevy_inspector_egui`. You must implement the [`MapEntities`](bevy::ecs::entity::MapEntities) trait for your component to make it work. See the `FollowCamera` struct from `examples/platformer.rs`.
 #[derive(Reflect, Clone)]
 #[reflect(Default)]
@@ -133,10 +88,11 @@ impl Default for EntityLink {
 #[derive(Component, Reflect, Clone, Default)]
 #[reflect(Component, Default)]
 pub struct AssetMesh {
-    pub path: String,
+    pub path: AssetPathField<Mesh>,
 }
 
-/// Component to define path to material asset that will be loaded after prefab spawn
+/// Component to define path to material asset that will be loaded after prefab spawn.
+/// Path is a material of model, like `models/car.glb#Material0`, or a `.mat.ron` material file
 #[cfg(not(tarpaulin_include))]
 #[derive(Component, Reflect, Clone, Default)]
 #[reflect(Component, Default)]
@@ -157,31 +113,7 @@ mod tests {
     #[test]
     fn gltf_prefab_default() {
         let prefab = GltfPrefab::default();
-        assert_eq!(prefab.path, String::new());
+        assert!(prefab.path.is_empty());
         assert_eq!(prefab.scene, "Scene0".to_string());
     }
-
-    // Not really useful test
-    #[test]
-    fn get_auto_struct_data() {
-        #[derive(Debug, Default, Clone, Reflect, Component, PartialEq, Eq)]
-        #[reflect(Default, Component)]
-        pub struct TestAuto {
-            pub value: bool,
-        }
-
-        let mut app = App::new();
-        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
-            .register_type::<TestAuto>();
-
-        app.update();
-
-        let server = app.world.resource::<AssetServer>();
-        let prefab = AutoStruct::<TestAuto>::new(&TestAuto { value: false }, server);
-
-        app.update();
-
-        let server = app.world.resource::<AssetServer>();
-        assert_eq!(prefab.get_data(server), TestAuto { value: false });
-    }
 }
@@ -1,10 +1,231 @@
-//Planned to use trait for support file dialog per field
+use std::{fmt, marker::PhantomData, path::Path};
 
-use space_shared::ext::egui_file;
+use bevy::{
+    gltf::Gltf, prelude::*, reflect::TypePath, render::mesh::skinning::SkinnedMeshInverseBindposes,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-/// NOT USED. Will be used to auto converting reflected structures with assets to saveble AutoStructs
-pub trait AssetPath {
-    fn get_filter<T: std::ops::Deref>(&self) -> egui_file::Filter<T>;
-    fn set_path(&mut self, path: &str);
-    fn get_path_mut(&mut self) -> &mut String;
+/// Asset type, which can be referenced by [`AssetPathField`]
+pub trait AssetPathKind: Asset + TypePath {
+    /// File extensions of assets of this type, without leading dot
+    const EXTENSIONS: &'static [&'static str];
+    /// Sub asset label added to picked file, when asset is stored inside other file
+    const DEFAULT_LABEL: Option<&'static str> = None;
+}
+
+impl AssetPathKind for Image {
+    const EXTENSIONS: &'static [&'static str] = &[
+        "png", "jpg", "jpeg", "bmp", "tga", "ktx2", "dds", "hdr", "exr",
+    ];
+}
+
+impl AssetPathKind for Mesh {
+    const EXTENSIONS: &'static [&'static str] = &["glb", "gltf"];
+    const DEFAULT_LABEL: Option<&'static str> = Some("Mesh0/Primitive0");
+}
+
+impl AssetPathKind for Gltf {
+    const EXTENSIONS: &'static [&'static str] = &["glb", "gltf"];
+}
+
+impl AssetPathKind for AnimationClip {
+    const EXTENSIONS: &'static [&'static str] = &["glb", "gltf"];
+    const DEFAULT_LABEL: Option<&'static str> = Some("Animation0");
+}
+
+impl AssetPathKind for SkinnedMeshInverseBindposes {
+    const EXTENSIONS: &'static [&'static str] = &["glb", "gltf"];
+    const DEFAULT_LABEL: Option<&'static str> = Some("Skin0");
+}
+
+impl AssetPathKind for DynamicScene {
+    const EXTENSIONS: &'static [&'static str] = &["scn.ron", "scn.bin"];
+}
+
+/// Path to asset of type `T`, relative to assets folder. Can contain label of sub asset,
+/// like `models/car.glb#Mesh0/Primitive0`.
+///
+/// Saved as plain string, so prefabs with `String` paths are loaded without migration.
+/// Editor shows it as file picker with check of missing files
+#[derive(Reflect)]
+#[reflect(Default, Serialize, Deserialize)]
+pub struct AssetPathField<T: AssetPathKind> {
+    pub path: String,
+    #[reflect(ignore)]
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T: AssetPathKind> AssetPathField<T> {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            marker: PhantomData,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.path
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.path.is_empty()
+    }
+
+    /// Path to file without sub asset label
+    pub fn file(&self) -> &str {
+        self.path
+            .split_once('#')
+            .map_or(self.path.as_str(), |(file, _)| file)
+    }
+
+    /// Sub asset label, like `Scene0` or `Mesh0/Primitive0`
+    pub fn label(&self) -> Option<&str> {
+        self.path.split_once('#').map(|(_, label)| label)
+    }
+
+    /// Is file extension one of [`AssetPathKind::EXTENSIONS`] of `T`
+    pub fn has_valid_extension(&self) -> bool {
+        Self::matches_extension(self.file())
+    }
+
+    /// Is file at `path` can be referenced by this field
+    pub fn matches_extension(path: &str) -> bool {
+        let path = path.to_lowercase();
+        T::EXTENSIONS
+            .iter()
+            .any(|ext| path.ends_with(&format!(".{ext}")))
+    }
+
+    /// Path to picked file with [`AssetPathKind::DEFAULT_LABEL`]
+    pub fn from_file(file: &str) -> Self {
+        T::DEFAULT_LABEL.map_or_else(
+            || Self::new(file),
+            |label| Self::new(format!("{file}#{label}")),
+        )
+    }
+
+    /// Is file exists in assets folder
+    pub fn exists(&self) -> bool {
+        !self.is_empty() && Path::new("assets").join(self.file()).is_file()
+    }
+
+    /// Load referenced asset. Empty path gives default handle
+    pub fn load(&self, asset_server: &AssetServer) -> Handle<T> {
+        if self.is_empty() {
+            Handle::default()
+        } else {
+            asset_server.load(self.path.clone())
+        }
+    }
+}
+
+impl<T: AssetPathKind> Default for AssetPathField<T> {
+    fn default() -> Self {
+        Self::new(String::new())
+    }
+}
+
+impl<T: AssetPathKind> Clone for AssetPathField<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.path.clone())
+    }
+}
+
+impl<T: AssetPathKind> PartialEq for AssetPathField<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path
+    }
+}
+
+impl<T: AssetPathKind> Eq for AssetPathField<T> {}
+
+impl<T: AssetPathKind> fmt::Debug for AssetPathField<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.path, f)
+    }
+}
+
+impl<T: AssetPathKind> fmt::Display for AssetPathField<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.path)
+    }
+}
+
+impl<T: AssetPathKind> From<&str> for AssetPathField<T> {
+    fn from(path: &str) -> Self {
+        Self::new(path)
+    }
+}
+
+impl<T: AssetPathKind> From<String> for AssetPathField<T> {
+    fn from(path: String) -> Self {
+        Self::new(path)
+    }
+}
+
+impl<T: AssetPathKind> Serialize for AssetPathField<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.path)
+    }
+}
+
+impl<'de, T: AssetPathKind> Deserialize<'de> for AssetPathField<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        reflect::serde::{TypedReflectDeserializer, TypedReflectSerializer},
+        scene::ron,
+    };
+    use serde::de::DeserializeSeed;
+
+    use super::*;
+
+    #[test]
+    fn path_with_label() {
+        let path = AssetPathField::<Mesh>::new("models/car.glb#Mesh0/Primitive0");
+        assert_eq!(path.file(), "models/car.glb");
+        assert_eq!(path.label(), Some("Mesh0/Primitive0"));
+        assert!(path.has_valid_extension());
+        assert!(!AssetPathField::<Image>::new("models/car.glb").has_valid_extension());
+        assert!(AssetPathField::<DynamicScene>::matches_extension(
+            "scenes/Level.SCN.RON"
+        ));
+        assert!(!AssetPathField::<Image>::default().exists());
+        assert_eq!(
+            AssetPathField::<Mesh>::from_file("models/car.glb"),
+            AssetPathField::new("models/car.glb#Mesh0/Primitive0")
+        );
+        assert_eq!(
+            AssetPathField::<Image>::from_file("a.png").as_str(),
+            "a.png"
+        );
+    }
+
+    #[test]
+    fn saved_as_plain_string() {
+        let mut registry = bevy::reflect::TypeRegistry::default();
+        registry.register::<AssetPathField<Image>>();
+        let path = AssetPathField::<Image>::new("textures/grid.png");
+
+        // Components are saved from dynamic copies
+        let dynamic = path.clone_value();
+        let text =
+            ron::to_string(&TypedReflectSerializer::new(dynamic.as_ref(), &registry)).unwrap();
+        assert_eq!(text, "\"textures/grid.png\"");
+
+        let registration = registry.get(std::any::TypeId::of::<AssetPathField<Image>>());
+        let mut deserializer = ron::Deserializer::from_str(&text).unwrap();
+        let value = TypedReflectDeserializer::new(registration.unwrap(), &registry)
+            .deserialize(&mut deserializer)
+            .unwrap();
+        assert_eq!(
+            <AssetPathField<Image> as FromReflect>::from_reflect(value.as_ref()),
+            Some(path)
+        );
+    }
 }
@@ -1,20 +1,12 @@
+use super::AssetPathField;
 use crate::ext::*;
 
 /// Entities with this component will spawn prefab on enter to [`EditorState::Game`] state
 ///
 /// [`EditorState::Game`]: crate::EditorState::Game
 #[cfg(not(tarpaulin_include))]
-#[derive(Component, Reflect, Clone)]
+#[derive(Component, Reflect, Clone, Default)]
 #[reflect(Component, Default)]
 pub struct PlayerStart {
-    pub prefab: String,
-}
-
-#[cfg_attr(tarpaulin, ignore)]
-impl Default for PlayerStart {
-    fn default() -> Self {
-        Self {
-            prefab: "".to_string(),
-        }
-    }
+    pub prefab: AssetPathField<DynamicScene>,
 }
@@ -2,13 +2,13 @@ use crate::ext::*;
 use bevy::utils::HashMap;
 use bevy_inspector_egui::{inspector_options::ReflectInspectorOptions, InspectorOptions};
 
-use super::material::try_image;
+use super::{material::try_image, AssetPathField};
 
 /// Prefab component that store parameters and asset paths for creating [`StandardMaterial`]
 #[derive(Component, Reflect, Clone, InspectorOptions, Default)]
 #[reflect(Default, Component, InspectorOptions)]
 pub struct SpriteTexture {
-    pub texture: String,
+    pub texture: AssetPathField<Image>,
 }
 
 impl SpriteTexture {
@@ -26,7 +26,7 @@ impl SpriteTexture {
 #[derive(Component, Reflect, Clone, InspectorOptions, Default)]
 #[reflect(Default, Component, InspectorOptions)]
 pub struct SpritesheetTexture {
-    pub texture: String,
+    pub texture: AssetPathField<Image>,
 }
 
 impl SpritesheetTexture {
@@ -185,7 +185,7 @@ mod tests {
     #[test]
     fn sprite_texture_to_sprite_with_path() {
         let prefab = SpriteTexture {
-            texture: String::from("test_asset.png"),
+            texture: AssetPathField::new("test_asset.png"),
         };
 
         let mut app = App::new();
@@ -206,7 +206,7 @@ mod tests {
     #[test]
     fn sprite_texture_to_sprite_with_fake_path() {
         let prefab = SpriteTexture {
-            texture: String::from("fake_asset.png"),
+            texture: AssetPathField::new("fake_asset.png"),
         };
 
         let mut app = App::new();
@@ -225,7 +225,7 @@ mod tests {
     #[test]
     fn spritesheet_texture_to_sprite_with_path() {
         let prefab = SpritesheetTexture {
-            texture: String::from("test_asset.png"),
+            texture: AssetPathField::new("test_asset.png"),
         };
 
         let mut app = App::new();
@@ -246,7 +246,7 @@ mod tests {
     #[test]
     fn spritesheet_texture_to_sprite_with_fake_path() {
         let prefab = SpritesheetTexture {
-            texture: String::from("fake_asset.png"),
+            texture: AssetPathField::new("fake_asset.png"),
         };
 
         let mut app = App::new();
@@ -288,7 +288,7 @@ mod tests {
     #[test]
     fn default_texture_atlas_to_texture_exists() {
         let sprite_prefab = SpritesheetTexture {
-            texture: String::from("test_asset.png"),
+            texture: AssetPathField::new("test_asset.png"),
         };
         let mut prefab = TextureAtlasPrefab::default();
 
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use bevy::{
     ecs::system::{EntityCommand, EntityCommands},
@@ -11,7 +11,7 @@ use space_shared::*;
 use space_undo::AppAutoUndo;
 use std::any::TypeId;
 
-use crate::{component::AutoStruct, save::SaveState, PrefabSet};
+use crate::{component::AutoStruct, migration::PrefabMigrations, raw_ron::RonNode, PrefabSet};
 
 /// Plugin to activate custom registry
 pub struct EditorRegistryPlugin;
@@ -21,6 +21,15 @@ impl Plugin for EditorRegistryPlugin {
         app.init_resource::<EditorRegistry>();
 
         app.editor_clone_registry::<PrefabMarker>();
+
+        app.add_systems(PostStartup, capture_scene_resources);
+    }
+}
+
+/// Remember values of scene resources set by app plugins and startup systems
+fn capture_scene_resources(world: &World) {
+    for resource in world.resource::<EditorRegistry>().scene_resources.iter() {
+        resource.capture(world);
     }
 }
 
@@ -66,6 +75,54 @@ impl CloneComponent {
     }
 }
 
+/// Container struct for functions to work with component registered as [`AutoStruct`]
+#[derive(Clone)]
+pub struct AutoStructComponent {
+    convert: Arc<dyn Fn(&dyn Reflect) -> Option<Box<dyn Reflect>> + Send + Sync>,
+    move_assets: Arc<dyn Fn(&mut World, &dyn Fn(&str) -> Option<String>) -> usize + Send + Sync>,
+}
+
+impl AutoStructComponent {
+    pub fn new<T: Component + Reflect + FromReflect + Default + Clone + TypePath>() -> Self {
+        Self {
+            convert: Arc::new(move |component| {
+                T::from_reflect(component)
+                    .map(|data| Box::new(AutoStruct::new(&data)) as Box<dyn Reflect>)
+            }),
+            move_assets: Arc::new(move |world, rename| {
+                let moved = world
+                    .query::<(Entity, &T)>()
+                    .iter(world)
+                    .filter_map(|(entity, data)| {
+                        let mut auto_struct = AutoStruct::new(data);
+                        auto_struct
+                            .rename_assets(rename)
+                            .then_some((entity, auto_struct))
+                    })
+                    .collect::<Vec<_>>();
+                let assets = world.resource::<AssetServer>().clone();
+                for (entity, auto_struct) in moved.iter() {
+                    world
+                        .entity_mut(*entity)
+                        .insert(auto_struct.get_data(&assets));
+                }
+                moved.len()
+            }),
+        }
+    }
+
+    /// Saved form of component, or `None` if component is not `T`
+    pub fn convert(&self, component: &dyn Reflect) -> Option<Box<dyn Reflect>> {
+        (self.convert)(component)
+    }
+
+    /// Reload handles of loaded components, which paths are changed by `rename`.
+    /// Returns number of changed components
+    pub fn move_assets(&self, world: &mut World, rename: &dyn Fn(&str) -> Option<String>) -> usize {
+        (self.move_assets)(world, rename)
+    }
+}
+
 /// Container struct for function to add default component in untyped style
 #[derive(Clone)]
 pub struct AddDefaultComponent {
@@ -126,6 +183,66 @@ impl SendEvent {
     }
 }
 
+/// Container struct for resource, which is saved in scene file
+#[derive(Clone)]
+pub struct SceneResource {
+    name: String,
+    path: String,
+    pub type_id: TypeId,
+    capture: Arc<dyn Fn(&World) + Send + Sync>,
+    reset: Arc<dyn Fn(&mut World) + Send + Sync>,
+}
+
+impl SceneResource {
+    pub fn new<T: Default + Resource + FromReflect>() -> Self {
+        let path = std::any::type_name::<T>().to_string();
+        let name = path.split("::").last().unwrap_or("UnnamedResource").into();
+        let type_id = TypeId::of::<T>();
+        // Value set by app, restored instead of `T::default()`
+        let initial = Arc::new(RwLock::new(None::<T>));
+        let captured = initial.clone();
+        Self {
+            name,
+            path,
+            type_id,
+            capture: Arc::new(move |world| {
+                if let (Some(value), Ok(mut captured)) =
+                    (world.get_resource::<T>(), captured.write())
+                {
+                    *captured = T::from_reflect(value);
+                }
+            }),
+            reset: Arc::new(move |world| {
+                let value = initial
+                    .read()
+                    .ok()
+                    .and_then(|initial| initial.as_ref().and_then(|value| T::from_reflect(value)))
+                    .unwrap_or_default();
+                world.insert_resource(value);
+            }),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Remember current value of resource as its default value
+    pub fn capture(&self, world: &World) {
+        (self.capture)(world);
+    }
+
+    /// Set default value, used before loading scene which does not contain this resource.
+    /// Default value is the value at app start, or `T::default()` if resource was missing
+    pub fn reset(&self, world: &mut World) {
+        (self.reset)(world);
+    }
+}
+
 /// Resource, which contains all custom editor registry
 #[derive(Default, Resource, Clone)]
 pub struct EditorRegistry {
@@ -134,6 +251,9 @@ pub struct EditorRegistry {
     pub clone_components: Vec<CloneComponent>,
     pub remove_components: HashMap<TypeId, RemoveComponent>,
     pub send_events: Vec<SendEvent>,
+    pub scene_resources: Vec<SceneResource>,
+    /// Components saved as [`AutoStruct`]
+    pub auto_structs: HashMap<TypeId, AutoStructComponent>,
     pub silent: HashSet<TypeId>, //skip in inspector ui
 }
 
@@ -184,6 +304,41 @@ impl EditorRegistry {
         self.clone_components.push(CloneComponent::new::<T>());
     }
 
+    /// Register component, which will be saved as [`AutoStruct`] to keep paths of its asset handles
+    pub fn auto_struct_register<
+        T: Component + Reflect + FromReflect + Default + Clone + TypePath,
+    >(
+        &mut self,
+    ) {
+        self.auto_structs
+            .insert(TypeId::of::<T>(), AutoStructComponent::new::<T>());
+    }
+
+    /// Replace components registered with `editor_auto_struct` in scene with their [`AutoStruct`]
+    pub fn convert_auto_structs(&self, scene: &mut DynamicScene) {
+        if self.auto_structs.is_empty() {
+            return;
+        }
+        for entity in scene.entities.iter_mut() {
+            for component in entity.components.iter_mut() {
+                let Some(auto_struct) = component
+                    .get_represented_type_info()
+                    .and_then(|info| self.auto_structs.get(&info.type_id()))
+                else {
+                    continue;
+                };
+                if let Some(converted) = auto_struct.convert(component.as_ref()) {
+                    *component = converted;
+                } else {
+                    error!(
+                        "Failed to convert {} to auto struct",
+                        component.reflect_type_path()
+                    );
+                }
+            }
+        }
+    }
+
     /// Get spawn function for this component type
     pub fn get_spawn_command(&self, id: &TypeId) -> AddDefaultComponent {
         self.spawn_components.get(id).unwrap().clone()
@@ -214,6 +369,21 @@ impl EditorRegistry {
             (send_event.name().to_owned(), send_event.path().to_owned())
         });
     }
+
+    /// Register new resource, which will be shown in scene settings and saved in scene file
+    pub fn scene_resource_register<T: Default + Resource + FromReflect>(&mut self) {
+        if self
+            .scene_resources
+            .iter()
+            .any(|resource| resource.type_id == TypeId::of::<T>())
+        {
+            return;
+        }
+        self.scene_resources.push(SceneResource::new::<T>());
+        self.scene_resources.sort_unstable_by_key(|resource| {
+            (resource.name().to_owned(), resource.path().to_owned())
+        });
+    }
 }
 
 pub trait EditorRegistryExt {
@@ -248,7 +418,9 @@ pub trait EditorRegistryExt {
         T: Component + Clone + Into<Target>,
         Target: Component;
 
-    /// Not used yet
+    /// Register component with asset handles, which is saved as [`AutoStruct`]:
+    /// handles are saved as asset paths and loaded again after prefab spawn.
+    /// Types of nested fields (like `Vec<Handle<Image>>`) must be registered to load saved prefab
     fn editor_auto_struct<T>(&mut self) -> &mut Self
     where
         T: Component
@@ -266,6 +438,20 @@ pub trait EditorRegistryExt {
     >(
         &mut self,
     ) -> &mut Self;
+
+    /// Register resource with level settings, which is saved in scene file and restored on load.
+    /// Resource must reflect `Resource`
+    fn editor_scene_resource<T: Resource + Default + Reflect + FromReflect + GetTypeRegistration>(
+        &mut self,
+    ) -> &mut Self;
+
+    /// Register migration of saved T data from schema version `from` to `from + 1`.
+    /// Current schema version of T is the last registered version
+    fn editor_migration<T: TypePath>(
+        &mut self,
+        from: u32,
+        migration: impl Fn(&mut RonNode) -> Result<(), String> + Send + Sync + 'static,
+    ) -> &mut Self;
 }
 
 impl EditorRegistryExt for App {
@@ -317,7 +503,6 @@ impl EditorRegistryExt for App {
         self
     }
 
-    //Not used now
     fn editor_auto_struct<T>(&mut self) -> &mut Self
     where
         T: Component
@@ -330,10 +515,13 @@ impl EditorRegistryExt for App {
             + TypePath,
     {
         self.editor_silent_registry::<AutoStruct<T>>();
+        self.register_type::<HashMap<String, String>>();
         self.editor_registry::<T>();
+        self.world
+            .resource_mut::<EditorRegistry>()
+            .auto_struct_register::<T>();
 
-        self.add_systems(OnEnter(SaveState::Save), generate_auto_structs::<T>);
-        self.add_systems(Update, clear_auto_structs::<T>);
+        self.add_systems(Update, restore_auto_structs::<T>);
         self
     }
 
@@ -362,6 +550,50 @@ impl EditorRegistryExt for App {
             .event_register::<T>();
         self
     }
+
+    fn editor_scene_resource<
+        T: Resource + Default + Reflect + FromReflect + GetTypeRegistration,
+    >(
+        &mut self,
+    ) -> &mut Self {
+        if T::get_type_registration()
+            .data::<ReflectResource>()
+            .is_none()
+        {
+            warn!(
+                "{} is registered as scene resource without #[reflect(Resource)] and will not be saved",
+                std::any::type_name::<T>()
+            );
+        }
+        self.register_type::<T>();
+        self.init_resource::<T>();
+        let mut registry = self.world.resource_mut::<EditorRegistry>();
+        registry.scene_resource_register::<T>();
+        if let Some(resource) = registry
+            .scene_resources
+            .iter()
+            .find(|resource| resource.type_id == TypeId::of::<T>())
+            .cloned()
+        {
+            resource.capture(&self.world);
+        }
+        self
+    }
+
+    fn editor_migration<T: TypePath>(
+        &mut self,
+        from: u32,
+        migration: impl Fn(&mut RonNode) -> Result<(), String> + Send + Sync + 'static,
+    ) -> &mut Self {
+        let migrations = self
+            .world
+            .get_resource_or_insert_with(PrefabMigrations::default)
+            .clone();
+        if let Ok(mut migrations) = migrations.0.write() {
+            migrations.register(T::type_path(), from, Arc::new(migration));
+        }
+        self
+    }
 }
 
 fn into_sync_system<T: Component + Clone + Into<Target>, Target: Component>(
@@ -374,19 +606,8 @@ fn into_sync_system<T: Component + Clone + Into<Target>, Target: Component>(
     }
 }
 
-/// Not used
-fn generate_auto_structs<T: Component + Reflect + FromReflect + Default + Clone>(
-    mut commands: Commands,
-    query: Query<(Entity, &T)>,
-    assets: Res<AssetServer>,
-) {
-    for (e, data) in query.iter() {
-        commands.entity(e).insert(AutoStruct::new(data, &assets));
-    }
-}
-
-/// Not used
-fn clear_auto_structs<T: Component + Reflect + FromReflect + Default + Clone>(
+/// Replace loaded [`AutoStruct`] with component with loaded asset handles
+fn restore_auto_structs<T: Component + Reflect + FromReflect + Default + Clone>(
     mut commands: Commands,
     query: Query<(Entity, &AutoStruct<T>)>,
     assets: Res<AssetServer>,
@@ -408,7 +629,8 @@ fn relation_system<T: Component, Relation: Component + Default>(
 
 #[cfg(test)]
 mod tests {
-    use bevy::{ecs::system::CommandQueue, prelude::*};
+    use bevy::{ecs::system::CommandQueue, prelude::*, scene::serde::SceneDeserializer};
+    use serde::de::DeserializeSeed;
 
     use super::*;
 
@@ -573,4 +795,53 @@ mod tests {
         let registry = app.world.resource::<EditorRegistry>();
         assert_eq!("AnEvent", registry.send_events.first().unwrap().name);
     }
+
+    #[test]
+    fn auto_struct_saves_asset_paths() {
+        #[derive(Component, Reflect, Default, Clone)]
+        #[reflect(Component, Default)]
+        struct Textured {
+            textures: Vec<Handle<Image>>,
+        }
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), EditorRegistryPlugin))
+            .init_asset::<Image>()
+            .register_type::<Vec<Handle<Image>>>()
+            .editor_auto_struct::<Textured>();
+        let texture = app
+            .world
+            .resource::<AssetServer>()
+            .load::<Image>("textures/grid.png");
+        let e = app
+            .world
+            .spawn(Textured {
+                textures: vec![texture.clone()],
+            })
+            .id();
+
+        let scene = crate::save::build_prefab_scene(&app.world, [e].into_iter());
+        let data = crate::save::serialize_prefab(&app.world, &scene).unwrap();
+        assert!(data.contains("\"textures[0]\": \"textures/grid.png\""));
+
+        let scene = {
+            let registry = app.world.resource::<AppTypeRegistry>().read();
+            let mut deserializer = bevy::scene::ron::Deserializer::from_str(&data).unwrap();
+            SceneDeserializer {
+                type_registry: &registry,
+            }
+            .deserialize(&mut deserializer)
+            .unwrap()
+        };
+        app.world.despawn(e);
+        scene
+            .write_to_world(&mut app.world, &mut Default::default())
+            .unwrap();
+        app.update();
+
+        let mut query = app.world.query::<&Textured>();
+        assert_eq!(query.single(&app.world).textures, vec![texture]);
+        let mut auto_structs = app.world.query::<&AutoStruct<Textured>>();
+        assert!(auto_structs.iter(&app.world).next().is_none());
+    }
 }
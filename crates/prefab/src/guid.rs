@@ -0,0 +1,318 @@
+use bevy::{
+    prelude::*,
+    reflect::ReflectMut,
+    utils::{HashMap, HashSet, Uuid},
+};
+use space_shared::PrefabMarker;
+
+use crate::{editor_registry::EditorRegistryExt, load::PrefabAutoChild, EditorState};
+
+/// Stable id of prefab entity. Saved prefab uses ids derived from it as entity keys, so
+/// children lists and [`EntityLink`](crate::component::EntityLink)s refer to entities by guid
+/// and do not change between saves
+#[derive(Component, Reflect, Clone, Copy, Default, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[reflect(Component, Default)]
+pub struct PrefabGuid(pub Uuid);
+
+impl PrefabGuid {
+    pub fn new_random() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    /// Entity index in saved file, which is used when it is not taken by other guid
+    fn preferred_index(&self) -> u32 {
+        let (hi, lo) = self.0.as_u64_pair();
+        (hi ^ lo) as u32
+    }
+}
+
+/// Give guid to all prefab entities without it and to copies of entities with same guid.
+/// Entity, which already had the guid, keeps it
+pub fn assign_prefab_guids(
+    mut commands: Commands,
+    without_guid: Query<
+        Entity,
+        (
+            With<PrefabMarker>,
+            Without<PrefabGuid>,
+            Without<PrefabAutoChild>,
+        ),
+    >,
+    added: Query<(Entity, &PrefabGuid), (Added<PrefabGuid>, Without<PrefabAutoChild>)>,
+    all: Query<(Entity, &PrefabGuid), (With<PrefabMarker>, Without<PrefabAutoChild>)>,
+) {
+    for entity in without_guid.iter() {
+        commands.entity(entity).insert(PrefabGuid::new_random());
+    }
+
+    let mut added = added
+        .iter()
+        .map(|(entity, guid)| (*guid, entity))
+        .collect::<Vec<_>>();
+    if added.is_empty() {
+        return;
+    }
+    let added_entities = added
+        .iter()
+        .map(|(_, entity)| *entity)
+        .collect::<HashSet<_>>();
+    let existing = all
+        .iter()
+        .filter(|(entity, _)| !added_entities.contains(entity))
+        .map(|(_, guid)| *guid)
+        .collect::<HashSet<_>>();
+    // Entities added at the same frame with same guid are ordered by entity id with generation,
+    // so the same one keeps the guid regardless of query order
+    added.sort();
+    let mut kept = HashSet::new();
+    for (guid, entity) in added {
+        if existing.contains(&guid) || !kept.insert(guid) {
+            commands.entity(entity).insert(PrefabGuid::new_random());
+        }
+    }
+}
+
+/// Make saved prefab independent from runtime entity ids and extraction order.
+/// Entities get ids derived from their [`PrefabGuid`] and are sorted by them,
+/// components and resources are sorted by type path
+pub fn sort_prefab_scene(world: &World, scene: &mut DynamicScene) {
+    let mut order = scene
+        .entities
+        .iter()
+        .map(|dyn_entity| {
+            (
+                world.get::<PrefabGuid>(dyn_entity.entity),
+                dyn_entity.entity,
+            )
+        })
+        .collect::<Vec<_>>();
+    // Entities with guid first, so their ids do not depend on entities without it
+    order.sort_by_key(|(guid, entity)| (guid.is_none(), guid.copied(), *entity));
+
+    let mut used = HashSet::new();
+    let mut map = HashMap::new();
+    for (guid, entity) in order {
+        let mut index = guid.map_or_else(|| entity.index(), PrefabGuid::preferred_index);
+        while index == u32::MAX || !used.insert(index) {
+            index = index.wrapping_add(1);
+        }
+        map.insert(entity, Entity::from_raw(index));
+    }
+    remap_prefab_scene(scene, &map);
+}
+
+/// Replace ids of scene entities and entity references in their components with ids from `map`.
+/// Components and resources are sorted by type path
+pub(crate) fn remap_prefab_scene(scene: &mut DynamicScene, map: &HashMap<Entity, Entity>) {
+    for dyn_entity in scene.entities.iter_mut() {
+        dyn_entity.entity = map[&dyn_entity.entity];
+        for component in dyn_entity.components.iter_mut() {
+            map_entities(component.as_mut(), map);
+        }
+        dyn_entity
+            .components
+            .sort_by(|a, b| type_path(a.as_ref()).cmp(type_path(b.as_ref())));
+    }
+    scene.entities.sort_by_key(|dyn_entity| dyn_entity.entity);
+    scene
+        .resources
+        .sort_by(|a, b| type_path(a.as_ref()).cmp(type_path(b.as_ref())));
+}
+
+/// Type path of reflected value, also for dynamic values
+pub(crate) fn type_path(value: &dyn Reflect) -> &str {
+    value
+        .get_represented_type_info()
+        .map_or_else(|| value.reflect_type_path(), |info| info.type_path())
+}
+
+/// Replace all entity references inside reflected value
+fn map_entities(value: &mut dyn Reflect, map: &HashMap<Entity, Entity>) {
+    if let Some(entity) = value.downcast_mut::<Entity>() {
+        if let Some(mapped) = map.get(entity) {
+            *entity = *mapped;
+        }
+        return;
+    }
+    match value.reflect_mut() {
+        ReflectMut::Struct(value) => {
+            for idx in 0..value.field_len() {
+                if let Some(field) = value.field_at_mut(idx) {
+                    map_entities(field, map);
+                }
+            }
+        }
+        ReflectMut::TupleStruct(value) => {
+            for idx in 0..value.field_len() {
+                if let Some(field) = value.field_mut(idx) {
+                    map_entities(field, map);
+                }
+            }
+        }
+        ReflectMut::Tuple(value) => {
+            for idx in 0..value.field_len() {
+                if let Some(field) = value.field_mut(idx) {
+                    map_entities(field, map);
+                }
+            }
+        }
+        ReflectMut::List(value) => {
+            for idx in 0..value.len() {
+                if let Some(item) = value.get_mut(idx) {
+                    map_entities(item, map);
+                }
+            }
+        }
+        ReflectMut::Array(value) => {
+            for idx in 0..value.len() {
+                if let Some(item) = value.get_mut(idx) {
+                    map_entities(item, map);
+                }
+            }
+        }
+        ReflectMut::Map(value) => {
+            for idx in 0..value.len() {
+                if let Some((_, item)) = value.get_at_mut(idx) {
+                    map_entities(item, map);
+                }
+            }
+        }
+        ReflectMut::Enum(value) => {
+            for idx in 0..value.field_len() {
+                if let Some(field) = value.field_at_mut(idx) {
+                    map_entities(field, map);
+                }
+            }
+        }
+        ReflectMut::Value(_) => {}
+    }
+}
+
+/// Plugin for stable entity ids in saved prefabs
+pub struct PrefabGuidPlugin;
+
+impl Plugin for PrefabGuidPlugin {
+    fn build(&self, app: &mut App) {
+        app.editor_silent_registry::<PrefabGuid>();
+        app.register_type::<Uuid>();
+        app.add_systems(
+            Update,
+            assign_prefab_guids.run_if(in_state(EditorState::Editor)),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::save::ChildrenPrefab;
+
+    fn spawn_level(world: &mut World, guids: [Uuid; 2]) -> Vec<Entity> {
+        let child = world.spawn((PrefabGuid(guids[1]), Name::new("child"))).id();
+        let parent = world
+            .spawn((
+                PrefabGuid(guids[0]),
+                Name::new("parent"),
+                ChildrenPrefab(vec![child]),
+            ))
+            .id();
+        vec![child, parent]
+    }
+
+    fn saved(world: &World, entities: &[Entity]) -> DynamicScene {
+        let mut scene = DynamicSceneBuilder::from_world(world)
+            .extract_entities(entities.iter().copied())
+            .build();
+        sort_prefab_scene(world, &mut scene);
+        scene
+    }
+
+    #[test]
+    fn saved_ids_do_not_depend_on_runtime_ids() {
+        let guids = [Uuid::new_v4(), Uuid::new_v4()];
+        let registry = AppTypeRegistry::default();
+        {
+            let mut registry = registry.write();
+            registry.register::<PrefabGuid>();
+            registry.register::<Uuid>();
+            registry.register::<Name>();
+            registry.register::<std::borrow::Cow<'static, str>>();
+            registry.register::<ChildrenPrefab>();
+            registry.register::<Vec<Entity>>();
+            registry.register::<Entity>();
+        }
+
+        let mut first = World::new();
+        first.insert_resource(registry.clone());
+        let entities = spawn_level(&mut first, guids);
+        let first = saved(&first, &entities).serialize_ron(&registry).unwrap();
+
+        let mut second = World::new();
+        second.insert_resource(registry.clone());
+        second.spawn_batch((0..10).map(|_| Name::new("other")));
+        let mut entities = spawn_level(&mut second, guids);
+        entities.reverse();
+        let second = saved(&second, &entities).serialize_ron(&registry).unwrap();
+
+        assert_eq!(first, second);
+        let child_id = PrefabGuid(guids[1]).preferred_index();
+        assert!(first.contains(&Entity::from_raw(child_id).to_bits().to_string()));
+    }
+
+    fn assign_guids_schedule() -> Schedule {
+        let mut schedule = Schedule::default();
+        schedule.add_systems(assign_prefab_guids);
+        schedule
+    }
+
+    #[test]
+    fn copied_guid_is_replaced() {
+        let mut world = World::new();
+        let mut schedule = assign_guids_schedule();
+        let guid = PrefabGuid::new_random();
+        let original = world.spawn((PrefabMarker, guid)).id();
+        schedule.run(&mut world);
+        let copy = world.spawn((PrefabMarker, guid)).id();
+        let new = world.spawn(PrefabMarker).id();
+        schedule.run(&mut world);
+
+        assert_eq!(world.get::<PrefabGuid>(original), Some(&guid));
+        assert_ne!(world.get::<PrefabGuid>(copy), Some(&guid));
+        assert!(world.get::<PrefabGuid>(new).is_some());
+    }
+
+    #[test]
+    fn copy_with_recycled_lower_index_does_not_take_guid() {
+        let mut world = World::new();
+        let mut schedule = assign_guids_schedule();
+        let guid = PrefabGuid::new_random();
+        let freed = world.spawn_empty().id();
+        let original = world.spawn((PrefabMarker, guid)).id();
+        world.despawn(freed);
+        schedule.run(&mut world);
+
+        let copy = world.spawn((PrefabMarker, guid)).id();
+        assert!(copy.index() < original.index());
+        schedule.run(&mut world);
+        assert_eq!(world.get::<PrefabGuid>(original), Some(&guid));
+        assert_ne!(world.get::<PrefabGuid>(copy), Some(&guid));
+    }
+
+    #[test]
+    fn same_frame_duplicates_keep_one_guid() {
+        let mut world = World::new();
+        let mut schedule = assign_guids_schedule();
+        let guid = PrefabGuid::new_random();
+        let entities = [
+            world.spawn((PrefabMarker, guid)).id(),
+            world.spawn((PrefabMarker, guid)).id(),
+        ];
+        schedule.run(&mut world);
+        let kept = entities
+            .iter()
+            .filter(|e| world.get::<PrefabGuid>(**e) == Some(&guid))
+            .count();
+        assert_eq!(kept, 1);
+        assert_eq!(world.get::<PrefabGuid>(entities[0]), Some(&guid));
+    }
+}
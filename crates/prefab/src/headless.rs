@@ -0,0 +1,371 @@
+use std::{fmt, fs, path::Path};
+
+use bevy::{prelude::*, reflect::TypeRegistryArc};
+
+use crate::{
+    binary::{deserialize_binary, serialize_binary, BINARY_PREFAB_EXTENSION},
+    lenient::{restore_unknown_components, PrefabLoadReport},
+    migration::{deserialize_prefab, Migrations},
+    raw_ron::RonNode,
+};
+
+/// Load prefab file of any supported format without asset server
+pub fn load_prefab_file(
+    path: &Path,
+    migrations: &Migrations,
+    type_registry: &TypeRegistryArc,
+) -> Result<(DynamicScene, PrefabLoadReport), String> {
+    let mut report = PrefabLoadReport {
+        path: path.display().to_string(),
+        ..default()
+    };
+    let bytes = fs::read(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    let scene = if is_binary(path) {
+        deserialize_binary(&bytes, migrations, type_registry)?
+    } else {
+        let text = String::from_utf8(bytes).map_err(|e| e.to_string())?;
+        deserialize_prefab(&text, migrations, type_registry, &mut report)?
+    };
+    Ok((scene, report))
+}
+
+/// Save prefab to file. Format is selected by file extension
+pub fn save_prefab_file(
+    path: &Path,
+    scene: &DynamicScene,
+    migrations: &Migrations,
+    type_registry: &TypeRegistryArc,
+) -> Result<(), String> {
+    let mut scene = clone_scene(scene);
+    scene.resources.push(Box::new(migrations.header()));
+    let data = if is_binary(path) {
+        serialize_binary(&scene, type_registry)?
+    } else {
+        serialize_to_ron(&scene, type_registry)?.into_bytes()
+    };
+    fs::write(path, data).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// Convert prefab between RON and binary format
+pub fn convert_prefab_file(
+    input: &Path,
+    output: &Path,
+    migrations: &Migrations,
+    type_registry: &TypeRegistryArc,
+) -> Result<PrefabLoadReport, String> {
+    let (scene, report) = load_prefab_file(input, migrations, type_registry)?;
+    save_prefab_file(output, &scene, migrations, type_registry)?;
+    Ok(report)
+}
+
+/// Find all prefab files in folder and its subfolders
+pub fn find_prefab_files(dir: &Path) -> Vec<std::path::PathBuf> {
+    find_files(dir, &is_prefab_file)
+}
+
+/// Find files accepted by `filter` in folder and its subfolders
+pub fn find_files(dir: &Path, filter: &dyn Fn(&Path) -> bool) -> Vec<std::path::PathBuf> {
+    let mut files = vec![];
+    let Ok(entries) = fs::read_dir(dir) else {
+        return files;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            files.extend(find_files(&path, filter));
+        } else if filter(&path) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Is file a RON or binary prefab
+pub fn is_prefab_file(path: &Path) -> bool {
+    path.to_string_lossy().ends_with(".scn.ron") || is_binary(path)
+}
+
+pub(crate) fn is_binary(path: &Path) -> bool {
+    path.to_string_lossy()
+        .ends_with(&format!(".{BINARY_PREFAB_EXTENSION}"))
+}
+
+fn clone_scene(scene: &DynamicScene) -> DynamicScene {
+    DynamicScene {
+        resources: scene.resources.iter().map(|r| r.clone_value()).collect(),
+        entities: scene
+            .entities
+            .iter()
+            .map(|entity| bevy::scene::DynamicEntity {
+                entity: entity.entity,
+                components: entity.components.iter().map(|c| c.clone_value()).collect(),
+            })
+            .collect(),
+    }
+}
+
+fn serialize_to_ron(
+    scene: &DynamicScene,
+    type_registry: &TypeRegistryArc,
+) -> Result<String, String> {
+    let registry = AppTypeRegistry(type_registry.clone());
+    let data = scene.serialize_ron(&registry).map_err(|e| e.to_string())?;
+    restore_unknown_components(&data)
+}
+
+/// One difference between two prefabs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrefabDiff {
+    /// Entity id in file or `resources`
+    pub entity: String,
+    /// Type path of component. None if whole entity is added or removed
+    pub component: Option<String>,
+    /// Path to changed field inside component. Empty for whole component
+    pub field: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+impl fmt::Display for PrefabDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = match (&self.old, &self.new) {
+            (None, Some(_)) => "+",
+            (Some(_), None) => "-",
+            _ => "~",
+        };
+        write!(f, "{sign} {}", self.entity)?;
+        if let Some(component) = &self.component {
+            write!(f, " {component}{}", self.field)?;
+        }
+        match (&self.old, &self.new) {
+            (Some(old), Some(new)) => write!(f, ": {old} -> {new}"),
+            (None, Some(value)) | (Some(value), None) if self.component.is_some() => {
+                write!(f, ": {value}")
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Semantic difference of two prefabs. Formatting and order of entities, components and
+/// fields are ignored
+pub fn diff_prefabs(
+    old: &DynamicScene,
+    new: &DynamicScene,
+    type_registry: &TypeRegistryArc,
+) -> Result<Vec<PrefabDiff>, String> {
+    let old = RonNode::parse(&serialize_to_ron(old, type_registry)?)?;
+    let new = RonNode::parse(&serialize_to_ron(new, type_registry)?)?;
+    let mut diffs = vec![];
+
+    diff_components(
+        "resources",
+        old.field("resources"),
+        new.field("resources"),
+        &mut diffs,
+    );
+
+    let empty = vec![];
+    let old_entities = match old.field("entities") {
+        Some(RonNode::Map(entities)) => entities,
+        _ => &empty,
+    };
+    let new_entities = match new.field("entities") {
+        Some(RonNode::Map(entities)) => entities,
+        _ => &empty,
+    };
+    for (key, old_entity) in old_entities {
+        let id = key.to_string();
+        match new_entities.iter().find(|(k, _)| *k == *key) {
+            Some((_, new_entity)) => diff_components(
+                &id,
+                old_entity.field("components"),
+                new_entity.field("components"),
+                &mut diffs,
+            ),
+            None => diffs.push(PrefabDiff {
+                entity: id,
+                component: None,
+                field: String::new(),
+                old: Some(old_entity.to_string()),
+                new: None,
+            }),
+        }
+    }
+    for (key, new_entity) in new_entities {
+        if !old_entities.iter().any(|(k, _)| k == key) {
+            diffs.push(PrefabDiff {
+                entity: key.to_string(),
+                component: None,
+                field: String::new(),
+                old: None,
+                new: Some(new_entity.to_string()),
+            });
+        }
+    }
+    Ok(diffs)
+}
+
+fn diff_components(
+    entity: &str,
+    old: Option<&RonNode>,
+    new: Option<&RonNode>,
+    diffs: &mut Vec<PrefabDiff>,
+) {
+    let empty = vec![];
+    let old = match old {
+        Some(RonNode::Map(components)) => components,
+        _ => &empty,
+    };
+    let new = match new {
+        Some(RonNode::Map(components)) => components,
+        _ => &empty,
+    };
+    let mut push =
+        |component: &RonNode, field: String, old: Option<String>, new: Option<String>| {
+            diffs.push(PrefabDiff {
+                entity: entity.to_string(),
+                component: Some(component.as_str().unwrap_or_default().to_string()),
+                field,
+                old,
+                new,
+            });
+        };
+    for (key, old_value) in old {
+        match new.iter().find(|(k, _)| k == key) {
+            Some((_, new_value)) => {
+                let mut fields = vec![];
+                diff_nodes(String::new(), old_value, new_value, &mut fields);
+                for (field, old, new) in fields {
+                    push(key, field, old, new);
+                }
+            }
+            None => push(key, String::new(), Some(old_value.to_string()), None),
+        }
+    }
+    for (key, new_value) in new {
+        if !old.iter().any(|(k, _)| k == key) {
+            push(key, String::new(), None, Some(new_value.to_string()));
+        }
+    }
+}
+
+type FieldDiff = (String, Option<String>, Option<String>);
+
+fn diff_nodes(path: String, old: &RonNode, new: &RonNode, out: &mut Vec<FieldDiff>) {
+    match (old, new) {
+        (RonNode::Struct(old_name, old_fields), RonNode::Struct(new_name, new_fields))
+            if old_name == new_name =>
+        {
+            for (name, old_value) in old_fields {
+                let field = format!("{path}.{name}");
+                match new_fields.iter().find(|(n, _)| n == name) {
+                    Some((_, new_value)) => diff_nodes(field, old_value, new_value, out),
+                    None => out.push((field, Some(old_value.to_string()), None)),
+                }
+            }
+            for (name, new_value) in new_fields {
+                if !old_fields.iter().any(|(n, _)| n == name) {
+                    out.push((format!("{path}.{name}"), None, Some(new_value.to_string())));
+                }
+            }
+        }
+        (RonNode::Tuple(old_name, old_items), RonNode::Tuple(new_name, new_items))
+            if old_name == new_name && old_items.len() == new_items.len() =>
+        {
+            for (idx, (old_value, new_value)) in old_items.iter().zip(new_items).enumerate() {
+                diff_nodes(format!("{path}.{idx}"), old_value, new_value, out);
+            }
+        }
+        (RonNode::List(old_items), RonNode::List(new_items))
+            if old_items.len() == new_items.len() =>
+        {
+            for (idx, (old_value, new_value)) in old_items.iter().zip(new_items).enumerate() {
+                diff_nodes(format!("{path}[{idx}]"), old_value, new_value, out);
+            }
+        }
+        _ => {
+            if old != new {
+                out.push((path, Some(old.to_string()), Some(new.to_string())));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> TypeRegistryArc {
+        let registry = TypeRegistryArc::default();
+        {
+            let mut registry = registry.write();
+            registry.register::<Transform>();
+            registry.register::<Vec3>();
+            registry.register::<Quat>();
+            registry.register::<Name>();
+            registry.register::<std::borrow::Cow<'static, str>>();
+        }
+        registry
+    }
+
+    fn scene(translation: Vec3, name: Option<&str>) -> DynamicScene {
+        let mut world = World::new();
+        world.insert_resource(AppTypeRegistry(registry()));
+        let mut entity = world.spawn(Transform::from_translation(translation));
+        if let Some(name) = name {
+            entity.insert(Name::new(name.to_string()));
+        }
+        DynamicScene::from_world(&world)
+    }
+
+    #[test]
+    fn diff_reports_changed_fields() {
+        let registry = registry();
+        let diffs = diff_prefabs(
+            &scene(Vec3::ZERO, None),
+            &scene(Vec3::new(0., 2., 0.), Some("cube")),
+            &registry,
+        )
+        .unwrap();
+
+        assert_eq!(diffs.len(), 2);
+        assert_eq!(diffs[0].field, ".translation.y");
+        assert_eq!(diffs[0].old.as_deref(), Some("0.0"));
+        assert_eq!(diffs[0].new.as_deref(), Some("2.0"));
+        assert_eq!(diffs[1].component.as_deref(), Some(Name::type_path()));
+        assert!(diffs[1].old.is_none());
+
+        let same = diff_prefabs(&scene(Vec3::ONE, None), &scene(Vec3::ONE, None), &registry);
+        assert!(same.unwrap().is_empty());
+    }
+
+    #[test]
+    fn convert_roundtrip() {
+        let registry = registry();
+        registry
+            .write()
+            .register::<crate::migration::PrefabVersion>();
+        registry
+            .write()
+            .register::<bevy::utils::HashMap<String, u32>>();
+        let dir = std::env::temp_dir().join(format!("space_prefab_convert_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let ron_path = dir.join("cube.scn.ron");
+        let bin_path = dir.join("cube.scn.bin");
+        let migrations = Migrations::default();
+        let source = scene(Vec3::X, Some("cube"));
+
+        save_prefab_file(&ron_path, &source, &migrations, &registry).unwrap();
+        convert_prefab_file(&ron_path, &bin_path, &migrations, &registry).unwrap();
+        let (loaded, report) = load_prefab_file(&bin_path, &migrations, &registry).unwrap();
+
+        assert!(report.dropped.is_empty());
+        assert!(diff_prefabs(&source, &loaded, &registry)
+            .unwrap()
+            .is_empty());
+        assert_eq!(find_prefab_files(&dir), vec![bin_path, ron_path]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
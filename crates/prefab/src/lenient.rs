@@ -0,0 +1,378 @@
+use std::sync::{Arc, Mutex};
+
+use bevy::{
+    prelude::*,
+    reflect::{serde::TypedReflectDeserializer, TypeRegistry},
+    scene::DynamicEntity,
+};
+use serde::de::DeserializeSeed;
+
+use crate::{
+    editor_registry::EditorRegistryExt,
+    migration::PrefabVersion,
+    raw_ron::{extensions_header, RonNode},
+};
+
+/// Saved component, which type is not registered or data can not be deserialized.
+/// Kept as is to be written back on save
+#[derive(Reflect, Clone, Default, Debug, PartialEq, Eq)]
+pub struct UnknownComponent {
+    pub type_path: String,
+    pub ron: String,
+}
+
+/// All unknown components of entity
+#[derive(Component, Reflect, Clone, Default, Debug)]
+#[reflect(Component, Default)]
+pub struct UnknownComponents(pub Vec<UnknownComponent>);
+
+/// Part of prefab, which was not loaded into world
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DroppedData {
+    /// Entity id in prefab file. None for resources
+    pub entity: Option<u64>,
+    pub type_path: String,
+    pub reason: String,
+    /// Data will be written back on save
+    pub kept: bool,
+}
+
+/// Report about all data, which was not loaded from prefab file
+#[derive(Event, Clone, Debug, Default)]
+pub struct PrefabLoadReport {
+    pub path: String,
+    pub dropped: Vec<DroppedData>,
+}
+
+/// Reports from asset loader waiting to be shown
+#[derive(Resource, Default, Clone)]
+pub struct PendingLoadReports(pub Arc<Mutex<Vec<PrefabLoadReport>>>);
+
+/// Deserialize scene entity-by-entity and component-by-component.
+/// Broken components are kept in [`UnknownComponents`] and listed in report.
+/// `extensions` are RON extensions enabled in header of scene file
+pub fn deserialize_lenient(
+    node: &RonNode,
+    extensions: &[String],
+    registry: &TypeRegistry,
+    report: &mut PrefabLoadReport,
+) -> Result<DynamicScene, String> {
+    let mut scene = DynamicScene::default();
+    // Values are deserialized one by one, each of them needs header of the file
+    let header = extensions_header(extensions);
+
+    if let Some(RonNode::Map(resources)) = node.field("resources") {
+        for (type_path, value) in resources {
+            let type_path = type_path.as_str().unwrap_or_default();
+            if type_path == PrefabVersion::type_path() {
+                continue;
+            }
+            match deserialize_value::<ReflectResource>(&type_path, &header, value, registry) {
+                Ok(resource) => scene.resources.push(resource),
+                Err(reason) => report.dropped.push(DroppedData {
+                    entity: None,
+                    type_path: type_path.to_string(),
+                    reason,
+                    kept: false,
+                }),
+            }
+        }
+    }
+
+    let Some(RonNode::Map(entities)) = node.field("entities") else {
+        return Err("prefab has no entities".to_string());
+    };
+    for (key, value) in entities {
+        let Some(entity) = key
+            .to_string()
+            .parse::<u64>()
+            .ok()
+            .and_then(|bits| Entity::try_from_bits(bits).ok())
+        else {
+            report.dropped.push(DroppedData {
+                entity: None,
+                type_path: String::new(),
+                reason: format!("invalid entity id {key}"),
+                kept: false,
+            });
+            continue;
+        };
+
+        let mut dyn_entity = DynamicEntity {
+            entity,
+            components: vec![],
+        };
+        let mut unknown = UnknownComponents::default();
+        if let Some(RonNode::Map(components)) = value.field("components") {
+            for (type_path, value) in components {
+                let type_path = type_path.as_str().unwrap_or_default();
+                if type_path == UnknownComponents::type_path() {
+                    // Blobs saved by old editor version, try to load them again
+                    if let Ok(saved) =
+                        deserialize_value::<ReflectComponent>(&type_path, &header, value, registry)
+                    {
+                        if let Some(saved) = UnknownComponents::from_reflect(saved.as_ref()) {
+                            unknown.0.extend(saved.0);
+                        }
+                    }
+                    continue;
+                }
+                match deserialize_value::<ReflectComponent>(&type_path, &header, value, registry) {
+                    Ok(component) => dyn_entity.components.push(component),
+                    Err(reason) => {
+                        report.dropped.push(DroppedData {
+                            entity: Some(entity.to_bits()),
+                            type_path: type_path.to_string(),
+                            reason,
+                            kept: true,
+                        });
+                        unknown.0.push(UnknownComponent {
+                            type_path: type_path.to_string(),
+                            ron: value.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+        if !unknown.0.is_empty()
+            && registry
+                .get(std::any::TypeId::of::<UnknownComponents>())
+                .is_some()
+        {
+            dyn_entity.components.push(Box::new(unknown));
+        }
+        scene.entities.push(dyn_entity);
+    }
+
+    Ok(scene)
+}
+
+fn deserialize_value<T: bevy::reflect::TypeData>(
+    type_path: &str,
+    header: &str,
+    value: &RonNode,
+    registry: &TypeRegistry,
+) -> Result<Box<dyn Reflect>, String> {
+    let registration = registry
+        .get_with_type_path(type_path)
+        .ok_or_else(|| "type is not registered".to_string())?;
+    if registration.data::<T>().is_none() {
+        return Err(format!(
+            "type is not reflected as {}",
+            std::any::type_name::<T>()
+        ));
+    }
+    let text = format!("{header}{value}");
+    let mut deserializer = ron::de::Deserializer::from_str(&text).map_err(|e| e.to_string())?;
+    TypedReflectDeserializer::new(registration, registry)
+        .deserialize(&mut deserializer)
+        .map_err(|e| e.to_string())
+}
+
+/// Move [`UnknownComponents`] blobs back to their original places in serialized prefab
+pub fn restore_unknown_components(text: &str) -> Result<String, String> {
+    let mut node = RonNode::parse(text)?;
+    let Some(RonNode::Map(entities)) = node.field_mut("entities") else {
+        return Ok(text.to_string());
+    };
+    for (_, entity) in entities.iter_mut() {
+        let Some(RonNode::Map(components)) = entity.field_mut("components") else {
+            continue;
+        };
+        let Some(idx) = components
+            .iter()
+            .position(|(key, _)| key.as_str().as_deref() == Some(UnknownComponents::type_path()))
+        else {
+            continue;
+        };
+        let (_, blobs) = components.remove(idx);
+        // UnknownComponents((type_path: "..", ron: ".."), ..)
+        let blobs = match blobs {
+            RonNode::Tuple(_, mut items) if items.len() == 1 => items.remove(0),
+            other => other,
+        };
+        let RonNode::List(blobs) = blobs else {
+            continue;
+        };
+        for blob in blobs {
+            let type_path = blob.field("type_path").and_then(RonNode::as_str);
+            let data = blob.field("ron").and_then(RonNode::as_str);
+            if let (Some(type_path), Some(data)) = (type_path, data) {
+                components.push((RonNode::string(&type_path), RonNode::parse(&data)?));
+            }
+        }
+        components.sort_by(|(a, _), (b, _)| a.as_str().cmp(&b.as_str()));
+    }
+    Ok(node.to_pretty_string())
+}
+
+/// Send collected load reports as events and show them to user
+pub fn show_load_reports(world: &mut World) {
+    let reports = world
+        .get_resource::<PendingLoadReports>()
+        .and_then(|reports| {
+            reports
+                .0
+                .lock()
+                .ok()
+                .map(|mut reports| std::mem::take(&mut *reports))
+        })
+        .unwrap_or_default();
+    for report in reports {
+        for dropped in report.dropped.iter() {
+            warn!(
+                "{}: entity {:?} {} was not loaded ({}){}",
+                report.path,
+                dropped.entity,
+                dropped.type_path,
+                dropped.reason,
+                if dropped.kept {
+                    ", kept for saving"
+                } else {
+                    ""
+                }
+            );
+        }
+        #[cfg(feature = "editor")]
+        world.send_event(space_shared::toast::ToastMessage::new(
+            &format!(
+                "{} loaded partially: {} components dropped",
+                report.path,
+                report.dropped.len()
+            ),
+            space_shared::toast::ToastKind::Warning,
+        ));
+        world.send_event(report);
+    }
+}
+
+/// Plugin for lenient prefab loading
+pub struct LenientLoadPlugin;
+
+impl Plugin for LenientLoadPlugin {
+    fn build(&self, app: &mut App) {
+        app.editor_silent_registry::<UnknownComponents>();
+        app.init_resource::<PendingLoadReports>()
+            .add_event::<PrefabLoadReport>()
+            .register_type::<UnknownComponent>()
+            .register_type::<Vec<UnknownComponent>>()
+            .add_systems(PreUpdate, show_load_reports);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> TypeRegistry {
+        let mut registry = TypeRegistry::default();
+        registry.register::<Name>();
+        registry.register::<std::borrow::Cow<'static, str>>();
+        registry.register::<UnknownComponents>();
+        registry.register::<UnknownComponent>();
+        registry.register::<Vec<UnknownComponent>>();
+        registry
+    }
+
+    const SCENE: &str = r#"(
+        resources: {},
+        entities: {
+            4294967296: (
+                components: {
+                    "bevy_core::name::Name": (hash: 0, name: "first"),
+                    "my_game::Removed": (speed: 1.0, kind: Fast),
+                },
+            ),
+            4294967297: (
+                components: {
+                    "bevy_core::name::Name": (broken: true),
+                },
+            ),
+        },
+    )"#;
+
+    #[test]
+    fn keeps_loadable_components() {
+        let registry = registry();
+        let mut report = PrefabLoadReport::default();
+        let scene =
+            deserialize_lenient(&RonNode::parse(SCENE).unwrap(), &[], &registry, &mut report)
+                .unwrap();
+
+        assert_eq!(scene.entities.len(), 2);
+        assert_eq!(report.dropped.len(), 2);
+        assert_eq!(report.dropped[0].type_path, "my_game::Removed");
+        assert!(report.dropped.iter().all(|dropped| dropped.kept));
+
+        let unknown = scene.entities[0]
+            .components
+            .iter()
+            .find_map(|c| UnknownComponents::from_reflect(c.as_ref()))
+            .unwrap();
+        assert_eq!(unknown.0[0].ron, "(speed: 1.0,kind: Fast,)");
+    }
+
+    #[derive(Component, Reflect, Default, PartialEq, Debug)]
+    #[reflect(Component)]
+    struct Target {
+        name: Option<String>,
+    }
+
+    #[test]
+    fn values_use_extensions_of_file() {
+        let mut registry = registry();
+        registry.register::<Target>();
+        registry.register::<Option<String>>();
+        let text = format!(
+            r#"#![enable(implicit_some)]
+            (
+                resources: {{}},
+                entities: {{
+                    4294967296: (
+                        components: {{
+                            "{}": (name: "player"),
+                        }},
+                    ),
+                }},
+            )"#,
+            Target::type_path()
+        );
+        let (extensions, node) = RonNode::parse_with_extensions(&text).unwrap();
+        let mut report = PrefabLoadReport::default();
+        let scene = deserialize_lenient(&node, &extensions, &registry, &mut report).unwrap();
+
+        assert!(report.dropped.is_empty());
+        assert_eq!(
+            Target::from_reflect(scene.entities[0].components[0].as_ref()),
+            Some(Target {
+                name: Some("player".to_string())
+            })
+        );
+    }
+
+    #[test]
+    fn unknown_components_roundtrip() {
+        let registry = registry();
+        let mut report = PrefabLoadReport::default();
+        let scene =
+            deserialize_lenient(&RonNode::parse(SCENE).unwrap(), &[], &registry, &mut report)
+                .unwrap();
+
+        let app_registry = AppTypeRegistry::default();
+        *app_registry.write() = registry;
+        let text = scene.serialize_ron(&app_registry).unwrap();
+        let text = restore_unknown_components(&text).unwrap();
+        assert!(!text.contains("UnknownComponents"));
+
+        let node = RonNode::parse(&text).unwrap();
+        let components = node
+            .field("entities")
+            .and_then(|entities| entities.get_by_index(0))
+            .and_then(|entity| entity.field("components"))
+            .unwrap();
+        assert_eq!(
+            components.get("my_game::Removed").unwrap().to_string(),
+            "(speed: 1.0,kind: Fast,)"
+        );
+    }
+}
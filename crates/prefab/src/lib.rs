@@ -5,14 +5,38 @@
 #[cfg(all(feature = "f32", feature = "f64"))]
 compile_error!("feature \"f32\" and feature \"f64\" cannot be enabled at the same time");
 
+/// Contains compact binary prefab format
+pub mod binary;
+/// Contains headless prefab command line tool
+pub mod cli;
+/// Contains copy and paste of prefab entities and components as RON text
+pub mod clipboard;
 /// Contains all component for prefab logic
 pub mod component;
+/// Contains stable entity ids for deterministic prefab files
+pub mod guid;
+/// Contains prefab file tools, which work without editor and asset server
+pub mod headless;
+/// Contains tolerant prefab deserialization and load reports
+pub mod lenient;
 /// Contains systems for loading prefab from file
 pub mod load;
+/// Contains material files, which can be shared by many entities
+pub mod material_file;
+/// Contains prefab file versioning and migrations of old prefabs
+pub mod migration;
+/// Contains per-instance overrides of nested prefabs
+pub mod overrides;
 /// Module contains all prefab plugin extensions
 pub mod plugins;
+/// Lossless RON tree used to work with saved data without its types
+pub mod raw_ron;
+/// Contains index of asset references in scene files
+pub mod references;
 /// Contains systems for saving prefab
 pub mod save;
+/// Contains scene files opened in editor at once
+pub mod scenes;
 /// Contains systems for spawning prefabs
 pub mod spawn_system;
 
@@ -32,11 +56,19 @@ pub mod ext {
 
 /// All useful structure from this crate
 pub mod prelude {
+    pub use crate::binary::PrefabFormat;
     pub use crate::component::*;
     pub use crate::editor_registry::*;
+    pub use crate::guid::PrefabGuid;
+    pub use crate::lenient::{PrefabLoadReport, UnknownComponents};
     pub use crate::load::PrefabBundle;
+    pub use crate::migration::*;
+    pub use crate::overrides::*;
     pub use crate::plugins::*;
+    pub use crate::raw_ron::RonNode;
+    pub use crate::references::{AssetReference, AssetReferenceIndex};
     pub use crate::save::*;
+    pub use crate::scenes::{ActiveEditorScene, EditorScene, SaveEditorScene};
     pub use crate::sub_scene::*;
     pub use crate::PrefabSet;
     pub use space_shared::PrefabMarker;
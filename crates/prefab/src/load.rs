@@ -1,8 +1,18 @@
-use bevy::prelude::*;
-use bevy_scene_hook::SceneHook;
+use bevy::{
+    asset::LoadState,
+    ecs::{entity::EntityHashMap, event::ManualEventReader},
+    prelude::*,
+    scene::DynamicEntity,
+};
 use space_shared::PrefabMarker;
 
-use crate::prelude::EditorRegistryExt;
+use crate::{
+    overrides::{
+        apply_overrides, prefab_instance_commands, record_prefab_overrides, ApplyPrefabInstance,
+        PrefabOverrides, RevertPrefabInstance,
+    },
+    prelude::EditorRegistryExt,
+};
 
 use super::save::ChildrenPrefab;
 
@@ -44,18 +54,24 @@ pub struct PrefabAutoChild;
 impl Plugin for LoadPlugin {
     fn build(&self, app: &mut App) {
         app.editor_registry::<PrefabLoader>();
+        app.editor_silent_registry::<PrefabOverrides>();
+        app.register_type::<crate::overrides::PrefabOverride>();
+
+        app.add_event::<ApplyPrefabInstance>()
+            .add_event::<RevertPrefabInstance>();
 
         app.add_systems(
             Update,
-            load_prefab.after(bevy_scene_hook::Systems::SceneHookRunner),
-        );
-        app.add_systems(
-            Update,
-            conflict_resolve
-                .after(bevy_scene_hook::Systems::SceneHookRunner)
-                .before(load_prefab),
+            (
+                prefab_instance_commands,
+                load_prefab,
+                reload_modified_prefabs,
+                spawn_prefab_instances,
+                conflict_resolve,
+                auto_children,
+            )
+                .chain(),
         );
-        app.add_systems(Update, auto_children);
     }
 }
 
@@ -66,6 +82,29 @@ pub struct PrefabLoader {
     pub path: String,
 }
 
+/// Runtime link between prefab instance and its source scene.
+/// Used to restore overrides after source is spawned and to collect them on save
+#[derive(Component)]
+pub struct PrefabInstance {
+    pub scene: Handle<DynamicScene>,
+    /// Copy of the scene content, which was used to spawn the instance
+    pub source: DynamicScene,
+    /// Source scene entity -> spawned entity
+    pub entity_map: EntityHashMap<Entity>,
+    pub spawned: bool,
+}
+
+impl PrefabInstance {
+    pub fn new(scene: Handle<DynamicScene>) -> Self {
+        Self {
+            scene,
+            source: DynamicScene::default(),
+            entity_map: EntityHashMap::default(),
+            spawned: false,
+        }
+    }
+}
+
 /// System responsible for loading prefabs
 fn load_prefab(
     mut commands: Commands,
@@ -103,19 +142,158 @@ fn load_prefab(
         }
 
         let scene: Handle<DynamicScene> = assets.load(&l.path);
+        commands.entity(e).insert(PrefabInstance::new(scene));
+    }
+}
 
-        let id = commands
-            .spawn(DynamicSceneBundle { scene, ..default() })
-            .insert(SceneHook::new(move |_e, cmd| {
-                cmd.insert(PrefabAutoChild);
-            }))
-            .insert(PrefabAutoChild)
-            .id();
+/// Respawn all instances of prefab, when its source file was changed.
+/// Instance transform is stored on instance entity and is not touched, overrides are kept
+fn reload_modified_prefabs(
+    world: &mut World,
+    mut reader: Local<ManualEventReader<AssetEvent<DynamicScene>>>,
+) {
+    let Some(events) = world.get_resource::<Events<AssetEvent<DynamicScene>>>() else {
+        return;
+    };
+    let modified = reader
+        .read(events)
+        .filter_map(|event| match event {
+            AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if modified.is_empty() {
+        return;
+    }
 
-        commands.entity(e).push_children(&[id]);
+    let mut query = world.query::<(Entity, &PrefabInstance)>();
+    let instances = query
+        .iter(world)
+        .filter(|(_, instance)| instance.spawned && modified.contains(&instance.scene.id()))
+        .map(|(e, _)| e)
+        .collect::<Vec<_>>();
+    if instances.is_empty() {
+        return;
+    }
+
+    // Keep changes made since last save
+    record_prefab_overrides(world);
+
+    for e in instances {
+        despawn_prefab_instance(world, e);
+        info!("Reloading prefab instance {:?}", e);
+    }
+}
+
+/// Despawn spawned content of prefab instance. It will be spawned again from source scene
+pub(crate) fn despawn_prefab_instance(world: &mut World, e: Entity) {
+    let children = world
+        .get::<Children>(e)
+        .map(|children| children.to_vec())
+        .unwrap_or_default();
+    for child in children {
+        if world.get::<PrefabAutoChild>(child).is_some() {
+            world.entity_mut(child).despawn_recursive();
+        }
+    }
+    if let Some(mut instance) = world.get_mut::<PrefabInstance>(e) {
+        instance.entity_map.clear();
+        instance.spawned = false;
+    }
+}
+
+/// Spawn loaded prefab scenes and apply instance overrides on top of them
+fn spawn_prefab_instances(world: &mut World) {
+    let mut query = world.query::<(Entity, &PrefabInstance)>();
+    let pending = query
+        .iter(world)
+        .filter(|(_, instance)| !instance.spawned)
+        .map(|(e, instance)| (e, instance.scene.clone()))
+        .collect::<Vec<_>>();
+
+    for (e, handle) in pending {
+        world.resource_scope(|world, scenes: Mut<Assets<DynamicScene>>| {
+            let Some(scene) = scenes.get(&handle) else {
+                let state = world.resource::<AssetServer>().get_load_state(&handle);
+                if state == Some(LoadState::Failed) {
+                    report_error(world, format!("Failed to load prefab {:?}", handle.path()));
+                    world.entity_mut(e).remove::<PrefabInstance>();
+                }
+                return;
+            };
+            spawn_prefab_instance(world, e, scene);
+        });
     }
 }
 
+fn spawn_prefab_instance(world: &mut World, e: Entity, scene: &DynamicScene) {
+    let mut entity_map = EntityHashMap::default();
+    if let Err(err) = scene.write_to_world(world, &mut entity_map) {
+        report_error(world, format!("Failed to spawn prefab: {err}"));
+        world.entity_mut(e).remove::<PrefabInstance>();
+        return;
+    }
+
+    let holder = world
+        .spawn((SpatialBundle::default(), PrefabAutoChild))
+        .id();
+    world.entity_mut(e).add_child(holder);
+
+    // Entities, which are not children of other scene entities, are scene roots
+    let mut children = vec![];
+    for entity in scene.entities.iter() {
+        for component in entity.components.iter() {
+            if !component.represents::<ChildrenPrefab>() {
+                continue;
+            }
+            if let Some(prefab_children) = ChildrenPrefab::from_reflect(component.as_ref()) {
+                children.extend(prefab_children.0);
+            }
+        }
+    }
+    for (src, dst) in entity_map.iter() {
+        world.entity_mut(*dst).insert(PrefabAutoChild);
+        if !children.contains(src) {
+            world.entity_mut(holder).add_child(*dst);
+        }
+    }
+
+    if let Some(overrides) = world.get::<PrefabOverrides>(e).cloned() {
+        let registry = world.resource::<AppTypeRegistry>().clone();
+        let res = apply_overrides(world, &overrides.0, &entity_map, &registry.read());
+        if let Err(err) = res {
+            report_error(world, format!("Failed to apply prefab overrides: {err}"));
+        }
+    }
+
+    if let Some(mut instance) = world.get_mut::<PrefabInstance>(e) {
+        instance.source = DynamicScene {
+            resources: vec![],
+            entities: scene
+                .entities
+                .iter()
+                .map(|entity| DynamicEntity {
+                    entity: entity.entity,
+                    components: entity.components.iter().map(|c| c.clone_value()).collect(),
+                })
+                .collect(),
+        };
+        instance.entity_map = entity_map;
+        instance.spawned = true;
+    }
+}
+
+pub(crate) fn report_error(world: &mut World, err: String) {
+    #[cfg(feature = "editor")]
+    world.send_event(space_shared::toast::ToastMessage::new(
+        &err,
+        space_shared::toast::ToastKind::Error,
+    ));
+    #[cfg(not(feature = "editor"))]
+    let _ = world;
+    error!(err);
+}
+
 fn conflict_resolve(
     mut commands: Commands,
     query: Query<Entity, (With<PrefabAutoChild>, With<PrefabMarker>)>,
@@ -125,7 +303,7 @@ fn conflict_resolve(
     }
 }
 
-fn auto_children(
+pub(crate) fn auto_children(
     mut commands: Commands,
     query: Query<(Entity, &ChildrenPrefab)>,
     existen_entity: Query<Entity>,
@@ -140,3 +318,149 @@ fn auto_children(
         cmds.remove::<ChildrenPrefab>();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        overrides::{instance_scene, replace_instance_source},
+        prelude::*,
+    };
+    use bevy::ecs::system::RunSystemOnce;
+
+    fn source_scene(app: &App, translation: Vec3) -> DynamicScene {
+        let mut world = World::new();
+        world.insert_resource(app.world.resource::<AppTypeRegistry>().clone());
+        world.spawn((Transform::from_translation(translation), Name::new("child")));
+        DynamicScene::from_world(&world)
+    }
+
+    fn test_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            bevy::scene::ScenePlugin,
+            HierarchyPlugin,
+            EditorRegistryPlugin {},
+            LoadPlugin,
+        ))
+        .editor_registry::<Transform>()
+        .editor_registry::<Name>();
+        app
+    }
+
+    #[test]
+    fn modified_prefab_respawns_with_overrides() {
+        let mut app = test_app();
+        let scene = source_scene(&app, Vec3::ZERO);
+        let handle = app.world.resource_mut::<Assets<DynamicScene>>().add(scene);
+        let instance = app
+            .world
+            .spawn((
+                Transform::from_xyz(5., 0., 0.),
+                PrefabInstance::new(handle.clone()),
+            ))
+            .id();
+        app.update();
+
+        let mut query = app
+            .world
+            .query_filtered::<(Entity, &Name), With<PrefabAutoChild>>();
+        let (child, _) = query.single(&app.world);
+        app.world.get_mut::<Name>(child).unwrap().set("renamed");
+
+        let scene = source_scene(&app, Vec3::ONE);
+        app.world
+            .resource_mut::<Assets<DynamicScene>>()
+            .insert(handle, scene);
+        app.update();
+        app.update();
+
+        let (child, name) = query.single(&app.world);
+        assert_eq!(name.as_str(), "renamed");
+        assert_eq!(
+            app.world.get::<Transform>(child).unwrap().translation,
+            Vec3::ONE
+        );
+        assert_eq!(
+            app.world.get::<Transform>(instance).unwrap().translation,
+            Vec3::new(5., 0., 0.)
+        );
+    }
+
+    #[test]
+    fn revert_discards_instance_changes() {
+        let mut app = test_app();
+        let scene = source_scene(&app, Vec3::ZERO);
+        let handle = app.world.resource_mut::<Assets<DynamicScene>>().add(scene);
+        let instance = app.world.spawn(PrefabInstance::new(handle)).id();
+        app.update();
+
+        let mut query = app
+            .world
+            .query_filtered::<(Entity, &Name), With<PrefabAutoChild>>();
+        let (child, _) = query.single(&app.world);
+        app.world.get_mut::<Name>(child).unwrap().set("renamed");
+        app.world.run_system_once(record_prefab_overrides);
+        assert!(app.world.get::<PrefabOverrides>(instance).is_some());
+
+        app.world
+            .send_event(RevertPrefabInstance { entity: instance });
+        app.update();
+
+        let (_, name) = query.single(&app.world);
+        assert_eq!(name.as_str(), "child");
+        assert!(app.world.get::<PrefabOverrides>(instance).is_none());
+    }
+
+    #[test]
+    fn apply_keeps_overrides_of_other_instances() {
+        let mut app = test_app();
+        app.editor_registry::<ChildrenPrefab>();
+        let scene = source_scene(&app, Vec3::ZERO);
+        let handle = app.world.resource_mut::<Assets<DynamicScene>>().add(scene);
+        let applied = app.world.spawn(PrefabInstance::new(handle.clone())).id();
+        let other = app.world.spawn(PrefabInstance::new(handle)).id();
+        app.update();
+
+        let spawned_child = |app: &App, instance: Entity| {
+            *app.world
+                .get::<PrefabInstance>(instance)
+                .unwrap()
+                .entity_map
+                .values()
+                .next()
+                .unwrap()
+        };
+        let child = spawned_child(&app, other);
+        app.world.get_mut::<Name>(child).unwrap().set("renamed");
+        let child = spawned_child(&app, applied);
+        app.world.get_mut::<Transform>(child).unwrap().translation = Vec3::X;
+        let added = app.world.spawn((PrefabMarker, Name::new("added"))).id();
+        app.world.entity_mut(child).add_child(added);
+
+        let scene = instance_scene(&app.world, applied).unwrap();
+        assert_eq!(scene.entities.len(), 2);
+        replace_instance_source(&mut app.world, applied, scene);
+        app.update();
+        app.update();
+
+        for instance in [applied, other] {
+            let child = spawned_child(&app, instance);
+            assert_eq!(
+                app.world.get::<Transform>(child).unwrap().translation,
+                Vec3::X
+            );
+            let children = app.world.get::<Children>(child).unwrap();
+            assert_eq!(
+                app.world.get::<Name>(children[0]).unwrap().as_str(),
+                "added"
+            );
+        }
+        let child = spawned_child(&app, other);
+        assert_eq!(app.world.get::<Name>(child).unwrap().as_str(), "renamed");
+        let child = spawned_child(&app, applied);
+        assert_eq!(app.world.get::<Name>(child).unwrap().as_str(), "child");
+    }
+}
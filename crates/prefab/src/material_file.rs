@@ -0,0 +1,194 @@
+use std::{any::TypeId, fs, path::Path};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    reflect::{
+        serde::{TypedReflectDeserializer, TypedReflectSerializer},
+        TypeRegistryArc,
+    },
+    scene::ron,
+    utils::BoxedFuture,
+};
+use serde::de::DeserializeSeed;
+
+use crate::{
+    component::{AssetMaterial, MaterialPrefab},
+    references::ASSETS_FOLDER,
+    save::{write_file_atomic, SaveConfig, DEFAULT_BACKUPS},
+};
+
+/// Extension of material files
+pub const MATERIAL_EXTENSION: &str = "mat.ron";
+
+/// Serialize material to content of `.mat.ron` file
+pub fn serialize_material(
+    material: &MaterialPrefab,
+    type_registry: &TypeRegistryArc,
+) -> Result<String, String> {
+    let registry = type_registry.read();
+    let serializer = TypedReflectSerializer::new(material, &registry);
+    ron::ser::to_string_pretty(&serializer, ron::ser::PrettyConfig::default())
+        .map_err(|e| e.to_string())
+}
+
+/// Deserialize material from content of `.mat.ron` file
+pub fn deserialize_material(
+    text: &str,
+    type_registry: &TypeRegistryArc,
+) -> Result<MaterialPrefab, String> {
+    let registry = type_registry.read();
+    let registration = registry
+        .get(TypeId::of::<MaterialPrefab>())
+        .ok_or_else(|| "MaterialPrefab is not registered".to_string())?;
+    let mut deserializer = ron::Deserializer::from_str(text).map_err(|e| e.to_string())?;
+    let value = TypedReflectDeserializer::new(registration, &registry)
+        .deserialize(&mut deserializer)
+        .map_err(|e| e.to_string())?;
+    MaterialPrefab::from_reflect(value.as_ref())
+        .ok_or_else(|| "File does not contain MaterialPrefab".to_string())
+}
+
+/// Write material to `.mat.ron` file at `path` relative to assets folder.
+/// Loaded material of this file is reloaded for all entities, which use it
+pub fn save_material_file(
+    world: &World,
+    path: &str,
+    material: &MaterialPrefab,
+) -> Result<(), String> {
+    if !path.ends_with(&format!(".{MATERIAL_EXTENSION}")) {
+        return Err(format!(
+            "Material file must have .{MATERIAL_EXTENSION} extension"
+        ));
+    }
+    let text = serialize_material(material, &world.resource::<AppTypeRegistry>().0)?;
+    let backups = world
+        .get_resource::<SaveConfig>()
+        .map_or(DEFAULT_BACKUPS, |config| config.backups);
+    let file = Path::new(ASSETS_FOLDER).join(path);
+    if let Some(parent) = file.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    write_file_atomic(&file, text.as_bytes(), backups).map_err(|e| e.to_string())?;
+
+    if let Some(assets) = world.get_resource::<AssetServer>() {
+        if assets
+            .get_handle::<StandardMaterial>(path.to_string())
+            .is_some()
+        {
+            assets.reload(path.to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Save inline [`MaterialPrefab`] of `entity` to material file and replace it
+/// with [`AssetMaterial`], so the material can be shared with other entities
+pub fn extract_material_to_file(
+    world: &mut World,
+    entity: Entity,
+    path: &str,
+) -> Result<(), String> {
+    let material = world
+        .get::<MaterialPrefab>(entity)
+        .ok_or_else(|| "Entity has no MaterialPrefab".to_string())?
+        .clone();
+    save_material_file(world, path, &material)?;
+    world
+        .entity_mut(entity)
+        .remove::<MaterialPrefab>()
+        .insert(AssetMaterial {
+            path: path.to_string(),
+        });
+    Ok(())
+}
+
+/// Asset loader for `.mat.ron` material files
+pub struct MaterialFileLoader {
+    type_registry: TypeRegistryArc,
+}
+
+impl FromWorld for MaterialFileLoader {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            type_registry: world.resource::<AppTypeRegistry>().0.clone(),
+        }
+    }
+}
+
+impl AssetLoader for MaterialFileLoader {
+    type Asset = StandardMaterial;
+    type Settings = ();
+    type Error = String;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<StandardMaterial, Self::Error>> {
+        Box::pin(async move {
+            let mut text = String::new();
+            reader
+                .read_to_string(&mut text)
+                .await
+                .map_err(|e| e.to_string())?;
+            let material = deserialize_material(&text, &self.type_registry)?;
+            // Textures are loaded as dependencies of material
+            Ok(material.to_material_with(|path| {
+                (!path.is_empty()).then(|| load_context.load(path.path.clone()))
+            }))
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[MATERIAL_EXTENSION]
+    }
+}
+
+/// Plugin for `.mat.ron` material files
+pub struct MaterialFilePlugin;
+
+impl Plugin for MaterialFilePlugin {
+    fn build(&self, app: &mut App) {
+        if app.world.contains_resource::<AssetServer>() {
+            app.init_asset_loader::<MaterialFileLoader>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::component::AssetPathField;
+
+    use super::*;
+
+    #[test]
+    fn material_roundtrip() {
+        let registry = AppTypeRegistry::default();
+        {
+            let mut registry = registry.write();
+            registry.register::<MaterialPrefab>();
+            registry.register::<AssetPathField<Image>>();
+            registry.register::<Color>();
+            registry.register::<AlphaMode>();
+            registry.register::<ParallaxMappingMethod>();
+        }
+        let material = MaterialPrefab {
+            base_color: Color::rgb(0.6, 0.3, 0.2),
+            base_color_texture: "textures/brick.png".into(),
+            perceptual_roughness: 0.9,
+            alpha_mode: AlphaMode::Mask(0.5),
+            ..default()
+        };
+
+        let text = serialize_material(&material, &registry).unwrap();
+        assert!(text.contains("\"textures/brick.png\""));
+        let loaded = deserialize_material(&text, &registry).unwrap();
+        assert_eq!(loaded.base_color, material.base_color);
+        assert_eq!(loaded.base_color_texture, material.base_color_texture);
+        assert_eq!(loaded.perceptual_roughness, 0.9);
+        assert_eq!(loaded.alpha_mode, AlphaMode::Mask(0.5));
+        assert!(deserialize_material("(base_color: 1)", &registry).is_err());
+    }
+}
@@ -0,0 +1,344 @@
+use std::sync::{Arc, RwLock};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    reflect::TypeRegistryArc,
+    utils::{BoxedFuture, HashMap},
+};
+
+use crate::{
+    lenient::{deserialize_lenient, PendingLoadReports, PrefabLoadReport},
+    raw_ron::RonNode,
+};
+
+/// Current version of prefab file layout
+pub const PREFAB_FORMAT_VERSION: u32 = 1;
+
+/// Header of saved prefab. Stored as scene resource to keep files readable by bevy scene loader
+#[derive(Resource, Reflect, Clone, Default, Debug)]
+#[reflect(Resource, Default)]
+pub struct PrefabVersion {
+    pub format: u32,
+    /// Schema versions of components, which have registered migrations. Missing type means version 0
+    pub schemas: HashMap<String, u32>,
+}
+
+/// Function to rewrite serialized component from version N to N+1
+pub type MigrationFn = Arc<dyn Fn(&mut RonNode) -> Result<(), String> + Send + Sync>;
+
+/// Registered migrations for all versioned components
+#[derive(Default, Clone)]
+pub struct Migrations {
+    /// (type path, from version) -> migration to next version
+    steps: HashMap<(String, u32), MigrationFn>,
+    /// Current schema version of each versioned type
+    versions: HashMap<String, u32>,
+}
+
+impl Migrations {
+    /// Register migration of type from version `from` to `from + 1`
+    pub fn register(&mut self, type_path: &str, from: u32, migration: MigrationFn) {
+        self.steps.insert((type_path.to_string(), from), migration);
+        let version = self.versions.entry(type_path.to_string()).or_default();
+        *version = (*version).max(from + 1);
+    }
+
+    /// Current schema version of type
+    pub fn version(&self, type_path: &str) -> u32 {
+        self.versions.get(type_path).copied().unwrap_or_default()
+    }
+
+    /// Header for newly saved prefab
+    pub fn header(&self) -> PrefabVersion {
+        PrefabVersion {
+            format: PREFAB_FORMAT_VERSION,
+            schemas: self.versions.clone(),
+        }
+    }
+
+    /// Upgrade all components of parsed scene to current schema versions
+    pub fn migrate(&self, scene: &mut RonNode) -> Result<(), String> {
+        let header_path = PrefabVersion::type_path();
+        let saved = scene
+            .field("resources")
+            .and_then(|resources| resources.get(header_path))
+            .map(parse_header)
+            .transpose()?
+            .unwrap_or_default();
+        if saved.format > PREFAB_FORMAT_VERSION {
+            return Err(format!(
+                "prefab format version {} is newer than supported {}",
+                saved.format, PREFAB_FORMAT_VERSION
+            ));
+        }
+
+        let Some(RonNode::Map(entities)) = scene.field_mut("entities") else {
+            return Ok(());
+        };
+        for (_, entity) in entities.iter_mut() {
+            let Some(RonNode::Map(components)) = entity.field_mut("components") else {
+                continue;
+            };
+            for (type_path, value) in components.iter_mut() {
+                let Some(type_path) = type_path.as_str() else {
+                    continue;
+                };
+                let current = self.version(&type_path);
+                let mut version = saved
+                    .schemas
+                    .get(type_path.as_ref())
+                    .copied()
+                    .unwrap_or_default();
+                if version > current {
+                    return Err(format!(
+                        "{type_path} version {version} is newer than supported {current}"
+                    ));
+                }
+                while version < current {
+                    let step = self
+                        .steps
+                        .get(&(type_path.to_string(), version))
+                        .ok_or_else(|| {
+                            format!("missing migration of {type_path} from version {version}")
+                        })?;
+                    step(value).map_err(|e| format!("{type_path} v{version}: {e}"))?;
+                    version += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_header(node: &RonNode) -> Result<PrefabVersion, String> {
+    let format = node
+        .field("format")
+        .map(|format| format.to_string().parse::<u32>())
+        .transpose()
+        .map_err(|e| format!("invalid prefab format version: {e}"))?
+        .unwrap_or_default();
+    let mut schemas = HashMap::default();
+    if let Some(RonNode::Map(entries)) = node.field("schemas") {
+        for (key, value) in entries {
+            if let (Some(key), Ok(value)) = (key.as_str(), value.to_string().parse::<u32>()) {
+                schemas.insert(key.to_string(), value);
+            }
+        }
+    }
+    Ok(PrefabVersion { format, schemas })
+}
+
+/// Shared storage of migrations. Shared with asset loader, so migrations registered after
+/// loader creation are still used
+#[derive(Resource, Default, Clone)]
+pub struct PrefabMigrations(pub Arc<RwLock<Migrations>>);
+
+/// Asset loader for `.scn.ron` files, which upgrades old prefabs before deserialization
+pub struct MigratingSceneLoader {
+    type_registry: TypeRegistryArc,
+    migrations: PrefabMigrations,
+    reports: PendingLoadReports,
+}
+
+impl FromWorld for MigratingSceneLoader {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            type_registry: world.resource::<AppTypeRegistry>().0.clone(),
+            migrations: world
+                .get_resource_or_insert_with(PrefabMigrations::default)
+                .clone(),
+            reports: world
+                .get_resource_or_insert_with(PendingLoadReports::default)
+                .clone(),
+        }
+    }
+}
+
+impl AssetLoader for MigratingSceneLoader {
+    type Asset = DynamicScene;
+    type Settings = ();
+    type Error = String;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<DynamicScene, Self::Error>> {
+        Box::pin(async move {
+            let mut text = String::new();
+            reader
+                .read_to_string(&mut text)
+                .await
+                .map_err(|e| e.to_string())?;
+            let migrations = self
+                .migrations
+                .0
+                .read()
+                .map_err(|_| "migrations lock is poisoned".to_string())?
+                .clone();
+            let mut report = PrefabLoadReport {
+                path: load_context.path().display().to_string(),
+                ..default()
+            };
+            let scene = deserialize_prefab(&text, &migrations, &self.type_registry, &mut report)?;
+            if !report.dropped.is_empty() {
+                if let Ok(mut reports) = self.reports.0.lock() {
+                    reports.push(report);
+                }
+            }
+            Ok(scene)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["scn.ron"]
+    }
+}
+
+/// Deserialize prefab RON with migration of old components.
+/// Components which can not be loaded are listed in `report`
+pub fn deserialize_prefab(
+    text: &str,
+    migrations: &Migrations,
+    type_registry: &TypeRegistryArc,
+    report: &mut PrefabLoadReport,
+) -> Result<DynamicScene, String> {
+    let (extensions, mut node) = RonNode::parse_with_extensions(text)?;
+    migrations.migrate(&mut node)?;
+    // Header is skipped, it is not needed in world
+    deserialize_lenient(&node, &extensions, &type_registry.read(), report)
+}
+
+/// Plugin for versioned prefab files
+pub struct MigrationPlugin;
+
+impl Plugin for MigrationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PrefabMigrations>()
+            .register_type::<PrefabVersion>()
+            .register_type::<HashMap<String, u32>>();
+    }
+
+    fn finish(&self, app: &mut App) {
+        // Asset server uses last loader registered for extension. Loader is added after all
+        // plugins are built, so it replaces bevy scene loader regardless of plugin order
+        if app.world.contains_resource::<AssetServer>() {
+            app.init_asset_loader::<MigratingSceneLoader>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Component, Reflect, Default, PartialEq, Debug)]
+    #[reflect(Component)]
+    struct Health {
+        max: f32,
+    }
+
+    #[test]
+    fn loader_replaces_bevy_scene_loader() {
+        let mut app = App::new();
+        app.add_plugins((
+            MigrationPlugin,
+            MinimalPlugins,
+            AssetPlugin::default(),
+            bevy::scene::ScenePlugin,
+        ));
+        app.finish();
+
+        let server = app.world.resource::<AssetServer>();
+        let loader =
+            bevy::tasks::block_on(server.get_path_asset_loader("scenes/level.scn.ron")).unwrap();
+        assert_eq!(
+            loader.type_name(),
+            std::any::type_name::<MigratingSceneLoader>()
+        );
+    }
+
+    fn registry() -> TypeRegistryArc {
+        let registry = TypeRegistryArc::default();
+        registry.write().register::<Health>();
+        registry.write().register::<PrefabVersion>();
+        registry.write().register::<HashMap<String, u32>>();
+        registry
+    }
+
+    #[test]
+    fn migrate_renamed_field() {
+        let text = format!(
+            r#"(
+                resources: {{}},
+                entities: {{
+                    4294967296: (
+                        components: {{
+                            "{}": (maximum: 10.0),
+                        }},
+                    ),
+                }},
+            )"#,
+            Health::type_path()
+        );
+        let mut migrations = Migrations::default();
+        migrations.register(
+            Health::type_path(),
+            0,
+            Arc::new(|node| {
+                node.rename_field("maximum", "max")
+                    .then_some(())
+                    .ok_or_else(|| "no field".to_string())
+            }),
+        );
+        let registry = registry();
+        assert!(
+            deserialize_prefab(&text, &Migrations::default(), &registry, &mut default())
+                .unwrap()
+                .entities[0]
+                .components
+                .is_empty()
+        );
+
+        let scene = deserialize_prefab(&text, &migrations, &registry, &mut default()).unwrap();
+        let health =
+            <Health as FromReflect>::from_reflect(scene.entities[0].components[0].as_ref())
+                .unwrap();
+        assert_eq!(health, Health { max: 10. });
+    }
+
+    #[test]
+    fn saved_version_skips_migration() {
+        let mut migrations = Migrations::default();
+        migrations.register(
+            Health::type_path(),
+            0,
+            Arc::new(|_| Err("must not be called".to_string())),
+        );
+        let mut world = World::new();
+        let registry = AppTypeRegistry(registry());
+        world.insert_resource(registry.clone());
+        world.spawn(Health { max: 5. });
+        let mut scene = DynamicScene::from_world(&world);
+        scene.resources.push(Box::new(migrations.header()));
+        let text = scene.serialize_ron(&registry).unwrap();
+
+        let scene = deserialize_prefab(&text, &migrations, &registry, &mut default()).unwrap();
+        assert!(scene.resources.is_empty());
+        assert_eq!(scene.entities.len(), 1);
+    }
+
+    #[test]
+    fn missing_migration_step() {
+        let mut migrations = Migrations::default();
+        migrations.register(Health::type_path(), 1, Arc::new(|_| Ok(())));
+        let mut node = RonNode::parse(&format!(
+            r#"(resources: {{}}, entities: {{ 1: (components: {{ "{}": (max: 1.0) }}) }})"#,
+            Health::type_path()
+        ))
+        .unwrap();
+        assert!(migrations.migrate(&mut node).is_err());
+    }
+}
@@ -0,0 +1,498 @@
+use bevy::{
+    ecs::entity::EntityHashMap,
+    prelude::*,
+    reflect::{
+        serde::{ReflectSerializer, UntypedReflectDeserializer},
+        GetPath, ReflectRef, TypeRegistry,
+    },
+    utils::HashMap,
+};
+use serde::de::DeserializeSeed;
+
+use crate::{
+    editor_registry::EditorRegistry,
+    guid::remap_prefab_scene,
+    load::{despawn_prefab_instance, report_error, PrefabInstance, PrefabLoader},
+    save::{extract_prefab_scene_with_children, prefab_subtree, write_scene_file, ChildrenPrefab},
+};
+
+/// Single property override of a prefab instance
+#[derive(Reflect, Clone, Default, Debug, PartialEq, Eq)]
+pub struct PrefabOverride {
+    /// Entity id inside the source prefab file (see [`Entity::to_bits`])
+    pub entity: u64,
+    /// Type path of the overridden component
+    pub component: String,
+    /// Reflect path of the overridden field. Empty string means whole component
+    pub field: String,
+    /// RON encoded value of the field
+    pub value: String,
+}
+
+/// List of overrides, which will be applied on top of the source prefab after it is spawned.
+/// Stored on the same entity as [`crate::load::PrefabLoader`]
+#[derive(Component, Reflect, Clone, Default, Debug)]
+#[reflect(Component, Default)]
+pub struct PrefabOverrides(pub Vec<PrefabOverride>);
+
+/// Event to write current state of prefab instance back to its source file
+#[derive(Event, Clone, Copy)]
+pub struct ApplyPrefabInstance {
+    pub entity: Entity,
+}
+
+/// Event to discard all local changes of prefab instance and respawn it from source file
+#[derive(Event, Clone, Copy)]
+pub struct RevertPrefabInstance {
+    pub entity: Entity,
+}
+
+/// Collect differences between source prefab scene and spawned entities
+pub fn collect_overrides(
+    world: &World,
+    scene: &DynamicScene,
+    entity_map: &EntityHashMap<Entity>,
+    editor_registry: &EditorRegistry,
+    registry: &TypeRegistry,
+) -> Vec<PrefabOverride> {
+    let mut overrides = vec![];
+    let editor_types = editor_registry.registry.read();
+
+    for src in scene.entities.iter() {
+        let Some(dst) = entity_map.get(&src.entity) else {
+            continue;
+        };
+        let Some(dst) = world.get_entity(*dst) else {
+            continue;
+        };
+
+        let mut source_types = vec![];
+        for component in src.components.iter() {
+            let Some(registration) = component
+                .get_represented_type_info()
+                .and_then(|info| registry.get(info.type_id()))
+            else {
+                continue;
+            };
+            source_types.push(registration.type_id());
+            if registration.type_id() == std::any::TypeId::of::<ChildrenPrefab>() {
+                continue;
+            }
+            let Some(current) = registration
+                .data::<ReflectComponent>()
+                .and_then(|reflect| reflect.reflect(dst))
+            else {
+                // Removed components are not tracked
+                continue;
+            };
+            let source = registration
+                .data::<ReflectFromReflect>()
+                .and_then(|from| from.from_reflect(component.as_ref()));
+            let source = source.as_deref().unwrap_or_else(|| component.as_ref());
+
+            let mut changed = vec![];
+            diff_values(source, current, String::new(), registry, &mut changed);
+            for (field, value) in changed {
+                if let Some(value) = serialize_value(value, registry) {
+                    overrides.push(PrefabOverride {
+                        entity: src.entity.to_bits(),
+                        component: registration.type_info().type_path().to_string(),
+                        field,
+                        value,
+                    });
+                }
+            }
+        }
+
+        // Components added to instance after spawn
+        for registration in editor_types.iter() {
+            if source_types.contains(&registration.type_id()) {
+                continue;
+            }
+            let Some(current) = registration
+                .data::<ReflectComponent>()
+                .and_then(|reflect| reflect.reflect(dst))
+            else {
+                continue;
+            };
+            let is_default = registration
+                .data::<ReflectDefault>()
+                .is_some_and(|default| values_eq(default.default().as_ref(), current, registry));
+            if is_default {
+                continue;
+            }
+            if let Some(value) = serialize_value(current, registry) {
+                overrides.push(PrefabOverride {
+                    entity: src.entity.to_bits(),
+                    component: registration.type_info().type_path().to_string(),
+                    field: String::new(),
+                    value,
+                });
+            }
+        }
+    }
+
+    overrides
+}
+
+/// Apply overrides to spawned prefab entities
+pub fn apply_overrides(
+    world: &mut World,
+    overrides: &[PrefabOverride],
+    entity_map: &EntityHashMap<Entity>,
+    registry: &TypeRegistry,
+) -> Result<(), String> {
+    let mut errors = vec![];
+    for item in overrides {
+        if let Err(e) = apply_override(world, item, entity_map, registry) {
+            errors.push(format!("{}{}: {}", item.component, item.field, e));
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("\n"))
+    }
+}
+
+fn apply_override(
+    world: &mut World,
+    item: &PrefabOverride,
+    entity_map: &EntityHashMap<Entity>,
+    registry: &TypeRegistry,
+) -> Result<(), String> {
+    let entity = Entity::try_from_bits(item.entity)
+        .ok()
+        .and_then(|e| entity_map.get(&e))
+        .ok_or("entity not found in prefab")?;
+    let reflect_component = registry
+        .get_with_type_path(&item.component)
+        .and_then(|registration| registration.data::<ReflectComponent>())
+        .ok_or("component is not registered")?;
+    let value = deserialize_value(&item.value, registry)?;
+
+    let mut entity_mut = world
+        .get_entity_mut(*entity)
+        .ok_or("entity was despawned")?;
+    if item.field.is_empty() {
+        reflect_component.apply_or_insert(&mut entity_mut, value.as_ref(), registry);
+    } else {
+        let mut component = reflect_component
+            .reflect_mut(&mut entity_mut)
+            .ok_or("component not found on entity")?;
+        let field = component
+            .reflect_path_mut(item.field.as_str())
+            .map_err(|e| e.to_string())?;
+        field.apply(value.as_ref());
+    }
+    Ok(())
+}
+
+/// Recursive comparison of two values. Changed fields pushed as (reflect path, value)
+fn diff_values<'a>(
+    source: &dyn Reflect,
+    current: &'a dyn Reflect,
+    path: String,
+    registry: &TypeRegistry,
+    changed: &mut Vec<(String, &'a dyn Reflect)>,
+) {
+    if values_eq(source, current, registry) {
+        return;
+    }
+    match (source.reflect_ref(), current.reflect_ref()) {
+        (ReflectRef::Struct(source), ReflectRef::Struct(current))
+            if source.field_len() == current.field_len() =>
+        {
+            for (idx, value) in current.iter_fields().enumerate() {
+                let name = current.name_at(idx).unwrap_or_default();
+                match source.field(name) {
+                    Some(src_value) => diff_values(
+                        src_value,
+                        value,
+                        format!("{path}.{name}"),
+                        registry,
+                        changed,
+                    ),
+                    None => changed.push((format!("{path}.{name}"), value)),
+                }
+            }
+        }
+        (ReflectRef::TupleStruct(source), ReflectRef::TupleStruct(current))
+            if source.field_len() == current.field_len() =>
+        {
+            for (idx, value) in current.iter_fields().enumerate() {
+                if let Some(src_value) = source.field(idx) {
+                    diff_values(src_value, value, format!("{path}.{idx}"), registry, changed);
+                }
+            }
+        }
+        _ => changed.push((path, current)),
+    }
+}
+
+fn values_eq(a: &dyn Reflect, b: &dyn Reflect, registry: &TypeRegistry) -> bool {
+    a.reflect_partial_eq(b).unwrap_or_else(|| {
+        let a = serialize_value(a, registry);
+        a.is_some() && a == serialize_value(b, registry)
+    })
+}
+
+fn serialize_value(value: &dyn Reflect, registry: &TypeRegistry) -> Option<String> {
+    ron::to_string(&ReflectSerializer::new(value, registry))
+        .inspect_err(|e| error!("Failed to serialize prefab override: {e}"))
+        .ok()
+}
+
+fn deserialize_value(value: &str, registry: &TypeRegistry) -> Result<Box<dyn Reflect>, String> {
+    let mut deserializer = ron::Deserializer::from_str(value).map_err(|e| e.to_string())?;
+    UntypedReflectDeserializer::new(registry)
+        .deserialize(&mut deserializer)
+        .map_err(|e| e.to_string())
+}
+
+/// Store changes of all spawned prefab instances in [`PrefabOverrides`] before saving
+pub fn record_prefab_overrides(world: &mut World) {
+    let mut query = world.query::<(Entity, &PrefabInstance)>();
+    let mut instances = query
+        .iter(world)
+        .filter(|(_, instance)| instance.spawned)
+        .map(|(e, _)| e)
+        .collect::<Vec<_>>();
+
+    // Nested instances first, so parent instances will see updated overrides of nested ones
+    let depth = |world: &World, mut e: Entity| {
+        let mut depth = 0;
+        while let Some(parent) = world.get::<Parent>(e) {
+            e = parent.get();
+            depth += 1;
+        }
+        depth
+    };
+    instances.sort_by_cached_key(|e| std::cmp::Reverse(depth(world, *e)));
+
+    for entity in instances {
+        record_instance_overrides(world, entity);
+    }
+}
+
+/// Compare prefab instance with the source it was spawned from and store result in [`PrefabOverrides`]
+pub fn record_instance_overrides(world: &mut World, entity: Entity) {
+    let Some(instance) = world.get::<PrefabInstance>(entity) else {
+        return;
+    };
+    if !instance.spawned {
+        return;
+    }
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let overrides = collect_overrides(
+        world,
+        &instance.source,
+        &instance.entity_map,
+        world.resource::<EditorRegistry>(),
+        &registry.read(),
+    );
+    if overrides.is_empty() {
+        world.entity_mut(entity).remove::<PrefabOverrides>();
+    } else {
+        world.entity_mut(entity).insert(PrefabOverrides(overrides));
+    }
+}
+
+/// System to process [`ApplyPrefabInstance`] and [`RevertPrefabInstance`] events
+pub fn prefab_instance_commands(world: &mut World) {
+    let apply = world
+        .resource_mut::<Events<ApplyPrefabInstance>>()
+        .drain()
+        .collect::<Vec<_>>();
+    for event in apply {
+        if let Err(err) = apply_to_prefab(world, event.entity) {
+            report_error(world, format!("Failed to apply prefab instance: {err}"));
+        }
+    }
+
+    let revert = world
+        .resource_mut::<Events<RevertPrefabInstance>>()
+        .drain()
+        .collect::<Vec<_>>();
+    for event in revert {
+        if world.get::<PrefabInstance>(event.entity).is_none() {
+            continue;
+        }
+        world.entity_mut(event.entity).remove::<PrefabOverrides>();
+        despawn_prefab_instance(world, event.entity);
+        info!("Reverted prefab instance {:?}", event.entity);
+    }
+}
+
+/// Save spawned entities of prefab instance as new content of its source file
+fn apply_to_prefab(world: &mut World, entity: Entity) -> Result<(), String> {
+    let path = world
+        .get::<PrefabLoader>(entity)
+        .map(|loader| loader.path.clone())
+        .ok_or("entity is not a prefab instance")?;
+    let scene = instance_scene(world, entity)?;
+
+    let data = crate::save::serialize_prefab(world, &scene)?;
+    write_scene_file(world, data, format!("assets/{path}"));
+    replace_instance_source(world, entity, scene);
+
+    #[cfg(feature = "editor")]
+    world.send_event(space_shared::toast::ToastMessage::new(
+        &format!("Applied prefab instance to {path}"),
+        space_shared::toast::ToastKind::Success,
+    ));
+    info!("Applied prefab instance {:?} to {}", entity, path);
+    Ok(())
+}
+
+/// Current state of prefab instance as scene with entity ids of its source file,
+/// so overrides of other instances still refer to the same entities.
+/// Entities added under spawned entities get new ids
+pub(crate) fn instance_scene(world: &World, entity: Entity) -> Result<DynamicScene, String> {
+    let instance = world
+        .get::<PrefabInstance>(entity)
+        .ok_or("entity is not a prefab instance")?;
+    if !instance.spawned {
+        return Err("prefab is not loaded yet".to_string());
+    }
+
+    // Spawned entity -> source entity
+    let mut ids = instance
+        .entity_map
+        .iter()
+        .filter(|(_, spawned)| world.get_entity(**spawned).is_some())
+        .map(|(source, spawned)| (*spawned, *source))
+        .collect::<HashMap<_, _>>();
+    let added = ids
+        .keys()
+        .filter_map(|spawned| world.get::<Children>(*spawned))
+        .flat_map(|children| children.iter().copied())
+        .filter(|child| !ids.contains_key(child))
+        .collect::<Vec<_>>();
+    let first_free = instance
+        .entity_map
+        .keys()
+        .map(|source| source.index() + 1)
+        .max()
+        .unwrap_or_default();
+    for (added, index) in prefab_subtree(world, &added).into_iter().zip(first_free..) {
+        ids.insert(added, Entity::from_raw(index));
+    }
+
+    let entities = ids.keys().copied().collect::<Vec<_>>();
+    let mut scene = extract_prefab_scene_with_children(world, &entities);
+    remap_prefab_scene(&mut scene, &ids);
+    Ok(scene)
+}
+
+/// Use `scene` as new source of prefab instance and respawn the instance from it.
+/// Other instances of the same source are respawned with their overrides
+pub(crate) fn replace_instance_source(world: &mut World, entity: Entity, scene: DynamicScene) {
+    let Some(handle) = world
+        .get::<PrefabInstance>(entity)
+        .map(|instance| instance.scene.clone())
+    else {
+        return;
+    };
+    world
+        .resource_mut::<Assets<DynamicScene>>()
+        .insert(handle, scene);
+    world.entity_mut(entity).remove::<PrefabOverrides>();
+    despawn_prefab_instance(world, entity);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (World, DynamicScene, EntityHashMap<Entity>) {
+        let mut world = World::new();
+        let registry = AppTypeRegistry::default();
+        {
+            let mut registry = registry.write();
+            registry.register::<Transform>();
+            registry.register::<Name>();
+            registry.register::<Visibility>();
+            registry.register::<Vec3>();
+            registry.register::<Quat>();
+            registry.register::<f32>();
+        }
+        world.insert_resource(registry);
+        let mut editor_registry = EditorRegistry::default();
+        editor_registry.register::<Transform>();
+        editor_registry.register::<Name>();
+        editor_registry.register::<Visibility>();
+        world.insert_resource(editor_registry);
+
+        let mut source = World::new();
+        source.insert_resource(world.resource::<AppTypeRegistry>().clone());
+        source.spawn((Transform::from_xyz(1., 2., 3.), Name::new("source")));
+        let scene = DynamicScene::from_world(&source);
+
+        let mut entity_map = EntityHashMap::default();
+        scene.write_to_world(&mut world, &mut entity_map).unwrap();
+        (world, scene, entity_map)
+    }
+
+    fn collect(
+        world: &World,
+        scene: &DynamicScene,
+        map: &EntityHashMap<Entity>,
+    ) -> Vec<PrefabOverride> {
+        collect_overrides(
+            world,
+            scene,
+            map,
+            world.resource::<EditorRegistry>(),
+            &world.resource::<AppTypeRegistry>().read(),
+        )
+    }
+
+    #[test]
+    fn unchanged_instance_has_no_overrides() {
+        let (world, scene, map) = setup();
+        assert!(collect(&world, &scene, &map).is_empty());
+    }
+
+    #[test]
+    fn changed_field_recorded_and_applied() {
+        let (mut world, scene, map) = setup();
+        let spawned = *map.values().next().unwrap();
+        world.get_mut::<Transform>(spawned).unwrap().translation.x = 10.;
+        world.entity_mut(spawned).insert(Visibility::Hidden);
+
+        let overrides = collect(&world, &scene, &map);
+        assert_eq!(overrides.len(), 2);
+        assert!(overrides.iter().any(|o| o.field == ".translation.x"));
+        assert!(overrides.iter().any(|o| o.field.is_empty()));
+
+        // Respawn prefab from source and apply overrides on top of it
+        let mut new_map = EntityHashMap::default();
+        scene.write_to_world(&mut world, &mut new_map).unwrap();
+        let registry = world.resource::<AppTypeRegistry>().clone();
+        apply_overrides(&mut world, &overrides, &new_map, &registry.read()).unwrap();
+
+        let respawned = *new_map.values().next().unwrap();
+        assert_eq!(
+            world.get::<Transform>(respawned).unwrap().translation,
+            Vec3::new(10., 2., 3.)
+        );
+        assert_eq!(
+            world.get::<Visibility>(respawned),
+            Some(&Visibility::Hidden)
+        );
+    }
+
+    #[test]
+    fn override_for_unknown_entity_is_error() {
+        let (mut world, _, map) = setup();
+        let overrides = vec![PrefabOverride {
+            entity: Entity::from_raw(1000).to_bits(),
+            component: Name::type_path().to_string(),
+            field: String::new(),
+            value: String::new(),
+        }];
+        let registry = world.resource::<AppTypeRegistry>().clone();
+        assert!(apply_overrides(&mut world, &overrides, &map, &registry.read()).is_err());
+    }
+}
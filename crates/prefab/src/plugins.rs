@@ -7,6 +7,7 @@ use bevy::{
     prelude::*,
     render::{
         camera::{CameraMainTextureUsages, CameraRenderGraph, Exposure},
+        mesh::skinning::{SkinnedMesh, SkinnedMeshInverseBindposes},
         primitives::{CascadesFrusta, CubemapFrusta, Frustum},
         view::{ColorGrading, VisibleEntities},
     },
@@ -67,6 +68,15 @@ impl Plugin for BasePrefabPlugin {
 
         app.register_type::<EntityLink>();
 
+        app.register_type::<AssetPathField<Image>>();
+        app.register_type::<AssetPathField<Mesh>>();
+        app.register_type::<AssetPathField<bevy::gltf::Gltf>>();
+        app.register_type::<AssetPathField<DynamicScene>>();
+        app.register_type::<AssetPathField<AnimationClip>>();
+        app.register_type::<AssetPathField<SkinnedMeshInverseBindposes>>();
+        app.register_type::<Vec<AssetPathField<AnimationClip>>>();
+        app.register_type::<Vec<Entity>>();
+
         app.register_type::<Direction3d>();
         app.register_type::<Direction2d>();
 
@@ -132,6 +142,18 @@ impl Plugin for BasePrefabPlugin {
             sync_asset_material.in_set(PrefabSet::DetectPrefabChange),
         );
 
+        app.editor_registry::<SkinnedMeshPrefab>();
+        app.editor_registry::<AnimationPlayerPrefab>();
+        app.add_systems(
+            Update,
+            (sync_skinned_mesh, sync_animation_player).in_set(PrefabSet::DetectPrefabChange),
+        );
+        app.add_systems(OnEnter(EditorState::Game), reset_animation_players::<true>);
+        app.add_systems(
+            OnEnter(EditorState::Editor),
+            reset_animation_players::<false>,
+        );
+
         //material registration
         app.register_type::<Color>();
         app.register_type::<AlphaMode>();
@@ -197,6 +219,9 @@ impl Plugin for BasePrefabPlugin {
 
         app.editor_registry::<PlaymodeLight>();
 
+        app.editor_scene_resource::<AmbientLight>();
+        app.editor_scene_resource::<ClearColor>();
+
         #[cfg(feature = "editor")]
         app.add_event::<ToastMessage>();
 
@@ -237,7 +262,13 @@ impl Plugin for BasePrefabPlugin {
         );
         app.add_systems(Update, animate_sprite);
 
+        app.add_plugins(crate::lenient::LenientLoadPlugin);
+        app.add_plugins(crate::guid::PrefabGuidPlugin);
+        app.add_plugins(crate::migration::MigrationPlugin);
+        app.add_plugins(crate::binary::BinaryPrefabPlugin);
+        app.add_plugins(crate::material_file::MaterialFilePlugin);
         app.add_plugins(SavePrefabPlugin);
+        app.add_plugins(crate::scenes::EditorScenesPlugin);
         app.add_plugins(LoadPlugin);
         app.add_plugins(crate::sub_scene::SceneUnpackPlugin);
     }
@@ -324,7 +355,7 @@ fn sync_asset_mesh(
     assets: Res<AssetServer>,
 ) {
     for (e, mesh) in changed.iter() {
-        commands.entity(e).insert(assets.load::<Mesh>(&mesh.path));
+        commands.entity(e).insert(mesh.path.load(&assets));
     }
 
     for e in deleted.read() {
@@ -335,6 +366,59 @@ fn sync_asset_mesh(
     }
 }
 
+fn sync_skinned_mesh(
+    mut commands: Commands,
+    changed: Query<(Entity, &SkinnedMeshPrefab), Changed<SkinnedMeshPrefab>>,
+    mut deleted: RemovedComponents<SkinnedMeshPrefab>,
+    assets: Res<AssetServer>,
+) {
+    for (e, skin) in changed.iter() {
+        commands.entity(e).insert(skin.to_skinned_mesh(&assets));
+    }
+
+    for e in deleted.read() {
+        if let Some(mut cmd) = commands.get_entity(e) {
+            cmd.remove::<SkinnedMesh>();
+        }
+    }
+}
+
+/// Animations are played only in game, so animated transforms are not saved in editor
+fn sync_animation_player(
+    mut commands: Commands,
+    changed: Query<(Entity, &AnimationPlayerPrefab), Changed<AnimationPlayerPrefab>>,
+    mut deleted: RemovedComponents<AnimationPlayerPrefab>,
+    assets: Res<AssetServer>,
+    state: Res<State<EditorState>>,
+) {
+    let play = *state.get() == EditorState::Game;
+    for (e, animation) in changed.iter() {
+        commands
+            .entity(e)
+            .insert(animation.to_player(&assets, play));
+    }
+
+    for e in deleted.read() {
+        if let Some(mut cmd) = commands.get_entity(e) {
+            cmd.remove::<AnimationPlayer>();
+        }
+    }
+}
+
+/// Rebuild animation players, when game is started or stopped.
+/// Entities are not respawned on state change, so autoplay clips are started here
+fn reset_animation_players<const PLAY: bool>(
+    mut commands: Commands,
+    query: Query<(Entity, &AnimationPlayerPrefab)>,
+    assets: Res<AssetServer>,
+) {
+    for (e, animation) in query.iter() {
+        commands
+            .entity(e)
+            .insert(animation.to_player(&assets, PLAY));
+    }
+}
+
 fn sync_asset_material(
     mut commands: Commands,
     changed: Query<(Entity, &AssetMaterial), Changed<AssetMaterial>>,
@@ -342,9 +426,13 @@ fn sync_asset_material(
     assets: Res<AssetServer>,
 ) {
     for (e, material) in changed.iter() {
-        commands
-            .entity(e)
-            .insert(assets.load::<StandardMaterial>(&material.path));
+        if material.path.is_empty() {
+            commands.entity(e).remove::<Handle<StandardMaterial>>();
+        } else {
+            commands
+                .entity(e)
+                .insert(assets.load::<StandardMaterial>(&material.path));
+        }
     }
 
     for e in deleted.read() {
@@ -353,3 +441,50 @@ fn sync_asset_material(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn autoplay_follows_editor_state() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<AnimationClip>()
+            .init_state::<EditorState>()
+            .add_systems(OnEnter(EditorState::Game), reset_animation_players::<true>)
+            .add_systems(
+                OnEnter(EditorState::Editor),
+                reset_animation_players::<false>,
+            );
+        let entity = app
+            .world
+            .spawn((
+                AnimationPlayerPrefab {
+                    clips: vec!["models/fox.glb#Animation0".into()],
+                    autoplay: Some(0),
+                    repeat: true,
+                },
+                AnimationPlayer::default(),
+            ))
+            .id();
+        let clip = app
+            .world
+            .resource::<AssetServer>()
+            .load("models/fox.glb#Animation0");
+
+        app.world
+            .resource_mut::<NextState<EditorState>>()
+            .set(EditorState::Game);
+        app.update();
+        let player = app.world.get::<AnimationPlayer>(entity).unwrap();
+        assert!(player.is_playing_clip(&clip));
+
+        app.world
+            .resource_mut::<NextState<EditorState>>()
+            .set(EditorState::Editor);
+        app.update();
+        let player = app.world.get::<AnimationPlayer>(entity).unwrap();
+        assert!(!player.is_playing_clip(&clip));
+    }
+}
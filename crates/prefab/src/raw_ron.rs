@@ -0,0 +1,581 @@
+use std::{borrow::Cow, fmt};
+
+/// Lossless tree of RON document. Unlike [`ron::Value`] it keeps struct and enum variant names,
+/// so data can be rewritten without knowing its type and serialized back
+#[derive(Clone, Debug, PartialEq)]
+pub enum RonNode {
+    /// Number, string, char, bool or unit variant exactly as written in file
+    Value(String),
+    /// `(a: 1, b: 2)` or `Name(a: 1)`
+    Struct(Option<String>, Vec<(String, Self)>),
+    /// `(1, 2)`, `Some(1)` or `Variant(1)`
+    Tuple(Option<String>, Vec<Self>),
+    /// `[1, 2]`
+    List(Vec<Self>),
+    /// `{"a": 1}`
+    Map(Vec<(Self, Self)>),
+}
+
+impl RonNode {
+    /// Parse RON document
+    pub fn parse(text: &str) -> Result<Self, String> {
+        Self::parse_with_extensions(text).map(|(_, node)| node)
+    }
+
+    /// Parse RON document and names of extensions enabled by its `#![enable(..)]` header,
+    /// like `implicit_some`
+    pub fn parse_with_extensions(text: &str) -> Result<(Vec<String>, Self), String> {
+        let mut parser = Parser { text, pos: 0 };
+        let extensions = parser.extensions()?;
+        let node = parser.node()?;
+        parser.skip_ws();
+        if parser.pos < text.len() {
+            return Err(parser.error("unexpected trailing characters"));
+        }
+        Ok((extensions, node))
+    }
+
+    /// Content of string value without quotes and escapes
+    pub fn as_str(&self) -> Option<Cow<'_, str>> {
+        let Self::Value(value) = self else {
+            return None;
+        };
+        if value.len() >= 2 && value.starts_with('"') && !value.contains('\\') {
+            Some(Cow::Borrowed(&value[1..value.len() - 1]))
+        } else if value.starts_with('"') || value.starts_with("r\"") || value.starts_with("r#") {
+            ron::from_str::<String>(value).ok().map(Cow::Owned)
+        } else {
+            None
+        }
+    }
+
+    /// Create string value
+    pub fn string(value: &str) -> Self {
+        let mut text = String::with_capacity(value.len() + 2);
+        text.push('"');
+        for c in value.chars() {
+            match c {
+                '"' => text.push_str("\\\""),
+                '\\' => text.push_str("\\\\"),
+                '\n' => text.push_str("\\n"),
+                '\r' => text.push_str("\\r"),
+                '\t' => text.push_str("\\t"),
+                '\0' => text.push_str("\\0"),
+                c if c.is_control() => text.push_str(&format!("\\u{{{:x}}}", c as u32)),
+                c => text.push(c),
+            }
+        }
+        text.push('"');
+        Self::Value(text)
+    }
+
+    /// Get struct field by name
+    pub fn field(&self, name: &str) -> Option<&Self> {
+        match self {
+            Self::Struct(_, fields) => fields.iter().find(|(n, _)| n == name).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Get mutable struct field by name
+    pub fn field_mut(&mut self, name: &str) -> Option<&mut Self> {
+        match self {
+            Self::Struct(_, fields) => fields.iter_mut().find(|(n, _)| n == name).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Rename struct field. Returns false if field not found
+    pub fn rename_field(&mut self, old: &str, new: &str) -> bool {
+        match self {
+            Self::Struct(_, fields) => fields
+                .iter_mut()
+                .find(|(n, _)| n == old)
+                .map(|(n, _)| *n = new.to_string())
+                .is_some(),
+            _ => false,
+        }
+    }
+
+    /// Remove struct field and return its value
+    pub fn remove_field(&mut self, name: &str) -> Option<Self> {
+        match self {
+            Self::Struct(_, fields) => {
+                let idx = fields.iter().position(|(n, _)| n == name)?;
+                Some(fields.remove(idx).1)
+            }
+            _ => None,
+        }
+    }
+
+    /// Insert or replace struct field
+    pub fn set_field(&mut self, name: &str, value: Self) {
+        if let Self::Struct(_, fields) = self {
+            if let Some((_, old)) = fields.iter_mut().find(|(n, _)| n == name) {
+                *old = value;
+            } else {
+                fields.push((name.to_string(), value));
+            }
+        }
+    }
+
+    /// Get map value by string key
+    pub fn get(&self, key: &str) -> Option<&Self> {
+        match self {
+            Self::Map(entries) => entries
+                .iter()
+                .find(|(k, _)| k.as_str().as_deref() == Some(key))
+                .map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Get value of map entry by its position
+    pub fn get_by_index(&self, idx: usize) -> Option<&Self> {
+        match self {
+            Self::Map(entries) => entries.get(idx).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Multiline representation in the same layout as bevy scene files
+    pub fn to_pretty_string(&self) -> String {
+        let mut out = String::new();
+        self.write_pretty(&mut out, 0);
+        out
+    }
+
+    fn write_pretty(&self, out: &mut String, indent: usize) {
+        let pad = "    ".repeat(indent + 1);
+        let end_pad = "    ".repeat(indent);
+        match self {
+            Self::Struct(name, fields) if !fields.is_empty() => {
+                out.push_str(name.as_deref().unwrap_or_default());
+                out.push_str("(\n");
+                for (name, value) in fields {
+                    out.push_str(&format!("{pad}{name}: "));
+                    value.write_pretty(out, indent + 1);
+                    out.push_str(",\n");
+                }
+                out.push_str(&format!("{end_pad})"));
+            }
+            Self::List(items) if !items.is_empty() => {
+                out.push_str("[\n");
+                for value in items {
+                    out.push_str(&pad);
+                    value.write_pretty(out, indent + 1);
+                    out.push_str(",\n");
+                }
+                out.push_str(&format!("{end_pad}]"));
+            }
+            Self::Map(entries) if !entries.is_empty() => {
+                out.push_str("{\n");
+                for (key, value) in entries {
+                    out.push_str(&format!("{pad}{key}: "));
+                    value.write_pretty(out, indent + 1);
+                    out.push_str(",\n");
+                }
+                out.push_str(&format!("{end_pad}}}"));
+            }
+            Self::Tuple(name, items) => {
+                out.push_str(name.as_deref().unwrap_or_default());
+                out.push('(');
+                for (idx, value) in items.iter().enumerate() {
+                    if idx > 0 {
+                        out.push_str(", ");
+                    }
+                    value.write_pretty(out, indent);
+                }
+                out.push(')');
+            }
+            other => out.push_str(&other.to_string()),
+        }
+    }
+
+    /// Get mutable map value by string key
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Self> {
+        match self {
+            Self::Map(entries) => entries
+                .iter_mut()
+                .find(|(k, _)| k.as_str().as_deref() == Some(key))
+                .map(|(_, v)| v),
+            _ => None,
+        }
+    }
+}
+
+/// Header of RON document, which enables `extensions`. Empty if there are no extensions
+pub fn extensions_header(extensions: &[String]) -> String {
+    if extensions.is_empty() {
+        String::new()
+    } else {
+        format!("#![enable({})]\n", extensions.join(", "))
+    }
+}
+
+impl fmt::Display for RonNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Value(value) => write!(f, "{value}"),
+            Self::Struct(name, fields) => {
+                write!(f, "{}(", name.as_deref().unwrap_or_default())?;
+                for (name, value) in fields {
+                    write!(f, "{name}: {value},")?;
+                }
+                write!(f, ")")
+            }
+            Self::Tuple(name, items) => {
+                write!(f, "{}(", name.as_deref().unwrap_or_default())?;
+                for value in items {
+                    write!(f, "{value},")?;
+                }
+                write!(f, ")")
+            }
+            Self::List(items) => {
+                write!(f, "[")?;
+                for value in items {
+                    write!(f, "{value},")?;
+                }
+                write!(f, "]")
+            }
+            Self::Map(entries) => {
+                write!(f, "{{")?;
+                for (key, value) in entries {
+                    write!(f, "{key}: {value},")?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, msg: &str) -> String {
+        let line = self.text[..self.pos].lines().count().max(1);
+        format!("{msg} at line {line}")
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn skip_ws(&mut self) {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if trimmed.starts_with("//") {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if trimmed.starts_with("/*") {
+                self.pos += block_comment_len(trimmed);
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// `#![enable(a, b)]` attributes before document value
+    fn extensions(&mut self) -> Result<Vec<String>, String> {
+        let mut extensions = vec![];
+        loop {
+            self.skip_ws();
+            if !self.rest().starts_with("#!") {
+                return Ok(extensions);
+            }
+            self.pos += 2;
+            self.expect('[')?;
+            self.skip_ws();
+            if self.word() != "enable" {
+                return Err(self.error("unknown attribute"));
+            }
+            self.expect('(')?;
+            while !self.eat(')') {
+                self.skip_ws();
+                let extension = self.word();
+                if extension.is_empty() {
+                    return Err(self.error("expected extension name"));
+                }
+                extensions.push(extension.to_string());
+                if !self.eat(',') {
+                    self.expect(')')?;
+                    break;
+                }
+            }
+            self.expect(']')?;
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_ws();
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{c}'")))
+        }
+    }
+
+    fn node(&mut self) -> Result<RonNode, String> {
+        self.skip_ws();
+        match self.peek() {
+            Some('(') => self.parens(None),
+            Some('[') => {
+                self.pos += 1;
+                let mut items = vec![];
+                while !self.eat(']') {
+                    items.push(self.node()?);
+                    if !self.eat(',') {
+                        self.expect(']')?;
+                        break;
+                    }
+                }
+                Ok(RonNode::List(items))
+            }
+            Some('{') => {
+                self.pos += 1;
+                let mut entries = vec![];
+                while !self.eat('}') {
+                    let key = self.node()?;
+                    self.expect(':')?;
+                    entries.push((key, self.node()?));
+                    if !self.eat(',') {
+                        self.expect('}')?;
+                        break;
+                    }
+                }
+                Ok(RonNode::Map(entries))
+            }
+            Some('"') => self.quoted('"'),
+            Some('\'') => self.quoted('\''),
+            Some(_) => {
+                if self.rest().starts_with("r\"") || self.rest().starts_with("r#") {
+                    return self.raw_string();
+                }
+                let word = self.word();
+                if word.is_empty() {
+                    return Err(self.error("unexpected character"));
+                }
+                self.skip_ws();
+                let is_ident = word
+                    .chars()
+                    .next()
+                    .is_some_and(|c| c.is_alphabetic() || c == '_');
+                if is_ident && self.peek() == Some('(') {
+                    self.parens(Some(word.to_string()))
+                } else {
+                    Ok(RonNode::Value(word.to_string()))
+                }
+            }
+            None => Err(self.error("unexpected end of file")),
+        }
+    }
+
+    fn word(&mut self) -> &'a str {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c.is_alphanumeric() || "_.+-".contains(c)))
+            .unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    fn quoted(&mut self, quote: char) -> Result<RonNode, String> {
+        let rest = self.rest();
+        let mut escaped = false;
+        for (idx, c) in rest.char_indices().skip(1) {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == quote {
+                self.pos += idx + 1;
+                return Ok(RonNode::Value(rest[..=idx].to_string()));
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+
+    fn raw_string(&mut self) -> Result<RonNode, String> {
+        let rest = self.rest();
+        let hashes = rest[1..].chars().take_while(|c| *c == '#').count();
+        let end = format!("\"{}", "#".repeat(hashes));
+        let start = 2 + hashes;
+        let len = rest[start..]
+            .find(&end)
+            .ok_or_else(|| self.error("unterminated raw string"))?;
+        let total = start + len + end.len();
+        self.pos += total;
+        Ok(RonNode::Value(rest[..total].to_string()))
+    }
+
+    fn parens(&mut self, name: Option<String>) -> Result<RonNode, String> {
+        self.expect('(')?;
+        if self.eat(')') {
+            return Ok(RonNode::Tuple(name, vec![]));
+        }
+        if self.is_field() {
+            let mut fields = vec![];
+            while !self.eat(')') {
+                self.skip_ws();
+                let field = self.word().to_string();
+                self.expect(':')?;
+                fields.push((field, self.node()?));
+                if !self.eat(',') {
+                    self.expect(')')?;
+                    break;
+                }
+            }
+            Ok(RonNode::Struct(name, fields))
+        } else {
+            let mut items = vec![];
+            while !self.eat(')') {
+                items.push(self.node()?);
+                if !self.eat(',') {
+                    self.expect(')')?;
+                    break;
+                }
+            }
+            Ok(RonNode::Tuple(name, items))
+        }
+    }
+
+    /// Check if next tokens are `ident:`
+    fn is_field(&mut self) -> bool {
+        self.skip_ws();
+        let start = self.pos;
+        let word = self.word();
+        let is_field = !word.is_empty()
+            && word
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_alphabetic() || c == '_')
+            && self.eat(':');
+        self.pos = start;
+        is_field
+    }
+}
+
+/// Length of `/* */` comment at start of `text`. Comments can be nested
+fn block_comment_len(text: &str) -> usize {
+    let mut depth = 0;
+    let mut idx = 0;
+    while idx < text.len() {
+        let rest = &text[idx..];
+        if rest.starts_with("/*") {
+            depth += 1;
+            idx += 2;
+        } else if rest.starts_with("*/") {
+            depth -= 1;
+            idx += 2;
+            if depth == 0 {
+                return idx;
+            }
+        } else {
+            idx += rest.chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    text.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip_keeps_names() {
+        let text = r##"(
+            // comment
+            a: Cube(3.0),
+            b: Some((x: 1, y: -2.5e-3)),
+            c: [None, Circle(r: 1.0)],
+            d: {"key": 'c', "raw": r#"text"#},
+            e: (),
+        )"##;
+        let node = RonNode::parse(text).unwrap();
+        assert_eq!(
+            node.field("a"),
+            Some(&RonNode::Tuple(
+                Some("Cube".to_string()),
+                vec![RonNode::Value("3.0".to_string())]
+            ))
+        );
+        assert_eq!(RonNode::parse(&node.to_string()).unwrap(), node);
+        let value: ron::Value = ron::from_str(&node.to_string()).unwrap();
+        let expected: ron::Value = ron::from_str(text).unwrap();
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn edit_struct_fields() {
+        let mut node = RonNode::parse("(old: 1, other: \"a\")").unwrap();
+        assert!(node.rename_field("old", "new"));
+        assert_eq!(
+            node.remove_field("other").unwrap().as_str().as_deref(),
+            Some("a")
+        );
+        node.set_field("added", RonNode::Value("true".to_string()));
+        assert_eq!(node.to_string(), "(new: 1,added: true,)");
+    }
+
+    #[test]
+    fn parse_error() {
+        assert!(RonNode::parse("(a: 1").is_err());
+        assert!(RonNode::parse("\"abc").is_err());
+    }
+
+    #[test]
+    fn strings_are_unescaped() {
+        let node =
+            RonNode::parse(r##"["a \"b\" \\ c\n", r#"raw "text""#, "\u{e9}", 'c']"##).unwrap();
+        let RonNode::List(items) = &node else {
+            panic!("expected list");
+        };
+        assert_eq!(items[0].as_str().unwrap(), "a \"b\" \\ c\n");
+        assert_eq!(items[1].as_str().unwrap(), "raw \"text\"");
+        assert_eq!(items[2].as_str().unwrap(), "\u{e9}");
+        assert_eq!(items[3].as_str(), None);
+
+        for text in ["plain", "a \"b\" \\ c\n\t", "bell \u{7}"] {
+            let value = RonNode::string(text);
+            assert_eq!(value.as_str().unwrap(), text);
+            assert_eq!(ron::from_str::<String>(&value.to_string()).unwrap(), text);
+        }
+    }
+
+    #[test]
+    fn extensions_in_header() {
+        let text = "#![enable(implicit_some)]\n#![enable(unwrap_newtypes, )]\n(a: 1)";
+        let (extensions, node) = RonNode::parse_with_extensions(text).unwrap();
+        assert_eq!(extensions, vec!["implicit_some", "unwrap_newtypes"]);
+        assert_eq!(node.field("a"), Some(&RonNode::Value("1".to_string())));
+        assert_eq!(
+            extensions_header(&extensions),
+            "#![enable(implicit_some, unwrap_newtypes)]\n"
+        );
+        assert!(RonNode::parse("#![deny(x)] ()").is_err());
+    }
+
+    #[test]
+    fn nested_comments() {
+        let node = RonNode::parse("/* a /* nested */ still comment */ (a: /* x */ 1)").unwrap();
+        assert_eq!(node.field("a"), Some(&RonNode::Value("1".to_string())));
+        assert!(RonNode::parse("/* a /* b */ (a: 1)").is_err());
+    }
+}
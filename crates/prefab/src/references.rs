@@ -0,0 +1,955 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    ecs::event::ManualEventReader,
+    prelude::*,
+    reflect::{GetPath, ReflectRef, TypePath},
+    utils::HashMap,
+};
+use space_shared::PrefabMarker;
+
+use crate::{
+    binary::serialize_binary,
+    component::{
+        AnimationPlayerPrefab, AssetMaterial, AssetMesh, AutoStruct, ColorMaterialPrefab,
+        GltfPrefab, MaterialPrefab, PlayerStart, SceneAutoChild, SkinnedMeshPrefab, SpriteTexture,
+        SpritesheetTexture,
+    },
+    editor_registry::EditorRegistry,
+    headless::{find_files, is_binary, is_prefab_file, load_prefab_file},
+    load::PrefabLoader,
+    material_file::MATERIAL_EXTENSION,
+    migration::{deserialize_prefab, Migrations, PrefabMigrations},
+    raw_ron::{extensions_header, RonNode},
+    save::{
+        extract_prefab_scene, serialize_prefab, write_file_atomic, PrefabSaveResult, SaveConfig,
+        DEFAULT_BACKUPS,
+    },
+};
+
+/// Folder scanned for scene files. Asset paths are relative to it
+pub const ASSETS_FOLDER: &str = "assets";
+
+/// Plugin to keep [`AssetReferenceIndex`] in sync with scene files
+pub struct AssetReferencePlugin;
+
+impl Plugin for AssetReferencePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AssetReferenceIndex>();
+
+        app.add_systems(Startup, scan_asset_references);
+        app.add_systems(Update, update_saved_scene_references);
+    }
+}
+
+/// Asset path stored in component of scene file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AssetReference {
+    /// Scene or material file relative to assets folder. Empty for scene opened in editor
+    pub scene: String,
+    /// Entity id in scene file. Always 0 for material file
+    pub entity: u64,
+    /// Type path of component
+    pub component: String,
+    /// Name of component field. For [`AutoStruct`] it is the key of `asset_paths`
+    pub field: String,
+    /// Referenced asset relative to assets folder. Can contain sub asset label
+    pub asset: String,
+}
+
+impl AssetReference {
+    /// Referenced file without sub asset label
+    pub fn file(&self) -> &str {
+        self.asset
+            .split_once('#')
+            .map_or(self.asset.as_str(), |(file, _)| file)
+    }
+
+    /// Component name without module path
+    pub fn component_name(&self) -> &str {
+        let path = self
+            .component
+            .split_once('<')
+            .map_or(self.component.as_str(), |(path, _)| path);
+        path.rsplit("::").next().unwrap_or(path)
+    }
+
+    /// Is referenced file missing in assets folder
+    pub fn is_missing(&self) -> bool {
+        !Path::new(ASSETS_FOLDER).join(self.file()).is_file()
+    }
+}
+
+/// Components of prefab crate with asset paths and names of their path fields
+fn reference_fields() -> [(&'static str, &'static [&'static str]); 11] {
+    [
+        (GltfPrefab::type_path(), &["path"]),
+        (AssetMesh::type_path(), &["path"]),
+        (AssetMaterial::type_path(), &["path"]),
+        (
+            MaterialPrefab::type_path(),
+            &[
+                "base_color_texture",
+                "emissive_texture",
+                "metallic_roughness_texture",
+                "normal_map_texture",
+                "occlusion_texture",
+                "depth_map",
+            ],
+        ),
+        (ColorMaterialPrefab::type_path(), &["texture"]),
+        (SpriteTexture::type_path(), &["texture"]),
+        (SpritesheetTexture::type_path(), &["texture"]),
+        (PlayerStart::type_path(), &["prefab"]),
+        (PrefabLoader::type_path(), &["path"]),
+        (SkinnedMeshPrefab::type_path(), &["inverse_bindposes"]),
+        (AnimationPlayerPrefab::type_path(), &["clips"]),
+    ]
+}
+
+/// Type path of [`AutoStruct`] without generic parameter
+fn auto_struct_path() -> &'static str {
+    let path = AutoStruct::<bool>::type_path();
+    path.split_once('<').map_or(path, |(path, _)| path)
+}
+
+/// Call `visit` with entity id, component type path, field name and value of each asset path
+/// field in RON scene
+fn visit_references(node: &mut RonNode, mut visit: impl FnMut(u64, &str, &str, &mut RonNode)) {
+    let Some(RonNode::Map(entities)) = node.field_mut("entities") else {
+        return;
+    };
+    for (entity, data) in entities.iter_mut() {
+        let entity = entity.to_string().parse::<u64>().unwrap_or_default();
+        let Some(RonNode::Map(components)) = data.field_mut("components") else {
+            continue;
+        };
+        for (component, value) in components.iter_mut() {
+            if let Some(component) = component.as_str() {
+                visit_component_references(entity, &component, value, &mut visit);
+            }
+        }
+    }
+}
+
+/// Call `visit` for each asset path field of one RON component value
+fn visit_component_references(
+    entity: u64,
+    component: &str,
+    value: &mut RonNode,
+    visit: &mut impl FnMut(u64, &str, &str, &mut RonNode),
+) {
+    if let Some((_, names)) = reference_fields()
+        .iter()
+        .find(|(path, _)| *path == component)
+    {
+        for name in names.iter() {
+            match value.field_mut(name) {
+                Some(RonNode::List(assets)) => {
+                    for (idx, asset) in assets.iter_mut().enumerate() {
+                        visit(entity, component, &format!("{name}[{idx}]"), asset);
+                    }
+                }
+                Some(asset) => visit(entity, component, name, asset),
+                None => {}
+            }
+        }
+    } else if component.starts_with(auto_struct_path()) {
+        if let Some(RonNode::Map(paths)) = value.field_mut("asset_paths") {
+            for (field, asset) in paths.iter_mut() {
+                visit(
+                    entity,
+                    component,
+                    &field.as_str().unwrap_or_default(),
+                    asset,
+                );
+            }
+        }
+    }
+}
+
+/// Is file a `.mat.ron` material file
+fn is_material_file(file: &str) -> bool {
+    file.ends_with(&format!(".{MATERIAL_EXTENSION}"))
+}
+
+/// Call `visit` for each asset path field of RON scene or material `file`.
+/// Material file is a single [`MaterialPrefab`] value with entity id 0
+fn visit_file_references(
+    file: &str,
+    node: &mut RonNode,
+    mut visit: impl FnMut(u64, &str, &str, &mut RonNode),
+) {
+    if is_material_file(file) {
+        visit_component_references(0, MaterialPrefab::type_path(), node, &mut visit);
+    } else {
+        visit_references(node, visit);
+    }
+}
+
+/// Collect asset references from components of RON scene file or from textures of material file
+pub fn scene_references(scene: &str, text: &str) -> Result<Vec<AssetReference>, String> {
+    let mut node = RonNode::parse(text)?;
+    let mut references = vec![];
+    visit_file_references(scene, &mut node, |entity, component, field, asset| {
+        if let Some(asset) = asset.as_str().filter(|asset| !asset.is_empty()) {
+            references.push(AssetReference {
+                scene: scene.to_string(),
+                entity,
+                component: component.to_string(),
+                field: field.to_string(),
+                asset: asset.to_string(),
+            });
+        }
+    });
+    Ok(references)
+}
+
+/// New path of `asset` after file or folder `from` is moved to `to`. Sub asset label is kept.
+/// Returns `None` if `asset` is not inside `from`
+pub fn moved_asset_path(asset: &str, from: &str, to: &str) -> Option<String> {
+    let rest = asset.strip_prefix(from)?;
+    (rest.is_empty() || rest.starts_with('#') || rest.starts_with('/'))
+        .then(|| format!("{to}{rest}"))
+}
+
+/// Rewrite references to moved file or folder in RON scene or material file.
+/// Returns `None` if file does not reference it
+pub fn rewrite_scene_references(
+    scene: &str,
+    text: &str,
+    from: &str,
+    to: &str,
+) -> Result<Option<String>, String> {
+    let (extensions, mut node) = RonNode::parse_with_extensions(text)?;
+    let mut changed = false;
+    visit_file_references(scene, &mut node, |_, _, _, asset| {
+        if let Some(moved) = asset
+            .as_str()
+            .and_then(|asset| moved_asset_path(&asset, from, to))
+        {
+            *asset = RonNode::string(&moved);
+            changed = true;
+        }
+    });
+    Ok(changed.then(|| {
+        format!(
+            "{}{}",
+            extensions_header(&extensions),
+            node.to_pretty_string()
+        )
+    }))
+}
+
+/// Asset references of all scene and material files in assets folder
+#[derive(Resource, Default, Clone, Debug)]
+pub struct AssetReferenceIndex {
+    /// References by scene or material file relative to assets folder
+    pub scenes: HashMap<String, Vec<AssetReference>>,
+    /// References by referenced file
+    usages: HashMap<String, Vec<AssetReference>>,
+}
+
+impl AssetReferenceIndex {
+    /// Rebuild index from all scene and material files in assets folder.
+    /// Binary scenes are read with types and migrations of `world`
+    pub fn scan(&mut self, world: &World) {
+        self.scenes.clear();
+        for file in find_scene_files(Path::new(ASSETS_FOLDER)) {
+            let scene = file
+                .to_string_lossy()
+                .replace('\\', "/")
+                .trim_start_matches(&format!("{ASSETS_FOLDER}/"))
+                .to_string();
+            self.read_scene(&scene, world);
+        }
+        self.update_usages();
+    }
+
+    /// Reindex one scene file after it is changed
+    pub fn update_scene_file(&mut self, scene: &str, world: &World) {
+        self.scenes.remove(scene);
+        self.read_scene(scene, world);
+        self.update_usages();
+    }
+
+    /// Set references of scene from its RON text
+    pub fn update_scene(&mut self, scene: &str, text: &str) -> Result<(), String> {
+        let references = scene_references(scene, text)?;
+        self.scenes.insert(scene.to_string(), references);
+        self.update_usages();
+        Ok(())
+    }
+
+    /// All references to file, with any sub asset label
+    pub fn usages(&self, file: &str) -> &[AssetReference] {
+        self.usages.get(file).map_or(&[], Vec::as_slice)
+    }
+
+    /// Reverse reference map: references by referenced file
+    pub fn reverse_map(&self) -> &HashMap<String, Vec<AssetReference>> {
+        &self.usages
+    }
+
+    /// References to files missing in assets folder
+    pub fn missing(&self) -> Vec<&AssetReference> {
+        self.usages
+            .values()
+            .flatten()
+            .filter(|reference| reference.is_missing())
+            .collect()
+    }
+
+    fn read_scene(&mut self, scene: &str, world: &World) {
+        let path = Path::new(ASSETS_FOLDER).join(scene);
+        let references =
+            read_scene_text(world, &path).and_then(|text| scene_references(scene, &text));
+        match references {
+            Ok(references) => {
+                self.scenes.insert(scene.to_string(), references);
+            }
+            Err(err) => warn!("Failed to index asset references of {}: {}", scene, err),
+        }
+    }
+
+    fn update_usages(&mut self) {
+        self.usages.clear();
+        for reference in self.scenes.values().flatten() {
+            self.usages
+                .entry(reference.file().to_string())
+                .or_default()
+                .push(reference.clone());
+        }
+        for references in self.usages.values_mut() {
+            references.sort_by(|a, b| (&a.scene, a.entity).cmp(&(&b.scene, b.entity)));
+        }
+    }
+}
+
+/// Scene and material files in folder and its subfolders
+fn find_scene_files(dir: &Path) -> Vec<PathBuf> {
+    find_files(dir, &|path| {
+        is_prefab_file(path) || is_material_file(&path.to_string_lossy())
+    })
+}
+
+/// Migrations registered in app
+fn app_migrations(world: &World) -> Migrations {
+    world
+        .get_resource::<PrefabMigrations>()
+        .and_then(|migrations| {
+            migrations
+                .0
+                .read()
+                .ok()
+                .map(|migrations| migrations.clone())
+        })
+        .unwrap_or_default()
+}
+
+/// RON text of scene or material file. Binary scene is converted to RON
+fn read_scene_text(world: &World, path: &Path) -> Result<String, String> {
+    if !is_binary(path) {
+        return fs::read_to_string(path).map_err(|e| e.to_string());
+    }
+    let type_registry = world.resource::<AppTypeRegistry>().0.clone();
+    let (scene, _) = load_prefab_file(path, &app_migrations(world), &type_registry)?;
+    serialize_prefab(world, &scene)
+}
+
+/// File content of scene or material file from RON text. Binary scene is converted back to binary
+fn scene_file_data(world: &World, path: &Path, text: String) -> Result<Vec<u8>, String> {
+    if !is_binary(path) {
+        return Ok(text.into_bytes());
+    }
+    let migrations = app_migrations(world);
+    let type_registry = world.resource::<AppTypeRegistry>().0.clone();
+    let mut scene = deserialize_prefab(&text, &migrations, &type_registry, &mut default())?;
+    scene.resources.push(Box::new(migrations.header()));
+    serialize_binary(&scene, &type_registry)
+}
+
+/// Asset references of entities opened in editor. Entity ids are ids of live entities
+pub fn open_scene_references(world: &World) -> Result<Vec<AssetReference>, String> {
+    let entities = world
+        .iter_entities()
+        .filter(|entity| entity.contains::<PrefabMarker>() && !entity.contains::<SceneAutoChild>())
+        .map(|entity| entity.id());
+    let scene = extract_prefab_scene(world, entities);
+    let text = serialize_prefab(world, &scene)?;
+    scene_references("", &text)
+}
+
+/// Rewrite references to moved file or folder in loaded entities. Returns number of changed components
+pub fn rewrite_loaded_references(world: &mut World, from: &str, to: &str) -> usize {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let mut changed = 0;
+    for (type_path, fields) in reference_fields() {
+        let Some(reflect_component) = type_registry
+            .get_with_type_path(type_path)
+            .and_then(|registration| registration.data::<ReflectComponent>())
+        else {
+            continue;
+        };
+        let entities = world
+            .iter_entities()
+            .filter(|entity| reflect_component.contains(*entity))
+            .map(|entity| entity.id())
+            .collect::<Vec<_>>();
+        for entity in entities {
+            let mut entity = world.entity_mut(entity);
+            let Some(mut component) = reflect_component.reflect_mut(&mut entity) else {
+                continue;
+            };
+            let moved = fields
+                .iter()
+                .flat_map(|field| {
+                    // List of paths is split into items
+                    match component
+                        .reflect_path(*field)
+                        .map(|value| value.reflect_ref())
+                    {
+                        Ok(ReflectRef::List(list)) => (0..list.len())
+                            .map(|idx| format!("{field}[{idx}]"))
+                            .collect::<Vec<_>>(),
+                        _ => vec![field.to_string()],
+                    }
+                })
+                .filter_map(|field| {
+                    // Field is `String` or `AssetPathField`
+                    [format!("{field}.path"), field]
+                        .into_iter()
+                        .find_map(|path| {
+                            let asset = component.path::<String>(path.as_str()).ok()?;
+                            moved_asset_path(asset, from, to).map(|moved| (path, moved))
+                        })
+                })
+                .collect::<Vec<_>>();
+            if moved.is_empty() {
+                continue;
+            }
+            for (path, asset) in moved {
+                if let Ok(field) = component.path_mut::<String>(path.as_str()) {
+                    *field = asset;
+                }
+            }
+            changed += 1;
+        }
+    }
+    drop(type_registry);
+
+    let auto_structs = world
+        .resource::<EditorRegistry>()
+        .auto_structs
+        .values()
+        .cloned()
+        .collect::<Vec<_>>();
+    for auto_struct in auto_structs {
+        changed += auto_struct.move_assets(world, &|asset| moved_asset_path(asset, from, to));
+    }
+    changed
+}
+
+/// Files and entities changed by [`move_asset`]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MovedAsset {
+    /// Rewritten scene and material files
+    pub scenes: Vec<String>,
+    /// Number of changed components of loaded entities
+    pub components: usize,
+}
+
+/// Move file or folder inside assets folder and rewrite references to it
+/// in all scene files, material files and loaded entities. Paths are relative to assets folder
+pub fn move_asset(world: &mut World, from: &str, to: &str) -> Result<MovedAsset, String> {
+    let from = from.trim_matches('/');
+    let to = to.trim_matches('/');
+    if from.is_empty() || to.is_empty() {
+        return Err("Asset path is empty".to_string());
+    }
+    if from == to {
+        return Ok(MovedAsset::default());
+    }
+    let assets = Path::new(ASSETS_FOLDER);
+
+    let backups = world
+        .get_resource::<SaveConfig>()
+        .map_or(DEFAULT_BACKUPS, |config| config.backups);
+    let mut index = world
+        .remove_resource::<AssetReferenceIndex>()
+        .unwrap_or_default();
+    index.scan(world);
+    let mut scenes = index
+        .scenes
+        .iter()
+        .filter(|(_, references)| {
+            references
+                .iter()
+                .any(|reference| moved_asset_path(&reference.asset, from, to).is_some())
+        })
+        .map(|(scene, _)| scene.clone())
+        .collect::<Vec<_>>();
+    scenes.sort();
+
+    // All scenes are rewritten in memory before anything is changed on disk,
+    // so failed scene does not leave references split between old and new paths
+    let mut rewritten = vec![];
+    for scene in scenes {
+        let path = assets.join(&scene);
+        let data = read_scene_text(world, &path)
+            .and_then(|text| rewrite_scene_references(&scene, &text, from, to))
+            .and_then(|text| {
+                text.map(|text| scene_file_data(world, &path, text))
+                    .transpose()
+            });
+        match data {
+            // Scene from moved folder is written to its new place
+            Ok(Some(data)) => {
+                rewritten.push((moved_asset_path(&scene, from, to).unwrap_or(scene), data))
+            }
+            Ok(None) => {}
+            Err(err) => {
+                world.insert_resource(index);
+                return Err(format!("Failed to update {}: {}", path.display(), err));
+            }
+        }
+    }
+
+    let result = move_asset_files(assets, from, to).and_then(|()| {
+        write_scene_files(assets, &rewritten, backups).inspect_err(|_| {
+            // Not rewritten scenes still refer to old paths
+            if let Err(err) = move_asset_files(assets, to, from) {
+                error!("Failed to move {to} back to {from}: {err}");
+            }
+        })
+    });
+    // Moved folder can contain scene files
+    index.scan(world);
+    world.insert_resource(index);
+    result?;
+
+    Ok(MovedAsset {
+        scenes: rewritten.into_iter().map(|(scene, _)| scene).collect(),
+        components: rewrite_loaded_references(world, from, to),
+    })
+}
+
+/// Write rewritten scene files. If one of them fails, already written files get old content back
+fn write_scene_files(
+    assets: &Path,
+    scenes: &[(String, Vec<u8>)],
+    backups: usize,
+) -> Result<(), String> {
+    let mut written = vec![];
+    for (scene, data) in scenes {
+        let path = assets.join(scene);
+        match fs::read(&path).and_then(|old| write_file_atomic(&path, data, backups).map(|()| old))
+        {
+            Ok(old) => written.push((path, old)),
+            Err(err) => {
+                for (path, old) in written {
+                    if let Err(err) = write_file_atomic(&path, &old, 0) {
+                        error!("Failed to restore {}: {err}", path.display());
+                    }
+                }
+                return Err(format!("Failed to update {}: {}", path.display(), err));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Move file or folder with bevy `.meta` file of it
+fn move_asset_files(assets: &Path, from: &str, to: &str) -> Result<(), String> {
+    let source = assets.join(from);
+    let target = assets.join(to);
+    if !source.exists() {
+        return Err(format!("{} not found", source.display()));
+    }
+    if target.exists() {
+        return Err(format!("{} already exists", target.display()));
+    }
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::rename(&source, &target).map_err(|e| e.to_string())?;
+    let meta = |path: &Path| PathBuf::from(format!("{}.meta", path.display()));
+    if meta(&source).is_file() {
+        fs::rename(meta(&source), meta(&target)).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// References of opened scene to files missing in assets folder
+pub fn missing_assets(world: &World) -> Result<Vec<AssetReference>, String> {
+    Ok(open_scene_references(world)?
+        .into_iter()
+        .filter(AssetReference::is_missing)
+        .collect())
+}
+
+fn scan_asset_references(world: &mut World) {
+    world.resource_scope::<AssetReferenceIndex, _>(|world, mut index| index.scan(world));
+}
+
+fn update_saved_scene_references(
+    world: &mut World,
+    mut reader: Local<ManualEventReader<PrefabSaveResult>>,
+) {
+    let scenes = reader
+        .read(world.resource::<Events<PrefabSaveResult>>())
+        .filter(|event| event.result.is_ok())
+        .filter_map(|event| {
+            let path = event.path.replace('\\', "/");
+            path.rsplit_once(&format!("{ASSETS_FOLDER}/"))
+                .map(|(_, scene)| scene.to_string())
+        })
+        .filter(|scene| is_prefab_file(Path::new(scene)))
+        .collect::<Vec<_>>();
+    if scenes.is_empty() {
+        return;
+    }
+    world.resource_scope::<AssetReferenceIndex, _>(|world, mut index| {
+        for scene in scenes {
+            index.update_scene_file(&scene, world);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor_registry::EditorRegistryExt;
+
+    const SCENE: &str = r#"(
+  resources: {},
+  entities: {
+    4294967296: (
+      components: {
+        "bevy_core::name::Name": (name: "Car"),
+        "space_prefab::component::GltfPrefab": (path: "models/car.glb", scene: "Scene0"),
+        "space_prefab::component::material::MaterialPrefab": (
+          base_color_texture: "textures/grid.png",
+          emissive_texture: "",
+        ),
+      },
+    ),
+    4294967297: (
+      components: {
+        "space_prefab::component::AssetMesh": (path: "models/car.glb#Mesh0/Primitive0"),
+        "space_prefab::component::auto_struct::AutoStruct<game::Weapon>": (
+          data: (),
+          asset_paths: {"sounds[0]": "sounds/shot.ogg"},
+        ),
+      },
+    ),
+  },
+)"#;
+
+    #[test]
+    fn references_in_scene() {
+        let references = scene_references("scenes/level.scn.ron", SCENE).unwrap();
+        let assets = references
+            .iter()
+            .map(|reference| (reference.entity, reference.field.as_str(), reference.file()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            assets,
+            vec![
+                (4294967296, "path", "models/car.glb"),
+                (4294967296, "base_color_texture", "textures/grid.png"),
+                (4294967297, "path", "models/car.glb"),
+                (4294967297, "sounds[0]", "sounds/shot.ogg"),
+            ]
+        );
+        assert_eq!(references[3].component_name(), "AutoStruct");
+        assert!(references[0].is_missing());
+    }
+
+    #[test]
+    fn usages_of_asset() {
+        let mut index = AssetReferenceIndex::default();
+        index.update_scene("scenes/level.scn.ron", SCENE).unwrap();
+        index
+            .update_scene("scenes/garage.scn.ron", SCENE.split_at(300).0)
+            .unwrap_err();
+        index
+            .update_scene(
+                "scenes/garage.scn.ron",
+                r#"(entities: {1: (components: {"space_prefab::load::PrefabLoader": (path: "models/car.glb")})})"#,
+            )
+            .unwrap();
+
+        let usages = index.usages("models/car.glb");
+        assert_eq!(usages.len(), 3);
+        assert_eq!(usages[0].scene, "scenes/garage.scn.ron");
+        assert_eq!(usages[2].asset, "models/car.glb#Mesh0/Primitive0");
+        assert!(index.usages("textures/missing.png").is_empty());
+        assert_eq!(index.reverse_map().len(), 3);
+    }
+
+    #[test]
+    fn missing_assets_of_open_scene() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            crate::editor_registry::EditorRegistryPlugin,
+        ))
+        .register_type::<crate::component::AssetPathField<bevy::gltf::Gltf>>()
+        .editor_registry::<GltfPrefab>();
+        let car = app
+            .world
+            .spawn((
+                PrefabMarker,
+                GltfPrefab {
+                    path: "models/missing_car.glb".into(),
+                    ..default()
+                },
+            ))
+            .id();
+        app.world.spawn(GltfPrefab::default());
+
+        let missing = missing_assets(&app.world).unwrap();
+        assert_eq!(missing.len(), 1);
+        assert_eq!(Entity::from_bits(missing[0].entity), car);
+        assert_eq!(missing[0].asset, "models/missing_car.glb");
+    }
+
+    #[test]
+    fn rewrite_moved_asset_in_scene() {
+        assert_eq!(
+            moved_asset_path("models/car.glb#Mesh0/Primitive0", "models", "meshes"),
+            Some("meshes/car.glb#Mesh0/Primitive0".to_string())
+        );
+        assert_eq!(
+            moved_asset_path("models/car.glb2", "models/car.glb", "a"),
+            None
+        );
+
+        let text = rewrite_scene_references("", SCENE, "models/car.glb", "vehicles/car.glb")
+            .unwrap()
+            .unwrap();
+        let assets = scene_references("", &text)
+            .unwrap()
+            .into_iter()
+            .map(|reference| reference.asset)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            assets,
+            vec![
+                "vehicles/car.glb",
+                "textures/grid.png",
+                "vehicles/car.glb#Mesh0/Primitive0",
+                "sounds/shot.ogg"
+            ]
+        );
+        assert!(text.contains("\"bevy_core::name::Name\": (\n"));
+        assert_eq!(
+            rewrite_scene_references("", SCENE, "textures/other.png", "a.png").unwrap(),
+            None
+        );
+
+        // Header with RON extensions is kept
+        let header = "#![enable(implicit_some)]\n";
+        let text = rewrite_scene_references("", &format!("{header}{SCENE}"), "models", "a")
+            .unwrap()
+            .unwrap();
+        assert!(text.starts_with(header));
+    }
+
+    #[test]
+    fn references_in_material_file() {
+        let material = r#"(
+  base_color: Rgba(red: 1.0, green: 1.0, blue: 1.0, alpha: 1.0),
+  base_color_texture: "textures/brick.png",
+  emissive_texture: "",
+  normal_map_texture: "textures/brick_normal.png",
+)"#;
+        let mut index = AssetReferenceIndex::default();
+        index
+            .update_scene("materials/brick.mat.ron", material)
+            .unwrap();
+        let usages = index.usages("textures/brick.png");
+        assert_eq!(usages.len(), 1);
+        assert_eq!(usages[0].entity, 0);
+        assert_eq!(usages[0].field, "base_color_texture");
+        assert_eq!(usages[0].component_name(), "MaterialPrefab");
+
+        let text = rewrite_scene_references(
+            "materials/brick.mat.ron",
+            material,
+            "textures",
+            "images/walls",
+        )
+        .unwrap()
+        .unwrap();
+        let assets = scene_references("materials/brick.mat.ron", &text)
+            .unwrap()
+            .into_iter()
+            .map(|reference| reference.asset)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            assets,
+            vec!["images/walls/brick.png", "images/walls/brick_normal.png"]
+        );
+        assert!(text.contains("base_color: Rgba("));
+
+        let dir = std::env::temp_dir().join(format!("space_find_materials_{}", std::process::id()));
+        fs::create_dir_all(dir.join("materials")).unwrap();
+        fs::write(dir.join("materials/brick.mat.ron"), material).unwrap();
+        fs::write(dir.join("materials/brick.png"), "").unwrap();
+        assert_eq!(
+            find_scene_files(&dir),
+            vec![dir.join("materials/brick.mat.ron")]
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn references_in_binary_scene() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            crate::editor_registry::EditorRegistryPlugin,
+        ))
+        .register_type::<crate::migration::PrefabVersion>()
+        .register_type::<HashMap<String, u32>>()
+        .register_type::<crate::component::AssetPathField<Mesh>>()
+        .editor_registry::<AssetMesh>();
+        let car = app
+            .world
+            .spawn(AssetMesh {
+                path: "models/car.glb#Mesh0/Primitive0".into(),
+            })
+            .id();
+        let mut scene = DynamicSceneBuilder::from_world(&app.world)
+            .extract_entity(car)
+            .build();
+        scene
+            .resources
+            .push(Box::new(Migrations::default().header()));
+        let bytes = serialize_binary(&scene, &app.world.resource::<AppTypeRegistry>().0).unwrap();
+
+        let dir = std::env::temp_dir().join(format!("space_binary_refs_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("car.scn.bin");
+        fs::write(&path, bytes).unwrap();
+        assert_eq!(find_scene_files(&dir), vec![path.clone()]);
+
+        let text = read_scene_text(&app.world, &path).unwrap();
+        let references = scene_references("car.scn.bin", &text).unwrap();
+        assert_eq!(references.len(), 1);
+        assert_eq!(references[0].file(), "models/car.glb");
+
+        let text = rewrite_scene_references("car.scn.bin", &text, "models", "meshes")
+            .unwrap()
+            .unwrap();
+        fs::write(&path, scene_file_data(&app.world, &path, text).unwrap()).unwrap();
+        assert!(!fs::read(&path).unwrap().starts_with(b"("));
+        let text = read_scene_text(&app.world, &path).unwrap();
+        assert_eq!(
+            scene_references("car.scn.bin", &text).unwrap()[0].asset,
+            "meshes/car.glb#Mesh0/Primitive0"
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rewrite_moved_asset_in_loaded_entities() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            crate::editor_registry::EditorRegistryPlugin,
+        ))
+        .editor_registry::<AssetMesh>()
+        .editor_registry::<PrefabLoader>()
+        .editor_registry::<AnimationPlayerPrefab>();
+        let mesh = app
+            .world
+            .spawn((
+                AssetMesh {
+                    path: "models/car.glb#Mesh0/Primitive0".into(),
+                },
+                PrefabLoader {
+                    path: "scenes/car.scn.ron".to_string(),
+                },
+                AnimationPlayerPrefab {
+                    clips: vec![
+                        "models/car.glb#Animation0".into(),
+                        "models/truck.glb#Animation0".into(),
+                    ],
+                    ..default()
+                },
+            ))
+            .id();
+
+        assert_eq!(
+            rewrite_loaded_references(&mut app.world, "models/car.glb", "cars/car.glb"),
+            2
+        );
+        assert_eq!(
+            rewrite_loaded_references(&mut app.world, "scenes", "levels"),
+            1
+        );
+        let entity = app.world.entity(mesh);
+        assert_eq!(
+            entity.get::<AssetMesh>().unwrap().path.as_str(),
+            "cars/car.glb#Mesh0/Primitive0"
+        );
+        assert_eq!(
+            entity.get::<PrefabLoader>().unwrap().path,
+            "levels/car.scn.ron"
+        );
+        let clips = &entity.get::<AnimationPlayerPrefab>().unwrap().clips;
+        assert_eq!(clips[0].as_str(), "cars/car.glb#Animation0");
+        assert_eq!(clips[1].as_str(), "models/truck.glb#Animation0");
+    }
+
+    #[test]
+    fn move_file_with_meta() {
+        let dir = std::env::temp_dir().join(format!("space_move_asset_{}", std::process::id()));
+        fs::create_dir_all(dir.join("models")).unwrap();
+        fs::write(dir.join("models/car.glb"), "car").unwrap();
+        fs::write(dir.join("models/car.glb.meta"), "meta").unwrap();
+
+        move_asset_files(&dir, "models/car.glb", "vehicles/red/car.glb").unwrap();
+        assert!(dir.join("vehicles/red/car.glb").is_file());
+        assert!(dir.join("vehicles/red/car.glb.meta").is_file());
+        assert!(!dir.join("models/car.glb").exists());
+        assert!(move_asset_files(&dir, "models/car.glb", "car.glb").is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn failed_scene_write_restores_written_scenes() {
+        let dir = std::env::temp_dir().join(format!("space_write_scenes_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.scn.ron"), "old a").unwrap();
+        fs::write(dir.join("b.scn.ron"), "old b").unwrap();
+        // Temporary file of second scene can not be created
+        fs::create_dir_all(dir.join("b.scn.ron.tmp")).unwrap();
+
+        let scenes = [
+            ("a.scn.ron".to_string(), b"new a".to_vec()),
+            ("b.scn.ron".to_string(), b"new b".to_vec()),
+        ];
+        assert!(write_scene_files(&dir, &scenes, 1).is_err());
+        assert_eq!(fs::read_to_string(dir.join("a.scn.ron")).unwrap(), "old a");
+        assert_eq!(fs::read_to_string(dir.join("b.scn.ron")).unwrap(), "old b");
+
+        fs::remove_dir_all(dir.join("b.scn.ron.tmp")).unwrap();
+        write_scene_files(&dir, &scenes, 1).unwrap();
+        assert_eq!(fs::read_to_string(dir.join("b.scn.ron")).unwrap(), "new b");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
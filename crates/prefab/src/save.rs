@@ -4,10 +4,24 @@ use bevy::{
     tasks::IoTaskPool,
     utils::HashSet,
 };
-use space_shared::{EditorPrefabPath, PrefabMarker, PrefabMemoryCache};
-use std::{any::TypeId, fs, io::Write};
+use space_shared::{EditorEvent, EditorPrefabPath, PrefabMarker, PrefabMemoryCache};
+use space_undo::{AddedEntity, NewChange, RemovedHierarchy};
+use std::{
+    any::TypeId,
+    fs,
+    io::Write,
+    path::Path,
+    sync::{Arc, Mutex},
+};
 
-use crate::prelude::{EditorRegistry, EditorRegistryExt, SceneAutoChild};
+use crate::{
+    binary::{binary_path, serialize_binary, PrefabFormat},
+    guid::{sort_prefab_scene, type_path},
+    load::{report_error, PrefabBundle},
+    migration::PrefabMigrations,
+    prelude::{EditorRegistry, EditorRegistryExt, SceneAutoChild},
+    scenes::EditorScene,
+};
 
 #[derive(Reflect, Default, Component, Clone)]
 #[reflect(Component, MapEntities)]
@@ -38,7 +52,10 @@ impl Plugin for SaveResourcesPrefabPlugin {
     fn build(&self, app: &mut App) {
         app.editor_registry::<ChildrenPrefab>();
 
-        app.init_resource::<SaveConfig>().init_state::<SaveState>();
+        app.init_resource::<SaveConfig>()
+            .init_resource::<PendingSaveResults>()
+            .add_event::<PrefabSaveResult>()
+            .init_state::<SaveState>();
     }
 }
 
@@ -49,9 +66,22 @@ impl Plugin for SavePrefabPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(SaveResourcesPrefabPlugin {});
 
+        app.add_event::<CreatePrefabFromEntities>();
+        app.add_event::<RestorePrefabBackup>();
+        app.add_systems(
+            Update,
+            (
+                create_prefab_from_entities,
+                restore_prefab_backups,
+                send_save_results,
+            ),
+        );
+
         app.add_systems(
             OnEnter(SaveState::Save),
             (
+                crate::overrides::record_prefab_overrides,
+                crate::guid::assign_prefab_guids,
                 prepare_children,
                 apply_deferred,
                 serialize_scene,
@@ -64,9 +94,26 @@ impl Plugin for SavePrefabPlugin {
 
 /// This struct determine path to save prefab
 #[cfg(not(tarpaulin_include))]
-#[derive(Resource, Clone, Default)]
+#[derive(Resource, Clone)]
 pub struct SaveConfig {
     pub path: Option<EditorPrefabPath>,
+    /// Encoding of saved file
+    pub format: PrefabFormat,
+    /// Number of `.bak` copies kept next to saved file
+    pub backups: usize,
+}
+
+/// Default number of backup copies of saved file
+pub const DEFAULT_BACKUPS: usize = 3;
+
+impl Default for SaveConfig {
+    fn default() -> Self {
+        Self {
+            path: None,
+            format: PrefabFormat::default(),
+            backups: DEFAULT_BACKUPS,
+        }
+    }
 }
 
 /// State system using to enable slow logic of saving
@@ -95,12 +142,427 @@ fn delete_prepared_children(mut commands: Commands, query: Query<Entity, With<Ch
     }
 }
 
+/// Extract entities with all components registered in [`EditorRegistry`]
+pub fn build_prefab_scene(world: &World, entities: impl Iterator<Item = Entity>) -> DynamicScene {
+    let mut scene = extract_prefab_scene(world, entities);
+    sort_prefab_scene(world, &mut scene);
+    scene
+}
+
+/// Same as [`build_prefab_scene`], but entities keep runtime ids
+pub(crate) fn extract_prefab_scene(
+    world: &World,
+    entities: impl Iterator<Item = Entity>,
+) -> DynamicScene {
+    let registry = world.resource::<EditorRegistry>().clone();
+    let allow_types: Vec<TypeId> = registry
+        .registry
+        .read()
+        .iter()
+        .map(|a| a.type_id())
+        .collect();
+    let mut builder = DynamicSceneBuilder::from_world(world);
+    builder = builder
+        .allow_all()
+        .with_filter(SceneFilter::Allowlist(HashSet::from_iter(
+            allow_types.iter().cloned(),
+        )))
+        .extract_entities(entities);
+    let mut scene = builder.build();
+    registry.convert_auto_structs(&mut scene);
+    if let Some(migrations) = world.get_resource::<PrefabMigrations>() {
+        if let Ok(migrations) = migrations.0.read() {
+            scene.resources.push(Box::new(migrations.header()));
+        }
+    }
+    scene
+}
+
+/// Add resources registered with `editor_scene_resource` to saved scene
+pub fn add_scene_resources(world: &World, scene: &mut DynamicScene) {
+    let allow = world
+        .resource::<EditorRegistry>()
+        .scene_resources
+        .iter()
+        .map(|resource| resource.type_id)
+        .collect::<HashSet<_>>();
+    if allow.is_empty() {
+        return;
+    }
+    let resources = DynamicSceneBuilder::from_world(world)
+        .with_resource_filter(SceneFilter::Allowlist(allow))
+        .extract_resources()
+        .build()
+        .resources;
+    scene.resources.extend(resources);
+    scene
+        .resources
+        .sort_by(|a, b| type_path(a.as_ref()).cmp(type_path(b.as_ref())));
+}
+
+/// Serialize prefab to RON. Unknown components are written back as they were loaded
+pub fn serialize_prefab(world: &World, scene: &DynamicScene) -> Result<String, String> {
+    let data = scene
+        .serialize_ron(world.resource::<AppTypeRegistry>())
+        .map_err(|e| e.to_string())?;
+    if data.contains(crate::lenient::UnknownComponents::type_path()) {
+        crate::lenient::restore_unknown_components(&data)
+    } else {
+        Ok(data)
+    }
+}
+
+/// Write serialized scene to file in background and reload opened instances of this prefab.
+/// Result is sent as [`PrefabSaveResult`] event
+pub fn write_scene_file(world: &World, data: impl AsRef<[u8]> + Send + 'static, path: String) {
+    let asset_server = world.get_resource::<AssetServer>().cloned();
+    let backups = world
+        .get_resource::<SaveConfig>()
+        .map_or(DEFAULT_BACKUPS, |config| config.backups);
+    let results = world
+        .get_resource::<PendingSaveResults>()
+        .cloned()
+        .unwrap_or_default();
+    IoTaskPool::get()
+        .spawn(async move {
+            let result = write_file_atomic(Path::new(&path), data.as_ref(), backups)
+                .map_err(|e| e.to_string());
+            if result.is_ok() {
+                info!("Saved prefab to file {}", path);
+                // Update opened instances of this prefab
+                if let (Some(asset_server), Some((_, asset_path))) =
+                    (asset_server, path.rsplit_once("assets/"))
+                {
+                    if asset_server
+                        .get_handle::<DynamicScene>(asset_path.to_string())
+                        .is_some()
+                    {
+                        asset_server.reload(asset_path.to_string());
+                    }
+                }
+            }
+            if let Ok(mut results) = results.0.lock() {
+                results.push(PrefabSaveResult { path, result });
+            }
+        })
+        .detach();
+}
+
+/// Path of n-th backup copy of file. First copy is the newest
+pub fn backup_path(path: &str, idx: usize) -> String {
+    format!("{path}.bak{idx}")
+}
+
+/// Write file through temporary file and rename it, so crash during write does not destroy
+/// old file. Old file is kept as first backup, older backups are shifted and last one is removed
+pub fn write_file_atomic(path: &Path, data: &[u8], backups: usize) -> std::io::Result<()> {
+    let path_str = path.to_string_lossy().to_string();
+    let tmp_path = format!("{path_str}.tmp");
+    {
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(data)?;
+        file.sync_all()?;
+    }
+
+    if backups > 0 && path.exists() {
+        let _ = fs::remove_file(backup_path(&path_str, backups));
+        for idx in (1..backups).rev() {
+            let from = backup_path(&path_str, idx);
+            if Path::new(&from).exists() {
+                fs::rename(&from, backup_path(&path_str, idx + 1))?;
+            }
+        }
+        fs::copy(path, backup_path(&path_str, 1))?;
+    }
+
+    fs::rename(&tmp_path, path).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp_path);
+    })
+}
+
+/// Result of writing prefab file
+#[derive(Event, Clone, Debug)]
+pub struct PrefabSaveResult {
+    pub path: String,
+    pub result: Result<(), String>,
+}
+
+/// Results of background writes waiting to be sent as events
+#[derive(Resource, Default, Clone)]
+pub struct PendingSaveResults(pub Arc<Mutex<Vec<PrefabSaveResult>>>);
+
+fn send_save_results(world: &mut World) {
+    let results = world
+        .resource::<PendingSaveResults>()
+        .0
+        .lock()
+        .map(|mut results| std::mem::take(&mut *results))
+        .unwrap_or_default();
+    for result in results {
+        if let Err(e) = &result.result {
+            report_error(world, format!("Failed to save {}: {e}", result.path));
+        }
+        world.send_event(result);
+    }
+}
+
+/// Event to replace file with its backup copy and load it in editor
+#[derive(Event, Clone)]
+pub struct RestorePrefabBackup {
+    /// Path to backup file, like `assets/scenes/level.scn.ron.bak1`
+    pub backup: String,
+}
+
+fn restore_prefab_backups(world: &mut World) {
+    let events = world
+        .resource_mut::<Events<RestorePrefabBackup>>()
+        .drain()
+        .collect::<Vec<_>>();
+    for event in events {
+        match restore_backup(world, &event.backup) {
+            Ok(path) => {
+                #[cfg(feature = "editor")]
+                world.send_event(space_shared::toast::ToastMessage::new(
+                    &format!("Restored {path} from backup"),
+                    space_shared::toast::ToastKind::Success,
+                ));
+                info!("Restored {} from {}", path, event.backup);
+            }
+            Err(err) => report_error(world, format!("Failed to restore backup: {err}")),
+        }
+    }
+}
+
+fn restore_backup(world: &mut World, backup: &str) -> Result<String, String> {
+    let path = backup
+        .rsplit_once(".bak")
+        .filter(|(_, idx)| idx.parse::<usize>().is_ok())
+        .map(|(path, _)| path.to_string())
+        .ok_or_else(|| format!("{backup} is not a backup file"))?;
+    let data = fs::read(backup).map_err(|e| e.to_string())?;
+    let backups = world
+        .get_resource::<SaveConfig>()
+        .map_or(DEFAULT_BACKUPS, |config| config.backups);
+    // Current version becomes backup too, so restore can be reverted
+    write_file_atomic(Path::new(&path), &data, backups).map_err(|e| e.to_string())?;
+
+    if let Some((_, asset_path)) = path.rsplit_once("assets/") {
+        if let Some(mut events) = world.get_resource_mut::<Events<EditorEvent>>() {
+            events.send(EditorEvent::Load(EditorPrefabPath::File(
+                asset_path.to_string(),
+            )));
+        }
+    }
+    Ok(path)
+}
+
+/// Same as [`build_prefab_scene`], but also stores hierarchy between given entities in [`ChildrenPrefab`]
+pub fn build_prefab_scene_with_children(world: &World, entities: &[Entity]) -> DynamicScene {
+    let mut scene = extract_prefab_scene_with_children(world, entities);
+    sort_prefab_scene(world, &mut scene);
+    scene
+}
+
+pub(crate) fn extract_prefab_scene_with_children(
+    world: &World,
+    entities: &[Entity],
+) -> DynamicScene {
+    let mut scene = extract_prefab_scene(world, entities.iter().copied());
+    for dyn_entity in scene.entities.iter_mut() {
+        let children = world
+            .get::<Children>(dyn_entity.entity)
+            .map(|children| {
+                children
+                    .iter()
+                    .filter(|child| entities.contains(child))
+                    .copied()
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        dyn_entity
+            .components
+            .retain(|component| !component.represents::<ChildrenPrefab>());
+        if !children.is_empty() {
+            dyn_entity
+                .components
+                .push(Box::new(ChildrenPrefab(children)));
+        }
+    }
+    scene
+}
+
+/// Event to save entities with their children to new prefab file
+/// and replace them with single prefab instance
+#[derive(Event, Clone)]
+pub struct CreatePrefabFromEntities {
+    pub entities: Vec<Entity>,
+    /// Path to new prefab file relative to assets folder
+    pub path: String,
+}
+
+fn create_prefab_from_entities(world: &mut World) {
+    let events = world
+        .resource_mut::<Events<CreatePrefabFromEntities>>()
+        .drain()
+        .collect::<Vec<_>>();
+    for event in events {
+        match create_prefab(world, &event.entities, &event.path) {
+            Ok(id) => {
+                #[cfg(feature = "editor")]
+                world.send_event(space_shared::toast::ToastMessage::new(
+                    &format!("Created prefab {}", event.path),
+                    space_shared::toast::ToastKind::Success,
+                ));
+                info!("Created prefab {} with instance {:?}", event.path, id);
+            }
+            Err(err) => report_error(world, format!("Failed to create prefab: {err}")),
+        }
+    }
+}
+
+/// Prefab entities of selection, which are not descendants of other selected entities
+pub(crate) fn selection_roots(world: &World, entities: &[Entity]) -> Vec<Entity> {
+    let is_selected_descendant = |mut e: Entity| {
+        while let Some(parent) = world.get::<Parent>(e) {
+            e = parent.get();
+            if entities.contains(&e) {
+                return true;
+            }
+        }
+        false
+    };
+    entities
+        .iter()
+        .copied()
+        .filter(|e| world.get::<PrefabMarker>(*e).is_some())
+        .filter(|e| !is_selected_descendant(*e))
+        .collect()
+}
+
+/// Prefab entities of `roots` with all their saved descendants
+pub(crate) fn prefab_subtree(world: &World, roots: &[Entity]) -> Vec<Entity> {
+    let mut subtree = vec![];
+    let mut stack = roots.to_vec();
+    while let Some(e) = stack.pop() {
+        if world.get::<PrefabMarker>(e).is_none() || world.get::<SceneAutoChild>(e).is_some() {
+            continue;
+        }
+        subtree.push(e);
+        if let Some(children) = world.get::<Children>(e) {
+            stack.extend(children.iter());
+        }
+    }
+    subtree
+}
+
+fn create_prefab(world: &mut World, entities: &[Entity], path: &str) -> Result<Entity, String> {
+    // Only top entities of selection are replaced, their children are saved with them
+    let roots = selection_roots(world, entities);
+    let Some(first) = roots.first().copied() else {
+        return Err("no prefab entities selected".to_string());
+    };
+    if roots
+        .iter()
+        .any(|root| world.get::<EditorScene>(*root).is_some())
+    {
+        return Err("scene root can not be saved as prefab".to_string());
+    }
+    let subtree = prefab_subtree(world, &roots);
+
+    // New instance replaces first root, all roots are stored relative to it
+    let anchor = world.get::<Transform>(first).copied().unwrap_or_default();
+    let anchor_global = world
+        .get::<GlobalTransform>(first)
+        .copied()
+        .unwrap_or_default();
+    let parent = world.get::<Parent>(first).map(|parent| parent.get());
+
+    let mut scene = extract_prefab_scene_with_children(world, &subtree);
+    for dyn_entity in scene.entities.iter_mut() {
+        if !roots.contains(&dyn_entity.entity) {
+            continue;
+        }
+        let global = world
+            .get::<GlobalTransform>(dyn_entity.entity)
+            .copied()
+            .unwrap_or_default();
+        let relative = global.reparented_to(&anchor_global);
+        for component in dyn_entity.components.iter_mut() {
+            if component.represents::<Transform>() {
+                *component = Box::new(relative);
+            }
+        }
+    }
+    sort_prefab_scene(world, &mut scene);
+
+    let data = serialize_prefab(world, &scene)?;
+    // Written in place, because instance will load this file right away
+    let backups = world
+        .get_resource::<SaveConfig>()
+        .map_or(DEFAULT_BACKUPS, |config| config.backups);
+    write_file_atomic(
+        Path::new(&format!("assets/{path}")),
+        data.as_bytes(),
+        backups,
+    )
+    .map_err(|e| e.to_string())?;
+
+    for root in roots.iter() {
+        let change = RemovedHierarchy::new(world, *root);
+        world.entity_mut(*root).despawn_recursive();
+        world.send_event(NewChange {
+            change: Arc::new(change),
+        });
+    }
+
+    let name = path
+        .rsplit('/')
+        .next()
+        .unwrap_or(path)
+        .trim_end_matches(".scn.ron")
+        .to_string();
+    let id = world
+        .spawn((PrefabBundle::new(path), PrefabMarker, Name::new(name)))
+        .insert(anchor)
+        .id();
+    if let Some(parent) = parent {
+        world.entity_mut(parent).add_child(id);
+    }
+    world.send_event(NewChange {
+        change: Arc::new(AddedEntity { entity: id }),
+    });
+    Ok(id)
+}
+
+/// Write serialized prefab and its binary copy in formats selected by `format`
+pub(crate) fn write_prefab_formats(
+    world: &mut World,
+    scene: &DynamicScene,
+    ron: String,
+    path: String,
+    format: PrefabFormat,
+) {
+    if format.writes_binary() {
+        match serialize_binary(scene, world.resource::<AppTypeRegistry>()) {
+            Ok(data) => write_scene_file(world, data, binary_path(&path)),
+            Err(e) => report_error(world, format!("failed to serialize binary prefab: {e}")),
+        }
+    }
+    if format.writes_ron() {
+        write_scene_file(world, ron, path);
+    }
+}
+
 /// Convert world scene to prefab
 pub fn serialize_scene(world: &mut World) {
     let config = world.resource::<SaveConfig>().clone();
 
-    let mut prefab_query =
-        world.query_filtered::<Entity, (With<PrefabMarker>, Without<SceneAutoChild>)>();
+    // Scene roots are not part of saved scene, their entities are saved as top entities
+    let mut prefab_query = world.query_filtered::<Entity, (
+        With<PrefabMarker>,
+        Without<SceneAutoChild>,
+        Without<EditorScene>,
+    )>();
     let entities = prefab_query.iter(world).collect::<Vec<_>>();
 
     if entities.is_empty() {
@@ -112,23 +574,10 @@ pub fn serialize_scene(world: &mut World) {
         warn!("Saving empty scene");
     }
 
-    let registry = world.resource::<EditorRegistry>().clone();
-    let allow_types: Vec<TypeId> = registry
-        .registry
-        .read()
-        .iter()
-        .map(|a| a.type_id())
-        .collect();
-    let mut builder = DynamicSceneBuilder::from_world(world);
-    builder = builder
-        .allow_all()
-        .with_filter(SceneFilter::Allowlist(HashSet::from_iter(
-            allow_types.iter().cloned(),
-        )))
-        .extract_entities(entities.iter().copied());
-    let scene = builder.build();
+    let mut scene = build_prefab_scene(world, entities.iter().copied());
+    add_scene_resources(world, &mut scene);
 
-    let res = scene.serialize_ron(world.resource::<AppTypeRegistry>());
+    let res = serialize_prefab(world, &scene);
 
     if let Ok(str) = res {
         // Write the scene RON data to file
@@ -136,20 +585,7 @@ pub fn serialize_scene(world: &mut World) {
         if let Some(path) = path {
             match path {
                 EditorPrefabPath::File(path) => {
-                    IoTaskPool::get()
-                        .spawn(async move {
-                            fs::OpenOptions::new()
-                                .create(true)
-                                .truncate(true)
-                                .append(false)
-                                .write(true)
-                                .open(&path)
-                                .and_then(|mut file| file.write(str.as_bytes()))
-                                .inspect_err(|e| error!("Error while writing scene to file: {e}"))
-                                .expect("Error while writing scene to file");
-                            info!("Saved prefab to file {}", path);
-                        })
-                        .detach();
+                    write_prefab_formats(world, &scene, str, path, config.format);
                 }
                 EditorPrefabPath::MemoryCache => {
                     let handle = world.resource_mut::<Assets<DynamicScene>>().add(scene);
@@ -177,13 +613,15 @@ pub fn serialize_scene(world: &mut World) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::prelude::*;
+    use crate::{load::PrefabLoader, prelude::*};
 
     #[test]
     fn flaky_save_to_file() {
         let file = "test.ron";
         let save_config = SaveConfig {
             path: Some(EditorPrefabPath::File(String::from(file))),
+            backups: 0,
+            ..default()
         };
         let mut app = App::new();
         app.add_plugins((
@@ -223,10 +661,128 @@ mod tests {
         assert!(contents.contains("space_shared::PrefabMarker"));
     }
 
+    #[test]
+    fn scene_roots_are_not_saved() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            bevy::scene::ScenePlugin,
+            HierarchyPlugin,
+            EditorRegistryPlugin {},
+            SaveResourcesPrefabPlugin {},
+        ))
+        .insert_resource(SaveConfig {
+            path: Some(EditorPrefabPath::MemoryCache),
+            ..default()
+        })
+        .init_resource::<PrefabMemoryCache>()
+        .editor_registry::<Name>()
+        .editor_registry::<PrefabMarker>()
+        .editor_silent_registry::<EditorScene>();
+
+        let entity = app.world.spawn((PrefabMarker, Name::new("entity"))).id();
+        app.world
+            .spawn((PrefabMarker, EditorScene::new("level.scn.ron")))
+            .add_child(entity);
+
+        serialize_scene(&mut app.world);
+
+        let handle = app.world.resource::<PrefabMemoryCache>().scene.clone();
+        let scenes = app.world.resource::<Assets<DynamicScene>>();
+        let scene = scenes.get(handle.unwrap()).unwrap();
+        assert_eq!(scene.entities.len(), 1);
+        assert_eq!(scene.entities[0].entity, entity);
+    }
+
+    #[derive(Resource, Reflect, Default, Clone, PartialEq, Debug)]
+    #[reflect(Resource)]
+    struct Gravity(f32);
+
+    #[test]
+    fn scene_resources_are_saved_and_restored() {
+        let mut app = App::new();
+        app.add_plugins(EditorRegistryPlugin {})
+            .editor_scene_resource::<Gravity>();
+        app.world.resource_mut::<Gravity>().0 = -9.8;
+
+        let mut scene = DynamicScene::default();
+        add_scene_resources(&app.world, &mut scene);
+        assert_eq!(scene.resources.len(), 1);
+
+        let resources = app
+            .world
+            .resource::<EditorRegistry>()
+            .scene_resources
+            .clone();
+        resources[0].reset(&mut app.world);
+        assert_eq!(app.world.resource::<Gravity>(), &Gravity(0.));
+
+        scene
+            .write_to_world(&mut app.world, &mut default())
+            .unwrap();
+        assert_eq!(app.world.resource::<Gravity>(), &Gravity(-9.8));
+    }
+
+    #[test]
+    fn scene_resources_reset_to_app_values() {
+        let mut app = App::new();
+        app.insert_resource(Gravity(-9.8))
+            .add_plugins(EditorRegistryPlugin {})
+            .editor_scene_resource::<Gravity>();
+        let resources = app
+            .world
+            .resource::<EditorRegistry>()
+            .scene_resources
+            .clone();
+
+        app.world.resource_mut::<Gravity>().0 = -1.6;
+        resources[0].reset(&mut app.world);
+        assert_eq!(app.world.resource::<Gravity>(), &Gravity(-9.8));
+
+        // Value set by game after registration is captured at app start
+        app.insert_resource(Gravity(-3.7));
+        app.update();
+        app.world.resource_mut::<Gravity>().0 = -1.6;
+        resources[0].reset(&mut app.world);
+        assert_eq!(app.world.resource::<Gravity>(), &Gravity(-3.7));
+    }
+
+    #[test]
+    fn atomic_write_rotates_backups() {
+        let dir = std::env::temp_dir().join(format!("space_prefab_backup_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("level.scn.ron");
+        let path_str = path.to_string_lossy().to_string();
+
+        for version in 0..4 {
+            write_file_atomic(&path, format!("v{version}").as_bytes(), 2).unwrap();
+        }
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "v3");
+        assert_eq!(fs::read_to_string(backup_path(&path_str, 1)).unwrap(), "v2");
+        assert_eq!(fs::read_to_string(backup_path(&path_str, 2)).unwrap(), "v1");
+        assert!(!Path::new(&backup_path(&path_str, 3)).exists());
+        assert!(!Path::new(&format!("{path_str}.tmp")).exists());
+
+        let mut world = World::new();
+        world.insert_resource(SaveConfig {
+            backups: 2,
+            ..default()
+        });
+        restore_backup(&mut world, &backup_path(&path_str, 2)).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "v1");
+        assert_eq!(fs::read_to_string(backup_path(&path_str, 1)).unwrap(), "v3");
+        assert!(restore_backup(&mut world, &path_str).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
     #[test]
     fn save_to_memory() {
         let save_config = SaveConfig {
             path: Some(EditorPrefabPath::MemoryCache),
+            ..default()
         };
         let mut app = App::new();
         app.add_plugins((
@@ -313,6 +869,7 @@ mod tests {
     fn attempts_to_serialize_empty_scene() {
         let save_config = SaveConfig {
             path: Some(EditorPrefabPath::MemoryCache),
+            ..default()
         };
         let mut app = App::new();
         app.add_plugins((
@@ -359,4 +916,70 @@ mod tests {
         let mut query = app.world.query_filtered::<Entity, With<ChildrenPrefab>>();
         assert_eq!(query.iter(&app.world).count(), 1);
     }
+
+    #[test]
+    fn create_prefab_replaces_subtree() {
+        let file = "test_created_prefab.scn.ron";
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            bevy::scene::ScenePlugin,
+            HierarchyPlugin,
+            EditorRegistryPlugin {},
+            SaveResourcesPrefabPlugin {},
+            space_undo::UndoPlugin,
+        ))
+        .editor_registry::<Name>()
+        .editor_registry::<Transform>();
+
+        let scene = app
+            .world
+            .spawn((PrefabMarker, EditorScene::new("a.scn.ron")))
+            .id();
+        assert!(create_prefab(&mut app.world, &[scene], file).is_err());
+
+        let child = app.world.spawn((PrefabMarker, Name::new("child"))).id();
+        let root = app
+            .world
+            .spawn((
+                PrefabMarker,
+                Name::new("root"),
+                Transform::from_xyz(1., 0., 0.),
+            ))
+            .add_child(child)
+            .id();
+
+        let id = create_prefab(&mut app.world, &[root, child], file).unwrap();
+        let contents = std::fs::read_to_string(format!("assets/{file}")).unwrap();
+        std::fs::remove_file(format!("assets/{file}")).unwrap();
+
+        assert!(contents.contains("child"));
+        assert!(contents.contains("ChildrenPrefab"));
+        assert!(app.world.get_entity(root).is_none());
+        assert!(app.world.get_entity(child).is_none());
+        assert_eq!(
+            app.world.get::<PrefabLoader>(id).unwrap().path,
+            file.to_string()
+        );
+        assert_eq!(
+            app.world.get::<Transform>(id).unwrap().translation,
+            Vec3::new(1., 0., 0.)
+        );
+
+        // Undo restores replaced entities with their children
+        app.update();
+        app.update();
+        app.world.send_event(space_undo::UndoRedo::Undo);
+        app.update();
+        assert!(app.world.get_entity(id).is_none());
+        let mut query = app.world.query::<(Entity, &Name, Option<&Parent>)>();
+        let restored = query
+            .iter(&app.world)
+            .map(|(entity, name, parent)| {
+                (name.as_str().to_string(), (entity, parent.map(Parent::get)))
+            })
+            .collect::<bevy::utils::HashMap<_, _>>();
+        assert_eq!(restored["child"].1, Some(restored["root"].0));
+    }
 }
@@ -0,0 +1,240 @@
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+use space_shared::PrefabMarker;
+
+use crate::{
+    editor_registry::EditorRegistryExt,
+    load::{report_error, PrefabAutoChild},
+    prelude::SceneAutoChild,
+    save::{
+        add_scene_resources, build_prefab_scene_with_children, serialize_prefab,
+        write_prefab_formats, ChildrenPrefab, SaveConfig,
+    },
+    EditorState,
+};
+
+/// Root of scene file opened in editor. Top entities of the scene are its children,
+/// root itself is not written to scene file
+#[derive(Component, Reflect, Clone, Default, Debug)]
+#[reflect(Component, Default)]
+pub struct EditorScene {
+    /// Scene file relative to assets folder
+    pub path: String,
+}
+
+impl EditorScene {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Scene name shown in hierarchy
+    pub fn name(&self) -> String {
+        let file = self.path.rsplit('/').next().unwrap_or(&self.path);
+        file.trim_end_matches(".scn.ron").to_string()
+    }
+}
+
+/// Scene, which receives new entities
+#[derive(Resource, Default, Clone, Copy, Debug)]
+pub struct ActiveEditorScene(pub Option<Entity>);
+
+/// Event to save opened scenes to their files
+#[derive(Event, Clone, Copy, Debug)]
+pub enum SaveEditorScene {
+    Scene(Entity),
+    All,
+}
+
+/// Spawn empty scene root for scene file
+pub fn spawn_scene_root(world: &mut World, path: &str) -> Entity {
+    let scene = EditorScene::new(path);
+    let name = Name::new(scene.name());
+    world
+        .spawn((scene, name, PrefabMarker, SpatialBundle::default()))
+        .id()
+}
+
+/// All saved entities of scene, without scene root
+pub fn scene_entities(world: &World, root: Entity) -> Vec<Entity> {
+    let mut entities = vec![];
+    let mut stack = world
+        .get::<Children>(root)
+        .map(|children| children.to_vec())
+        .unwrap_or_default();
+    while let Some(e) = stack.pop() {
+        if world.get::<PrefabMarker>(e).is_some() && world.get::<SceneAutoChild>(e).is_none() {
+            entities.push(e);
+        }
+        if let Some(children) = world.get::<Children>(e) {
+            stack.extend(children.iter());
+        }
+    }
+    entities.sort();
+    entities
+}
+
+fn save_editor_scenes(world: &mut World) {
+    let events = world
+        .resource_mut::<Events<SaveEditorScene>>()
+        .drain()
+        .collect::<Vec<_>>();
+    if events.is_empty() {
+        return;
+    }
+    let mut roots = vec![];
+    for event in events {
+        match event {
+            SaveEditorScene::Scene(root) => roots.push(root),
+            SaveEditorScene::All => {
+                let mut query = world.query_filtered::<Entity, With<EditorScene>>();
+                roots.extend(query.iter(world));
+            }
+        }
+    }
+    roots.sort();
+    roots.dedup();
+
+    world.run_system_once(crate::overrides::record_prefab_overrides);
+    world.run_system_once(crate::guid::assign_prefab_guids);
+    for root in roots {
+        if let Err(err) = save_editor_scene(world, root) {
+            report_error(world, format!("Failed to save scene: {err}"));
+        }
+    }
+}
+
+fn save_editor_scene(world: &mut World, root: Entity) -> Result<(), String> {
+    let path = world
+        .get::<EditorScene>(root)
+        .ok_or_else(|| format!("{root:?} is not a scene"))?
+        .path
+        .clone();
+    if path.is_empty() {
+        return Err("scene has no file".to_string());
+    }
+    let entities = scene_entities(world, root);
+    let mut scene = build_prefab_scene_with_children(world, &entities);
+    // Level settings are stored in active scene
+    if world.resource::<ActiveEditorScene>().0 == Some(root) {
+        add_scene_resources(world, &mut scene);
+    }
+    let data = serialize_prefab(world, &scene)?;
+    let format = world.resource::<SaveConfig>().format;
+    write_prefab_formats(world, &scene, data, format!("assets/{path}"), format);
+    Ok(())
+}
+
+/// Keep active scene pointing to existing scene root
+fn update_active_scene(
+    mut active: ResMut<ActiveEditorScene>,
+    roots: Query<Entity, With<EditorScene>>,
+) {
+    if active.0.is_some_and(|root| roots.contains(root)) {
+        return;
+    }
+    let first = roots.iter().min();
+    if active.0 != first {
+        active.0 = first;
+    }
+}
+
+/// New top entities are placed into active scene
+fn add_to_active_scene(
+    mut commands: Commands,
+    active: Res<ActiveEditorScene>,
+    added: Query<
+        Entity,
+        (
+            Added<PrefabMarker>,
+            Without<Parent>,
+            Without<EditorScene>,
+            Without<PrefabAutoChild>,
+            Without<SceneAutoChild>,
+        ),
+    >,
+    pending_children: Query<&ChildrenPrefab>,
+) {
+    let Some(root) = active.0 else {
+        return;
+    };
+    for entity in added.iter() {
+        // Loaded child, which will be attached to its parent
+        if pending_children
+            .iter()
+            .any(|children| children.0.contains(&entity))
+        {
+            continue;
+        }
+        commands.entity(root).add_child(entity);
+    }
+}
+
+/// Plugin for editing several scene files at once
+pub struct EditorScenesPlugin;
+
+impl Plugin for EditorScenesPlugin {
+    fn build(&self, app: &mut App) {
+        app.editor_silent_registry::<EditorScene>();
+        app.editor_relation::<EditorScene, Transform>();
+        app.editor_relation::<EditorScene, Visibility>();
+        app.init_resource::<ActiveEditorScene>()
+            .add_event::<SaveEditorScene>();
+        app.add_systems(
+            Update,
+            (
+                update_active_scene,
+                add_to_active_scene.after(crate::load::auto_children),
+                save_editor_scenes,
+            )
+                .chain()
+                .run_if(in_state(EditorState::Editor)),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scene_contains_only_its_prefab_entities() {
+        let mut world = World::new();
+        let first = spawn_scene_root(&mut world, "scenes/first.scn.ron");
+        let second = spawn_scene_root(&mut world, "scenes/second.scn.ron");
+        let child = world.spawn(PrefabMarker).id();
+        let auto_child = world.spawn((PrefabMarker, SceneAutoChild)).id();
+        let top = world
+            .spawn(PrefabMarker)
+            .push_children(&[child, auto_child])
+            .id();
+        world.entity_mut(first).add_child(top);
+        let other = world.spawn(PrefabMarker).set_parent(second).id();
+
+        assert_eq!(scene_entities(&world, first), vec![child, top]);
+        assert_eq!(scene_entities(&world, second), vec![other]);
+        assert_eq!(world.get::<Name>(first).map(Name::as_str), Some("first"));
+    }
+
+    #[test]
+    fn new_entities_are_added_to_active_scene() {
+        let mut world = World::new();
+        world.init_resource::<ActiveEditorScene>();
+        world.run_system_once(update_active_scene);
+        assert!(world.resource::<ActiveEditorScene>().0.is_none());
+
+        let root = spawn_scene_root(&mut world, "scenes/level.scn.ron");
+        world.run_system_once(update_active_scene);
+        assert_eq!(world.resource::<ActiveEditorScene>().0, Some(root));
+
+        let loaded_child = world.spawn(PrefabMarker).id();
+        let entity = world
+            .spawn((PrefabMarker, ChildrenPrefab(vec![loaded_child])))
+            .id();
+        world.run_system_once(add_to_active_scene);
+        assert_eq!(world.get::<Parent>(entity).map(Parent::get), Some(root));
+        assert!(world.get::<Parent>(loaded_child).is_none());
+
+        world.entity_mut(root).despawn_recursive();
+        world.run_system_once(update_active_scene);
+        assert!(world.resource::<ActiveEditorScene>().0.is_none());
+    }
+}
@@ -240,7 +240,7 @@ pub fn spawn_player_start(
         info!(msg);
         let child = commands
             .spawn(DynamicSceneBundle {
-                scene: asset_server.load(prefab.prefab.to_string()),
+                scene: asset_server.load(prefab.prefab.path.clone()),
                 ..default()
             })
             .id();
@@ -394,7 +394,7 @@ mod tests {
         ))
         .add_systems(Startup, |mut commands: Commands| {
             commands.spawn(SpriteTexture {
-                texture: String::from("test_asset.png"),
+                texture: AssetPathField::new("test_asset.png"),
             });
         })
         .add_systems(Update, sync_sprite_texture)
@@ -419,7 +419,7 @@ mod tests {
         .add_event::<ToastMessage>();
         app.add_systems(Startup, |mut commands: Commands| {
             commands.spawn(PlayerStart {
-                prefab: String::from("cube.glb#Scene0"),
+                prefab: AssetPathField::new("cube.glb#Scene0"),
             });
         })
         .add_systems(Update, spawn_player_start);
@@ -499,7 +499,7 @@ mod tests {
         app.add_systems(Startup, |mut commands: Commands| {
             commands.spawn((
                 SpritesheetTexture {
-                    texture: String::from("gabe-idle-run.png"),
+                    texture: AssetPathField::new("gabe-idle-run.png"),
                 },
                 AnimationIndicesSpriteSheet::default(),
                 TextureAtlasPrefab::default(),
@@ -530,7 +530,7 @@ mod tests {
             let child = commands.spawn((SceneAutoChild, DespawnTestChild)).id();
             commands
                 .spawn(GltfPrefab {
-                    path: String::from("low_poly_fighter_2.gltf"),
+                    path: AssetPathField::new("low_poly_fighter_2.gltf"),
                     scene: String::from("Scene0"),
                 })
                 .add_child(child);
@@ -565,7 +565,7 @@ mod tests {
         app.add_systems(Startup, |mut commands: Commands| {
             commands.spawn((
                 GltfPrefab {
-                    path: String::from("low_poly_fighter_2.gltf"),
+                    path: AssetPathField::new("low_poly_fighter_2.gltf"),
                     scene: String::from("Scene0"),
                 },
                 Visibility::Hidden,
@@ -65,8 +65,12 @@ pub enum EditorPrefabPath {
 #[derive(Event)]
 pub enum EditorEvent {
     Load(EditorPrefabPath),
+    /// Load scene file next to already opened scenes
+    LoadAdditive(String),
     Save(EditorPrefabPath),
     LoadGltfAsPrefab(String),
+    /// Update entities unpacked from gltf file with changes of the file
+    ReimportGltf(String),
     StartGame,
 }
 
@@ -5,7 +5,7 @@ mod tests;
 
 use std::sync::Arc;
 
-use bevy::{prelude::*, utils::HashMap};
+use bevy::{ecs::entity::EntityHashMap, prelude::*, utils::HashMap};
 
 const MAX_REFLECT_RECURSION: i32 = 10;
 const AUTO_UNDO_LATENCY: i32 = 2;
@@ -328,6 +328,81 @@ impl EditorChange for RemovedEntity {
     }
 }
 
+/// Entity removed with all its descendants. Revert writes back snapshot of the whole hierarchy,
+/// which must be taken with [`RemovedHierarchy::new`] before despawn
+pub struct RemovedHierarchy {
+    pub entity: Entity,
+    parent: Option<Entity>,
+    scene: Arc<DynamicScene>,
+}
+
+impl RemovedHierarchy {
+    pub fn new(world: &World, entity: Entity) -> Self {
+        let mut entities = vec![];
+        let mut stack = vec![entity];
+        while let Some(e) = stack.pop() {
+            entities.push(e);
+            if let Some(children) = world.get::<Children>(e) {
+                stack.extend(children.iter());
+            }
+        }
+        let mut scene = DynamicSceneBuilder::from_world(world)
+            .extract_entities(entities.into_iter())
+            .build();
+        // Root is attached to its parent again on revert
+        for dyn_entity in scene.entities.iter_mut() {
+            if dyn_entity.entity == entity {
+                dyn_entity
+                    .components
+                    .retain(|component| !component.represents::<Parent>());
+            }
+        }
+        Self {
+            entity,
+            parent: world.get::<Parent>(entity).map(Parent::get),
+            scene: Arc::new(scene),
+        }
+    }
+}
+
+impl EditorChange for RemovedHierarchy {
+    fn revert(
+        &self,
+        world: &mut World,
+        remap: &HashMap<Entity, Entity>,
+    ) -> Result<ChangeResult, String> {
+        let mut entity_map = EntityHashMap::default();
+        self.scene
+            .write_to_world(world, &mut entity_map)
+            .map_err(|e| e.to_string())?;
+        for id in entity_map.values() {
+            world
+                .entity_mut(*id)
+                .insert((UndoMarker, OneFrameUndoIgnore::default()));
+        }
+        if let (Some(parent), Some(id)) = (self.parent, entity_map.get(&self.entity)) {
+            let parent = get_entity_with_remap(parent, remap);
+            if world.get_entity(parent).is_some() {
+                world.entity_mut(parent).add_child(*id);
+            }
+        }
+        info!("Reverted Removed Hierarchy: {}", self.entity.index());
+        Ok(ChangeResult::SuccessWithRemap(
+            entity_map.into_iter().collect(),
+        ))
+    }
+
+    fn debug_text(&self) -> String {
+        format!("Removed Hierarchy: {}", self.entity.index())
+    }
+
+    fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
+        Arc::new(AddedEntity {
+            entity: self.entity,
+        })
+    }
+}
+
 pub struct ComponentChange<T: Component> {
     old_value: T,
     new_value: T,
@@ -129,6 +129,39 @@ fn test_reflected_undo() {
     assert!(app.world.get_entity(test_id).is_none());
 }
 
+#[test]
+fn test_reflected_undo_many_entities() {
+    let mut app = configure_app();
+    app.auto_reflected_undo::<Transform>();
+    app.update();
+
+    let entities = [
+        app.world.spawn((Transform::default(), UndoMarker)).id(),
+        app.world.spawn((Transform::default(), UndoMarker)).id(),
+    ];
+    repeat_update(&mut app, 10);
+    let changes = app.world.resource::<ChangeChain>().changes.len();
+
+    // Edit of several selected entities is done at one frame
+    for entity in entities {
+        app.world.get_mut::<Transform>(entity).unwrap().translation = Vec3::Y;
+    }
+    repeat_update(&mut app, 10);
+    assert_eq!(
+        app.world.resource::<ChangeChain>().changes.len(),
+        changes + 1
+    );
+
+    app.world.send_event(UndoRedo::Undo);
+    repeat_update(&mut app, 2);
+    for entity in entities {
+        assert_eq!(
+            app.world.get::<Transform>(entity).unwrap().translation,
+            Vec3::ZERO
+        );
+    }
+}
+
 #[test]
 fn test_reflected_redo() {
     let mut app = configure_app();
@@ -244,6 +277,47 @@ fn test_undo_with_remap() {
     assert!(query.get_single(&app.world).is_ok());
 }
 
+#[test]
+fn undo_removed_hierarchy() {
+    let mut app = configure_app();
+    app.register_type::<Name>()
+        .register_type::<Parent>()
+        .register_type::<Children>();
+
+    let parent = app.world.spawn_empty().id();
+    let root = app.world.spawn(Name::new("root")).set_parent(parent).id();
+    app.world.spawn(Name::new("child")).set_parent(root);
+    repeat_update(&mut app, 2);
+
+    let change = RemovedHierarchy::new(&app.world, root);
+    app.world.entity_mut(root).despawn_recursive();
+    app.world.send_event(NewChange {
+        change: Arc::new(change),
+    });
+    repeat_update(&mut app, 2);
+
+    app.world.send_event(UndoRedo::Undo);
+    repeat_update(&mut app, 2);
+
+    let mut query = app.world.query::<(Entity, &Name, &Parent)>();
+    let restored = query
+        .iter(&app.world)
+        .map(|(entity, name, parent)| (name.as_str().to_string(), (entity, parent.get())))
+        .collect::<HashMap<_, _>>();
+    let (new_root, root_parent) = restored["root"];
+    assert_eq!(root_parent, parent);
+    assert_eq!(restored["child"].1, new_root);
+    assert_eq!(
+        app.world.get::<Children>(parent).unwrap().to_vec(),
+        vec![new_root]
+    );
+
+    // Redo removes restored hierarchy again
+    app.world.send_event(UndoRedo::Redo);
+    repeat_update(&mut app, 2);
+    assert!(app.world.query::<&Name>().iter(&app.world).next().is_none());
+}
+
 #[test]
 fn clear_one_frame_ignores() {
     let spawn = |mut commands: Commands| {
@@ -14,36 +14,7 @@ pub struct BevyRapierPlugin;
 
 impl Plugin for BevyRapierPlugin {
     fn build(&self, app: &mut App) {
-        // colliders
-        app
-            .editor_registry::<ColliderPrefab>()
-            .editor_registry::<RigidBodyPrebuf>()
-            .editor_registry::<ColliderMassProperties>()
-            .editor_registry::<FrictionPrebuf>()
-            .editor_registry::<RestitutionPrebuf>()
-            .editor_registry::<Velocity>()
-            .editor_registry::<Damping>()
-            .editor_registry::<Dominance>()
-            .editor_registry::<ExternalForce>()
-            .editor_registry::<ExternalImpulse>()
-            .editor_registry::<LockedAxes>()
-            .editor_registry::<GravityScale>()
-            .editor_registry::<Sensor>()
-            .editor_registry::<Sleeping>()
-            .editor_registry::<Ccd>()
-            // TODO: add enums for this types
-            // .editor_registry::<ActiveCollisionTypes>()
-            // .editor_registry::<ActiveHooks>()
-            // .editor_registry::<ActiveEvents>()
-            // .editor_registry::<Group>()
-            .editor_silent_registry::<CoefficientCombineRulePrebuf>();
-
-        app
-            .editor_registry::<JointBodyBuilderPrefab>()
-            .editor_relation::<JointBodyBuilderPrefab, JointBodyBuilderHashMap>();
-
-        app.register_type::<JointBodyBuilderHashMap>();
-        app.register_type::<LocalAnchorEntityStorage>();
+        register_rapier_types(app);
 
         app.add_systems(Update, (
                 load_map,
@@ -61,21 +32,6 @@ impl Plugin for BevyRapierPlugin {
 
         app.add_systems(Update, (sync_local_anchor1, sync_local_anchor2, sync_joint));
 
-        app
-            .editor_registry::<ImpulseJointPrefab>()
-            .editor_silent_registry::<GenericJointPrefab>()
-            .editor_silent_registry::<MotorModelPrebuf>()
-            .editor_silent_registry::<MotorPositionPrebuf>()
-            .editor_silent_registry::<MotorVelocityPrebuf>()   
-            .editor_silent_registry::<MotorPrebuf>()
-            .editor_silent_registry::<LimitsPrebuf>()
-            .editor_silent_registry::<AdvancedSettings>()
-            .register_type::<List<AdvancedSettings>>()
-            .editor_silent_registry::<crate::joint::generic_joint::Axis>()
-            .editor_silent_registry::<crate::joint::generic_joint::JointAxisPrefab>()
-            .editor_silent_registry::<SphericalSettings>()
-            .register_type::<List<SphericalSettings>>();
-
         app.add_systems(Update, (
             detect_removals::<ColliderPrefab>, 
             detect_removals::<RigidBodyPrebuf>,
@@ -84,6 +40,56 @@ impl Plugin for BevyRapierPlugin {
     }
 }
 
+/// Register prefab components of rapier module without physics systems.
+/// Used by headless prefab tools to load saved physics components
+pub fn register_rapier_types(app: &mut App) {
+    // colliders
+    app
+        .editor_registry::<ColliderPrefab>()
+        .editor_registry::<RigidBodyPrebuf>()
+        .editor_registry::<ColliderMassProperties>()
+        .editor_registry::<FrictionPrebuf>()
+        .editor_registry::<RestitutionPrebuf>()
+        .editor_registry::<Velocity>()
+        .editor_registry::<Damping>()
+        .editor_registry::<Dominance>()
+        .editor_registry::<ExternalForce>()
+        .editor_registry::<ExternalImpulse>()
+        .editor_registry::<LockedAxes>()
+        .editor_registry::<GravityScale>()
+        .editor_registry::<Sensor>()
+        .editor_registry::<Sleeping>()
+        .editor_registry::<Ccd>()
+        // TODO: add enums for this types
+        // .editor_registry::<ActiveCollisionTypes>()
+        // .editor_registry::<ActiveHooks>()
+        // .editor_registry::<ActiveEvents>()
+        // .editor_registry::<Group>()
+        .editor_silent_registry::<CoefficientCombineRulePrebuf>();
+
+    app
+        .editor_registry::<JointBodyBuilderPrefab>()
+        .editor_relation::<JointBodyBuilderPrefab, JointBodyBuilderHashMap>();
+
+    app.register_type::<JointBodyBuilderHashMap>();
+    app.register_type::<LocalAnchorEntityStorage>();
+
+    app
+        .editor_registry::<ImpulseJointPrefab>()
+        .editor_silent_registry::<GenericJointPrefab>()
+        .editor_silent_registry::<MotorModelPrebuf>()
+        .editor_silent_registry::<MotorPositionPrebuf>()
+        .editor_silent_registry::<MotorVelocityPrebuf>()
+        .editor_silent_registry::<MotorPrebuf>()
+        .editor_silent_registry::<LimitsPrebuf>()
+        .editor_silent_registry::<AdvancedSettings>()
+        .register_type::<List<AdvancedSettings>>()
+        .editor_silent_registry::<crate::joint::generic_joint::Axis>()
+        .editor_silent_registry::<crate::joint::generic_joint::JointAxisPrefab>()
+        .editor_silent_registry::<SphericalSettings>()
+        .register_type::<List<SphericalSettings>>();
+}
+
 fn detect_removals<P: PrefabMarkerComponent>(
     mut commands: Commands,
     mut removals: RemovedComponents<P>,
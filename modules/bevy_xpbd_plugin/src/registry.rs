@@ -22,25 +22,7 @@ impl Plugin for BevyXpbdPlugin {
         app.add_plugins(PhysicsPlugins::default());
         app.add_plugins(bevy_xpbd_3d::plugins::PhysicsDebugPlugin::default());
 
-        app.editor_registry::<collider::ColliderPrefab>()
-            .editor_registry::<RigidBodyPrefab>()
-            .editor_registry::<Mass>()
-            .editor_registry::<Friction>()
-            .editor_registry::<Restitution>()
-            .editor_registry::<LinearDamping>()
-            .editor_registry::<AngularDamping>()
-            .editor_registry::<Inertia>()
-            .editor_registry::<CenterOfMass>()
-            .editor_registry::<LockedAxes>()
-            .editor_registry::<GravityScale>()
-            .editor_registry::<Sensor>();
-
-        app.register_type::<ColliderPrimitive>()
-            .register_type::<ColliderPart>()
-            .register_type::<Vec<ColliderPart>>()
-            .register_type::<ColliderPrefabCompound>();
-
-        register_xpbd_spatial_types(app);
+        register_xpbd_types(app);
 
         app.add_systems(
             Update,
@@ -84,6 +66,35 @@ impl Plugin for BevyXpbdPlugin {
     }
 }
 
+/// Register prefab components of bevy_xpbd_3d module without physics systems.
+/// Used by headless prefab tools to load saved physics components
+pub fn register_xpbd_types(app: &mut App) {
+    app.editor_registry::<collider::ColliderPrefab>()
+        .editor_registry::<RigidBodyPrefab>()
+        .editor_registry::<Mass>()
+        .editor_registry::<Friction>()
+        .editor_registry::<Restitution>()
+        .editor_registry::<LinearDamping>()
+        .editor_registry::<AngularDamping>()
+        .editor_registry::<Inertia>()
+        .editor_registry::<CenterOfMass>()
+        .editor_registry::<LockedAxes>()
+        .editor_registry::<GravityScale>()
+        .editor_registry::<Sensor>();
+
+    app.register_type::<ColliderPrimitive>()
+        .register_type::<ColliderPart>()
+        .register_type::<Vec<ColliderPart>>()
+        .register_type::<ColliderPrefabCompound>();
+
+    app.register_type::<Option<Vec3>>();
+    app.register_type::<Option<Color>>();
+    app.register_type::<Option<[f32; 4]>>();
+    app.register_type::<[f32; 4]>();
+
+    register_xpbd_spatial_types(app);
+}
+
 #[derive(Component)]
 struct LateSync;
 
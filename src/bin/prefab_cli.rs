@@ -0,0 +1,24 @@
+//! Headless prefab tool for CI
+//!
+//! cargo run --bin prefab_cli -- validate [dir]
+//! cargo run --bin prefab_cli -- convert <input> <output>
+//! cargo run --bin prefab_cli -- diff <old> <new>
+//!
+//! Knows the same types as editor with enabled physics features.
+//! Game with own components can run the same tool with [`space_prefab::cli::run_cli`]
+
+use std::process::ExitCode;
+
+use space_prefab::cli::{cli_app, run_cli};
+
+fn main() -> ExitCode {
+    let mut app = cli_app();
+
+    #[cfg(feature = "bevy_xpbd_3d")]
+    space_bevy_xpbd_plugin::registry::register_xpbd_types(&mut app);
+    #[cfg(feature = "bevy_rapier3d_plugin")]
+    #[cfg(not(feature = "bevy_xpbd_3d"))]
+    space_bevy_rapier3d_plugin::registry::register_rapier_types(&mut app);
+
+    run_cli(&mut app)
+}